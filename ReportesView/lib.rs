@@ -10,7 +10,10 @@ mod ReportesView {
         SistemaRef,
         Usuario,
         ErrorSistema,
-        Categoria
+        Categoria,
+        Storefront,
+        EstadisticaCategoria,
+        VERSION_API
     };
 
     #[ink(storage)]
@@ -20,8 +23,8 @@ mod ReportesView {
 
     impl ReportesView {
 
-        // Instancia el marketplace y lo devuelve contenido en una estructura
-        // para referenciarlo
+        // Referencia al marketplace ya desplegado en `marketplace_account_id`, así los reportes
+        // se calculan sobre sus datos reales. No instancia un marketplace nuevo.
         #[ink(constructor)]
         pub fn new(marketplace_account_id: AccountId) -> Self {
             let marketplace = SistemaRef::from_account_id(marketplace_account_id);
@@ -49,15 +52,54 @@ mod ReportesView {
             self.marketplace.estadisticas_por_categoria()
         }
 
+        /// Devuelve las estadísticas de cada categoría como `EstadisticaCategoria`: unidades vendidas,
+        /// órdenes recibidas, ingreso total y promedio de calificación.
+        #[ink(message)]
+        pub fn get_estadisticas_por_categoria_detallado(&self) -> Result<Vec<EstadisticaCategoria>, ErrorSistema> {
+            self.marketplace.estadisticas_por_categoria_detallado()
+        }
+
         /// Devuelve una lista de hasta 10 productos de una categoría específica ordenados por ventas en forma descendente.
         /// Solo accesible si se ha establecido ReportesView.
         ///
         /// # Parámetros
         /// - `categoria`: Categoría de los productos a buscar.
         ///
+        /// Retorna `VersionIncompatible` si el marketplace usa otra `VERSION_API`: la verifica el propio
+        /// marketplace con la versión que se le pasa, sin una llamada extra a `version()`.
         #[ink(message)]
         pub fn get_productos_mas_vendidos(&self, categoria: Categoria) -> Result<Vec<(u128, u8)>, ErrorSistema> {
-            self.marketplace.ver_productos_mas_vendidos(categoria)
+            self.marketplace.ver_productos_mas_vendidos(categoria.codigo(), VERSION_API)
+        }
+
+        /// Devuelve el resumen de la tienda de un vendedor: nombre, calificación, publicaciones activas y ventas.
+        #[ink(message)]
+        pub fn get_storefront(&self, vendedor: AccountId) -> Result<Storefront, ErrorSistema> {
+            self.marketplace.storefront(vendedor)
+        }
+
+        /// Devuelve las métricas generales del marketplace como
+        /// (total_usuarios, total_publicaciones_activas, total_ordenes, total_ordenes_recibidas).
+        /// Es de sólo lectura, así que puede consultarse con un dry-run sin enviar una transacción.
+        #[ink(message)]
+        pub fn get_metricas(&self) -> (u128, u128, u128, u128) {
+            self.marketplace.metricas_generales()
+        }
+
+        /// Devuelve la `VERSION_API` con la que se compiló ReportesView.
+        #[ink(message)]
+        pub fn version(&self) -> u32 {
+            VERSION_API
+        }
+
+        /// Compara la versión de ReportesView con la del marketplace.
+        /// Retorna `VersionIncompatible` si no coinciden.
+        #[ink(message)]
+        pub fn verificar_version(&self) -> Result<(), ErrorSistema> {
+            if self.marketplace.version() != VERSION_API {
+                return Err(ErrorSistema::VersionIncompatible);
+            }
+            Ok(())
         }
 
         /// Devuelve un vector con la cantidad de órdenes de todos los usuarios registrados.
@@ -67,4 +109,101 @@ mod ReportesView {
         }
 
     }
+
+    #[cfg(all(test, feature = "e2e-tests"))]
+    mod e2e_tests {
+        use super::*;
+        use ink_e2e::ContractsBackend;
+        use MarketPlace::{Rol, Sistema};
+
+        type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+        //Test que verifica que ReportesView lea los datos de un marketplace desplegado antes que él.
+        #[ink_e2e::test]
+        async fn test_reportes_sobre_marketplace_existente<Client: E2EBackend>(mut client: Client) -> E2EResult<()> {
+            //Despliego el marketplace y registro un vendedor antes de que exista ReportesView.
+            let mut constructor = SistemaRef::new();
+            let marketplace = client
+                .instantiate("MarketPlace", &ink_e2e::alice(), &mut constructor)
+                .submit()
+                .await
+                .expect("falló la instanciación del marketplace");
+            let mut sistema = marketplace.call_builder::<Sistema>();
+
+            let registrar = sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Vendedor);
+            client.call(&ink_e2e::bob(), &registrar).submit().await.expect("falló el registro");
+
+            //ReportesView apunta al marketplace existente.
+            let mut constructor = ReportesViewRef::new(marketplace.account_id);
+            let reportes = client
+                .instantiate("ReportesView", &ink_e2e::alice(), &mut constructor)
+                .submit()
+                .await
+                .expect("falló la instanciación de ReportesView");
+
+            let set_reportes_view = sistema.set_reportes_view(reportes.account_id);
+            client.call(&ink_e2e::alice(), &set_reportes_view).submit().await.expect("falló set_reportes_view");
+
+            let vista = reportes.call_builder::<ReportesView>();
+            let top5 = client.call(&ink_e2e::alice(), &vista.get_top5_vendedores()).dry_run().await?;
+            assert_eq!(top5.return_value().expect("no se pudo leer el top 5").len(), 1);
+
+            //Los dos contratos se compilaron con la misma versión.
+            let version = client.call(&ink_e2e::alice(), &vista.version()).dry_run().await?;
+            assert_eq!(version.return_value(), VERSION_API);
+            let verificacion = client.call(&ink_e2e::alice(), &vista.verificar_version()).dry_run().await?;
+            assert_eq!(verificacion.return_value(), Ok(()));
+
+            Ok(())
+        }
+
+        //Test que verifica las estadísticas detalladas por categoría sobre un marketplace con una venta conocida.
+        #[ink_e2e::test]
+        async fn test_estadisticas_por_categoria_detallado<Client: E2EBackend>(mut client: Client) -> E2EResult<()> {
+            let mut constructor = SistemaRef::new();
+            let marketplace = client
+                .instantiate("MarketPlace", &ink_e2e::alice(), &mut constructor)
+                .submit()
+                .await
+                .expect("falló la instanciación del marketplace");
+            let mut sistema = marketplace.call_builder::<Sistema>();
+
+            //Charlie vende una remera a 10 y Bob compra 3 unidades.
+            let registrar = sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor);
+            client.call(&ink_e2e::charlie(), &registrar).submit().await.expect("falló el registro del vendedor");
+            let producto = sistema.nuevo_producto(String::from("Remera"), String::from("Negra"), Categoria::Ropa, None);
+            client.call(&ink_e2e::charlie(), &producto).submit().await.expect("falló nuevo_producto");
+            let publicacion = sistema.crear_publicacion(0, 10, 5);
+            client.call(&ink_e2e::charlie(), &publicacion).submit().await.expect("falló crear_publicacion");
+
+            let registrar = sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador);
+            client.call(&ink_e2e::bob(), &registrar).submit().await.expect("falló el registro del comprador");
+            let orden = sistema.generar_orden_compra(ink::prelude::vec![(0, 3)], 1000, Some(String::from("Calle 1 123")));
+            client.call(&ink_e2e::bob(), &orden).submit().await.expect("falló generar_orden_compra");
+            let enviar = sistema.marcar_orden_como_enviada(0);
+            client.call(&ink_e2e::charlie(), &enviar).submit().await.expect("falló el envío");
+            let recibir = sistema.marcar_orden_como_recibida(0);
+            client.call(&ink_e2e::bob(), &recibir).submit().await.expect("falló la recepción");
+
+            let mut constructor = ReportesViewRef::new(marketplace.account_id);
+            let reportes = client
+                .instantiate("ReportesView", &ink_e2e::alice(), &mut constructor)
+                .submit()
+                .await
+                .expect("falló la instanciación de ReportesView");
+            let set_reportes_view = sistema.set_reportes_view(reportes.account_id);
+            client.call(&ink_e2e::alice(), &set_reportes_view).submit().await.expect("falló set_reportes_view");
+
+            let vista = reportes.call_builder::<ReportesView>();
+            let resultado = client.call(&ink_e2e::alice(), &vista.get_estadisticas_por_categoria_detallado()).dry_run().await?;
+            let estadisticas = resultado.return_value().expect("no se pudieron leer las estadísticas");
+            assert_eq!(estadisticas.len(), 1);
+            assert_eq!(estadisticas[0].categoria, Categoria::Ropa);
+            assert_eq!(estadisticas[0].ordenes, 1);
+            assert_eq!(estadisticas[0].ingreso_total, 30);
+            assert_eq!(estadisticas[0].prom_calificacion, None);
+
+            Ok(())
+        }
+    }
 }
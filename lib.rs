@@ -4,8 +4,12 @@ pub use self::usuarios_sistema::{
     Sistema,
     SistemaRef,
     Usuario,
+    Rol,
     ErrorSistema,
-    Categoria
+    Categoria,
+    Storefront,
+    EstadisticaCategoria,
+    VERSION_API
 };
 
 #[ink::contract]
@@ -28,24 +32,137 @@ mod usuarios_sistema {
     /// - `usuarios`: Mapeo de AccountId a struct Usuario, representa todos los usuarios registrados.
     /// - `publicaciones`: Vector con todas las publicaciones activas en el sistema.
     /// - `productos`: Mapeo de id de producto a struct Producto, representa todos los productos creados.
-    /// - `ordenes`: Vector con todas las órdenes de compra generadas.
+    /// - `ordenes`: Mapeo de id de orden a struct OrdenCompra, representa todas las órdenes de compra generadas.
+    /// - `id_ordenes`: Vector con los ids de las órdenes, para poder recorrerlas.
+    /// - `ordenes_archivadas`: Mapeo de id de orden a las órdenes finalizadas que se archivaron.
     /// - `proximo_id_publicacion`: Contador para el próximo id único de publicación.
     /// - `proximo_id_producto`: Contador para el próximo id único de producto.
     /// - `proximo_id_orden`: Contador para el próximo id único de orden de compra.
     /// - `owner`: La cuenta del propietario del contrato.
     /// - `reportes_view`: La cuenta de ReportesView.
+    /// - `escrow_maximo`: Monto máximo que pueden sumar las órdenes en curso (sin límite si es `None`).
+    /// - `comision_bps`: Comisión del marketplace en puntos básicos (1/100 de porcentaje) que se retiene al completar una orden.
+    /// - `comisiones_acumuladas`: Total de comisiones retenidas que el owner todavía no liquidó.
+    /// - `comisiones_por_categoria`: Comisión en puntos básicos que reemplaza a `comision_bps` para las órdenes de una categoría.
+    /// - `carritos`: Mapeo de AccountId al carrito del comprador, como tuplas (id_publicacion, cantidad).
+    /// - `resenas`: Mapeo de id de producto a las reseñas que recibió.
+    /// - `reservas`: Mapeo de id de publicación a sus reservas de stock, como tuplas (comprador, cantidad, expira_en).
+    /// - `bloqueados`: Mapeo de AccountId de un vendedor a los compradores que no le pueden comprar.
+    /// - `montos_minimos`: Mapeo de AccountId de un vendedor al monto mínimo que tiene que alcanzar una orden suya.
+    /// - `max_publicaciones_por_vendedor`: Cantidad máxima de publicaciones activas que puede tener un vendedor.
+    /// - `direccion_exenta`: Categorías cuyas órdenes no necesitan indicar una dirección de envío. Las demás la exigen.
     ///
     pub struct Sistema {
         usuarios: ink::storage::Mapping<AccountId, Usuario>,
         id_usuarios: Vec<AccountId>,
         publicaciones: Vec<Publicacion>,
         productos: Mapping<u128, Producto>,
-        ordenes: Vec<OrdenCompra>,
+        ordenes: Mapping<u128, OrdenCompra>,
+        id_ordenes: Vec<u128>,
+        ordenes_archivadas: Mapping<u128, OrdenCompra>,
         proximo_id_publicacion: u128,
         proximo_id_producto: u128,
         proximo_id_orden: u128,
         owner: AccountId,
         reportes_view: Option<AccountId>,
+        escrow_maximo: Option<u128>,
+        comision_bps: u16,
+        comisiones_acumuladas: u128,
+        comisiones_por_categoria: Mapping<Categoria, u16>,
+        carritos: Mapping<AccountId, Carrito>,
+        resenas: Mapping<u128, Vec<ResenaProducto>>,
+        reservas: Mapping<u128, Reservas>,
+        bloqueados: Mapping<AccountId, Vec<AccountId>>,
+        montos_minimos: Mapping<AccountId, u32>,
+        max_publicaciones_por_vendedor: u32,
+        direccion_exenta: Mapping<Categoria, bool>,
+    }
+
+    /// Largo máximo (en bytes) del texto de una reseña.
+    const MAX_LARGO_RESENA: usize = 280;
+
+    /// Plazo (en milisegundos) que tiene el vendedor para enviar una orden pendiente antes de que expire: 7 días.
+    const PLAZO_EXPIRACION_ORDEN: u64 = 7 * 24 * 60 * 60 * 1000;
+
+    /// Plazo (en milisegundos) que dura una reserva de stock antes de liberarse: 15 minutos.
+    const PLAZO_RESERVA: u64 = 15 * 60 * 1000;
+
+    /// Cantidad máxima de líneas (publicaciones distintas) que puede tener una orden de compra,
+    /// para acotar el gas de su procesamiento y el tamaño de `OrdenCompra`.
+    const MAX_ITEMS_POR_ORDEN: usize = 20;
+
+    /// Cantidad máxima de unidades que se pueden comprar de una misma publicación en una orden.
+    const MAX_CANTIDAD_POR_LINEA: u32 = 1000;
+
+    /// Cantidad máxima de elementos que devuelve una consulta paginada.
+    const MAX_TAMANO_PAGINA: u32 = 50;
+
+    /// Cantidad máxima de ids que se resuelven en una consulta por lote, para acotar su costo.
+    const MAX_IDS_POR_CONSULTA: usize = 50;
+
+    /// Largo máximo (en bytes) de la subcategoría libre de un producto.
+    const MAX_LARGO_SUBCATEGORIA: usize = 50;
+
+    /// Largo máximo (en bytes) de la dirección de envío de una orden.
+    const MAX_LARGO_DIRECCION: usize = 200;
+
+    /// Puntos básicos equivalentes al 100%: tope de comisiones y descuentos. Un valor mayor se rechaza, no se recorta.
+    const MAX_BPS: u16 = 10000;
+
+    /// Cantidad máxima de precios anteriores que guarda una publicación; se descartan los más viejos.
+    const MAX_HISTORIAL_PRECIOS: usize = 20;
+
+    /// Antigüedad (en milisegundos) a partir de la cual una calificación pesa lo mínimo en el promedio ponderado: 90 días.
+    const VENTANA_REPUTACION: u64 = 90 * 24 * 60 * 60 * 1000;
+
+    /// Peso de una calificación recién hecha en el promedio ponderado; una de más de `VENTANA_REPUTACION` pesa 1.
+    const PESO_MAXIMO_REPUTACION: u64 = 10;
+
+    /// Versión de la interfaz entre Sistema y ReportesView. Cambia cuando se modifica algo que ambos
+    /// contratos tienen que interpretar igual, como las variantes de `Categoria`.
+    pub const VERSION_API: u32 = 1;
+
+    /// Lista de tuplas (id_publicacion, cantidad) que arma un comprador antes de generar la orden.
+    pub type Carrito = Vec<(u128, u32)>;
+
+    /// Lista de tuplas (comprador, cantidad, expira_en) con las reservas vigentes de una publicación.
+    pub type Reservas = Vec<(AccountId, u32, u64)>;
+
+    /// Suma dos `u128` y retorna `Overflow` si el resultado no entra.
+    fn suma_segura_u128(a: u128, b: u128) -> Result<u128, ErrorSistema> {
+        a.checked_add(b).ok_or(ErrorSistema::Overflow)
+    }
+
+    /// Suma dos `u32` y retorna `Overflow` si el resultado no entra.
+    fn suma_segura_u32(a: u32, b: u32) -> Result<u32, ErrorSistema> {
+        a.checked_add(b).ok_or(ErrorSistema::Overflow)
+    }
+
+    /// Multiplica dos `u32` y retorna `Overflow` si el resultado no entra.
+    fn mul_segura_u32(a: u32, b: u32) -> Result<u32, ErrorSistema> {
+        a.checked_mul(b).ok_or(ErrorSistema::Overflow)
+    }
+
+    /// Evento emitido cuando una compra deja a una publicación sin stock.
+    #[ink(event)]
+    pub struct SinStock {
+        #[ink(topic)]
+        id_publicacion: u128,
+        #[ink(topic)]
+        publicador: AccountId,
+    }
+
+    /// Evento emitido cuando un usuario puntúa a su contraparte en una orden recibida.
+    /// `es_vendedor` indica si el calificado es el vendedor de la orden.
+    #[ink(event)]
+    pub struct Calificacion {
+        #[ink(topic)]
+        calificado: AccountId,
+        #[ink(topic)]
+        calificador: AccountId,
+        id_orden: u128,
+        puntuacion: u8,
+        es_vendedor: bool,
     }
 
     /// # Enumeración de los posibles errores que pueden ocurrir en ambos contratos.
@@ -75,9 +192,13 @@ mod usuarios_sistema {
         PublicacionRepetida,
         NoPuedeComprarCero,
         NoPuedeComprarPublicacionPropia,
+        /// La orden o la publicación no está en un estado que permita la operación.
+        /// Los errores por quién llama usan `UsuarioNoAutorizado`.
         OperacionNoValida,
         CancelacionYaSolicitada,
+        /// Reemplazado por `FondosInsuficientes`; se conserva para no alterar los códigos existentes.
         DineroInsuficiente,
+        /// Ya no se devuelve: los desbordes aritméticos usan `Overflow`. Se conserva para no cambiar los códigos.
         FueraDeRango,
         OrdenCancelada,
         UsuarioNoTieneProducto,
@@ -89,6 +210,217 @@ mod usuarios_sistema {
         AccesoDenegado,
         ReportesViewNoEstablecido,
         ReportesViewYaEstablecido,
+        // Agregados después de la versión inicial: siempre al final, en el orden de su código.
+        NoSePuedeQuitarRol,
+        EmailInvalido,
+        SubcategoriaInvalida,
+        OrdenesLleno,
+        DemasiadosItems,
+        StockOverflow,
+        VendedorNoDisponible,
+        CantidadExcedeLimite,
+        DireccionInvalida,
+        PrecioInvalido,
+        FavoritoRepetido,
+        SinDatos,
+        // Reseñas
+        ResenaDemasiadoLarga,
+        ResenaYaRealizada,
+        // Escrow
+        EscrowLleno,
+        ComisionInvalida,
+        // Disputas
+        OrdenEnDisputa,
+        /// El dinero disponible no alcanza para cubrir el monto de la orden.
+        FondosInsuficientes { requerido: u32, disponible: u32 },
+        DescuentoInvalido,
+        /// La publicación no tiene stock suficiente para la cantidad pedida en una compra.
+        StockInsuficienteDetalle { id_publicacion: u128, solicitado: u32, disponible: u32 },
+        /// La publicación está desactivada y no admite compras.
+        PublicacionInactiva,
+        /// Una operación aritmética se pasó del rango de su tipo.
+        /// Los ids agotados siguen usando `ProductosLleno`, `PublicacionesLleno` y `OrdenesLleno`.
+        Overflow,
+        /// La categoría recibida de otro contrato no es conocida.
+        CategoriaInvalida,
+        /// El vendedor de la compra bloqueó al comprador.
+        CompradorBloqueadoPorVendedor,
+        /// El monto de la orden no llega al mínimo configurado por el vendedor.
+        MontoMinimoNoAlcanzado,
+        /// El producto no se puede eliminar porque una publicación o una orden en curso lo usa.
+        ProductoEnUso,
+        /// El inicio del rango pedido es posterior a su fin.
+        RangoInvalido,
+        /// El vendedor ya tiene la cantidad máxima de publicaciones activas.
+        LimiteDePublicacionesAlcanzado,
+        /// Se pidió `Ambos` teniendo un rol simple: hay que agregar el rol faltante.
+        MismoRolAmbosRedundante,
+        /// ReportesView y el marketplace se compilaron con distinta `VERSION_API`.
+        VersionIncompatible,
+        /// La orden ya estaba enviada.
+        OrdenYaEnviada,
+        /// La orden incluye un producto de una categoría que exige dirección de envío y no se indicó ninguna.
+        DireccionRequerida,
+        /// La orden sigue en `PropuestaPendiente`: el comprador todavía no la aceptó.
+        CompradorNoAceptoOrden,
+        /// La publicación está agotada (stock 0); si tiene stock pero no alcanza se usa `StockInsuficienteDetalle`.
+        SinStock,
+        /// El monto de la orden propuesta cambió desde que el vendedor la creó.
+        PropuestaDesactualizada,
+        /// No se puede quitar el rol `Vendedor` mientras el usuario tenga publicaciones activas.
+        VendedorConPublicacionesActivas,
+        /// No se puede quitar el rol `Comprador` mientras el usuario tenga órdenes en curso.
+        CompradorConOrdenesEnCurso,
+    }
+
+    impl ErrorSistema {
+        /// Devuelve un código numérico estable para cada error, pensado para que los front ends
+        /// puedan traducirlos sin depender del nombre de la variante.
+        /// La asignación es sólo de agregado: los códigos existentes nunca cambian y cada
+        /// variante nueva se declara al final del enum y recibe el siguiente código libre,
+        /// así el código siempre es el índice con que scale codifica la variante más uno.
+        pub fn codigo(&self) -> u16 {
+            match self {
+                ErrorSistema::UsuarioYaRegistrado => 1,
+                ErrorSistema::UsuarioNoExiste => 2,
+                ErrorSistema::RolYaEnUso => 3,
+                ErrorSistema::ProductosLleno => 4,
+                ErrorSistema::UsuarioNoEsVendedor => 5,
+                ErrorSistema::UsuarioNoEsComprador => 6,
+                ErrorSistema::ProductoInvalido => 7,
+                ErrorSistema::PublicacionesLleno => 8,
+                ErrorSistema::CompraSinItems => 9,
+                ErrorSistema::PublicacionNoValida => 10,
+                ErrorSistema::StockInsuficiente => 11,
+                ErrorSistema::VendedorDistinto => 12,
+                ErrorSistema::IdDeOrdenNoValida => 13,
+                ErrorSistema::PublicacionRepetida => 14,
+                ErrorSistema::NoPuedeComprarCero => 15,
+                ErrorSistema::NoPuedeComprarPublicacionPropia => 16,
+                ErrorSistema::OperacionNoValida => 17,
+                ErrorSistema::CancelacionYaSolicitada => 18,
+                ErrorSistema::DineroInsuficiente => 19,
+                ErrorSistema::FueraDeRango => 20,
+                ErrorSistema::OrdenCancelada => 21,
+                ErrorSistema::UsuarioNoTieneProducto => 22,
+                ErrorSistema::UsuarioNoAutorizado => 23,
+                ErrorSistema::PuntuacionNoValida => 24,
+                ErrorSistema::OrdenYaPuntuada => 25,
+                ErrorSistema::NoPuedePuntuarOrdenSinRecibir => 26,
+                ErrorSistema::AccesoDenegado => 27,
+                ErrorSistema::ReportesViewNoEstablecido => 28,
+                ErrorSistema::ReportesViewYaEstablecido => 29,
+                ErrorSistema::NoSePuedeQuitarRol => 30,
+                ErrorSistema::EmailInvalido => 31,
+                ErrorSistema::SubcategoriaInvalida => 32,
+                ErrorSistema::OrdenesLleno => 33,
+                ErrorSistema::DemasiadosItems => 34,
+                ErrorSistema::StockOverflow => 35,
+                ErrorSistema::VendedorNoDisponible => 36,
+                ErrorSistema::CantidadExcedeLimite => 37,
+                ErrorSistema::DireccionInvalida => 38,
+                ErrorSistema::PrecioInvalido => 39,
+                ErrorSistema::FavoritoRepetido => 40,
+                ErrorSistema::SinDatos => 41,
+                ErrorSistema::ResenaDemasiadoLarga => 42,
+                ErrorSistema::ResenaYaRealizada => 43,
+                ErrorSistema::EscrowLleno => 44,
+                ErrorSistema::ComisionInvalida => 45,
+                ErrorSistema::OrdenEnDisputa => 46,
+                ErrorSistema::FondosInsuficientes { .. } => 47,
+                ErrorSistema::DescuentoInvalido => 48,
+                ErrorSistema::StockInsuficienteDetalle { .. } => 49,
+                ErrorSistema::PublicacionInactiva => 50,
+                ErrorSistema::Overflow => 51,
+                ErrorSistema::CategoriaInvalida => 52,
+                ErrorSistema::CompradorBloqueadoPorVendedor => 53,
+                ErrorSistema::MontoMinimoNoAlcanzado => 54,
+                ErrorSistema::ProductoEnUso => 55,
+                ErrorSistema::RangoInvalido => 56,
+                ErrorSistema::LimiteDePublicacionesAlcanzado => 57,
+                ErrorSistema::MismoRolAmbosRedundante => 58,
+                ErrorSistema::VersionIncompatible => 59,
+                ErrorSistema::OrdenYaEnviada => 60,
+                ErrorSistema::DireccionRequerida => 61,
+                ErrorSistema::CompradorNoAceptoOrden => 62,
+                ErrorSistema::SinStock => 63,
+                ErrorSistema::PropuestaDesactualizada => 64,
+                ErrorSistema::VendedorConPublicacionesActivas => 65,
+                ErrorSistema::CompradorConOrdenesEnCurso => 66,
+            }
+        }
+
+        /// Devuelve un mensaje corto y legible para cada error, pensado para logs y para mostrar en front ends.
+        pub fn mensaje(&self) -> &'static str {
+            match self {
+                ErrorSistema::UsuarioYaRegistrado => "El usuario ya está registrado",
+                ErrorSistema::UsuarioNoExiste => "El usuario no existe",
+                ErrorSistema::RolYaEnUso => "El usuario ya tiene ese rol",
+                ErrorSistema::ProductosLleno => "No se pueden crear más productos",
+                ErrorSistema::UsuarioNoEsVendedor => "El usuario no es vendedor",
+                ErrorSistema::UsuarioNoEsComprador => "El usuario no es comprador",
+                ErrorSistema::ProductoInvalido => "El producto no es válido",
+                ErrorSistema::PublicacionesLleno => "No se pueden crear más publicaciones",
+                ErrorSistema::CompraSinItems => "La compra no tiene productos",
+                ErrorSistema::PublicacionNoValida => "La publicación no existe",
+                ErrorSistema::StockInsuficiente => "No hay stock suficiente",
+                ErrorSistema::VendedorDistinto => "Todas las publicaciones deben ser del mismo vendedor",
+                ErrorSistema::IdDeOrdenNoValida => "La orden no existe",
+                ErrorSistema::PublicacionRepetida => "La publicación está repetida en la orden",
+                ErrorSistema::NoPuedeComprarCero => "No se puede comprar cero unidades",
+                ErrorSistema::NoPuedeComprarPublicacionPropia => "No se puede comprar una publicación propia",
+                ErrorSistema::OperacionNoValida => "La operación no es válida",
+                ErrorSistema::CancelacionYaSolicitada => "La cancelación ya fue solicitada",
+                ErrorSistema::DineroInsuficiente => "El dinero no alcanza",
+                ErrorSistema::FueraDeRango => "El valor está fuera de rango",
+                ErrorSistema::OrdenCancelada => "La orden está cancelada",
+                ErrorSistema::UsuarioNoTieneProducto => "El usuario no tiene ese producto",
+                ErrorSistema::UsuarioNoAutorizado => "El usuario no está autorizado",
+                ErrorSistema::PuntuacionNoValida => "La puntuación debe estar entre 1 y 5",
+                ErrorSistema::OrdenYaPuntuada => "La orden ya fue puntuada",
+                ErrorSistema::NoPuedePuntuarOrdenSinRecibir => "La orden todavía no fue recibida",
+                ErrorSistema::AccesoDenegado => "Acceso denegado",
+                ErrorSistema::ReportesViewNoEstablecido => "ReportesView no está establecido",
+                ErrorSistema::ReportesViewYaEstablecido => "ReportesView ya está establecido",
+                ErrorSistema::NoSePuedeQuitarRol => "No se puede quitar ese rol",
+                ErrorSistema::EmailInvalido => "El email no es válido",
+                ErrorSistema::SubcategoriaInvalida => "La subcategoría no es válida",
+                ErrorSistema::OrdenesLleno => "No se pueden crear más órdenes",
+                ErrorSistema::DemasiadosItems => "La orden tiene demasiadas publicaciones",
+                ErrorSistema::StockOverflow => "El stock excede el máximo permitido",
+                ErrorSistema::VendedorNoDisponible => "El vendedor ya no está disponible",
+                ErrorSistema::CantidadExcedeLimite => "La cantidad supera el máximo por línea",
+                ErrorSistema::DireccionInvalida => "La dirección de envío no es válida",
+                ErrorSistema::PrecioInvalido => "El precio no es válido",
+                ErrorSistema::FavoritoRepetido => "La publicación ya está en favoritos",
+                ErrorSistema::SinDatos => "No hay datos",
+                ErrorSistema::ResenaDemasiadoLarga => "La reseña es demasiado larga",
+                ErrorSistema::ResenaYaRealizada => "El producto ya tiene una reseña de la orden",
+                ErrorSistema::EscrowLleno => "Se alcanzó el escrow máximo",
+                ErrorSistema::ComisionInvalida => "La comisión no es válida",
+                ErrorSistema::OrdenEnDisputa => "La orden está en disputa",
+                ErrorSistema::FondosInsuficientes { .. } => "Fondos insuficientes para la compra",
+                ErrorSistema::DescuentoInvalido => "Los descuentos no son válidos",
+                ErrorSistema::StockInsuficienteDetalle { .. } => "No hay stock suficiente para una de las líneas",
+                ErrorSistema::PublicacionInactiva => "La publicación no está activa",
+                ErrorSistema::Overflow => "El resultado de una operación excede el rango permitido",
+                ErrorSistema::CategoriaInvalida => "La categoría no es válida",
+                ErrorSistema::CompradorBloqueadoPorVendedor => "El vendedor bloqueó a este comprador",
+                ErrorSistema::MontoMinimoNoAlcanzado => "La orden no alcanza el monto mínimo del vendedor",
+                ErrorSistema::ProductoEnUso => "El producto está en uso",
+                ErrorSistema::RangoInvalido => "El rango no es válido",
+                ErrorSistema::LimiteDePublicacionesAlcanzado => "Se alcanzó el máximo de publicaciones activas",
+                ErrorSistema::MismoRolAmbosRedundante => "Agregue el rol faltante en lugar de Ambos",
+                ErrorSistema::VersionIncompatible => "La versión del contrato no es compatible",
+                ErrorSistema::OrdenYaEnviada => "La orden ya fue enviada",
+                ErrorSistema::DireccionRequerida => "La orden necesita una dirección de envío",
+                ErrorSistema::CompradorNoAceptoOrden => "El comprador todavía no aceptó la orden",
+                ErrorSistema::SinStock => "La publicación no tiene stock",
+                ErrorSistema::PropuestaDesactualizada => "El monto de la propuesta cambió desde que se creó",
+                ErrorSistema::VendedorConPublicacionesActivas => "El vendedor todavía tiene publicaciones activas",
+                ErrorSistema::CompradorConOrdenesEnCurso => "El comprador todavía tiene órdenes en curso",
+            }
+        }
     }
 
     /// # Estructura de un usuario.
@@ -111,6 +443,8 @@ mod usuarios_sistema {
         // reseñas que hicieron del usuario como comprador y como vendedor
         calificaciones_comprador: Vec<u8>,
         calificaciones_vendedor: Vec<u8>,
+        momentos_calificaciones_vendedor: Vec<u64>, // momento en que se recibió cada orden calificada, en paralelo a calificaciones_vendedor
+        favoritos: Vec<u128>, // publicaciones marcadas como favoritas
     }
     
     /// # Estructura de un rol.
@@ -152,10 +486,26 @@ mod usuarios_sistema {
         nombre: String,
         descripcion: String,
         categoria: Categoria,
+        subcategoria: Option<String>,
         puntuaciones: Vec<u8>,
         total_ventas: u32,
     }
 
+    /// # Estructura de una reseña de producto.
+    /// Es instanciada por la funcion `dejar_resena` de Sistema, a partir de una orden recibida.
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(
+        feature = "std",
+        derive(ink::storage::traits::StorageLayout)
+    )]
+    #[derive(Clone, PartialEq, Eq, Debug)]
+    pub struct ResenaProducto {
+        autor: AccountId,
+        id_producto: u128,
+        texto: String,
+        puntuacion: u8,
+    }
+
     /// Categorías en las que puede entrar un producto.
     #[ink::scale_derive(Encode, Decode, TypeInfo)]
     #[cfg_attr(
@@ -172,6 +522,34 @@ mod usuarios_sistema {
         Otros,
     }
 
+    impl Categoria {
+        /// Código con el que viaja la categoría entre contratos, según el orden de declaración.
+        pub fn codigo(&self) -> u8 {
+            match self {
+                Categoria::Limpieza => 0,
+                Categoria::Tecnologia => 1,
+                Categoria::Musica => 2,
+                Categoria::Ropa => 3,
+                Categoria::Calzado => 4,
+                Categoria::Otros => 5,
+            }
+        }
+
+        /// Interpreta un código recibido de otro contrato.
+        /// Retorna `CategoriaInvalida` si no corresponde a ninguna categoría conocida.
+        pub fn desde_codigo(codigo: u8) -> Result<Categoria, ErrorSistema> {
+            match codigo {
+                0 => Ok(Categoria::Limpieza),
+                1 => Ok(Categoria::Tecnologia),
+                2 => Ok(Categoria::Musica),
+                3 => Ok(Categoria::Ropa),
+                4 => Ok(Categoria::Calzado),
+                5 => Ok(Categoria::Otros),
+                _ => Err(ErrorSistema::CategoriaInvalida),
+            }
+        }
+    }
+
     /// # Estructura de una publicación.
     /// Cada publicación está asociada a un producto específico y a un usuario vendedor.
     /// Contiene información relevante para la venta, como el precio, el stock disponible y el estado de la publicación.
@@ -184,13 +562,17 @@ mod usuarios_sistema {
     /// - `precio`: Precio del producto en la publicación.
     /// - `stock`: Cantidad disponible para la venta.
     /// - `activa`: Indica si la publicación está activa o no.
+    /// - `fecha_creacion`: Número de bloque en el que se creó la publicación.
+    /// - `primera_venta`: Número de bloque en el que se recibió la primera orden con su producto.
+    /// - `descuentos`: Escalones (cantidad_minima, descuento_en_bps) ordenados por cantidad mínima.
+    /// - `historial_precios`: Precios anteriores como (precio_anterior, timestamp del cambio), del más viejo al más nuevo.
     ///
     #[ink::scale_derive(Encode, Decode, TypeInfo)]
     #[cfg_attr(
         feature = "std",
         derive(ink::storage::traits::StorageLayout)
     )]
-    #[derive(Debug, Copy, Clone)]
+    #[derive(Debug, Clone)]
 
     pub struct Publicacion {
         id_publicacion: u128,
@@ -199,18 +581,31 @@ mod usuarios_sistema {
         precio: u32,
         stock: u32,
         activa: bool,
+        fecha_creacion: BlockNumber,
+        primera_venta: Option<BlockNumber>,
+        descuentos: Vec<(u32, u16)>,
+        historial_precios: Vec<(u32, u64)>,
     }
 
     /// # Estructura de una orden de compra.
     ///
     /// # Campos
     /// - `lista_productos`: Vector de tuplas [(id_producto, cantidad)] que componen la orden.
+    /// - `lista_publicaciones`: Vector de tuplas [(id_publicacion, cantidad)] de las que se descontó el stock.
     /// - `id_orden_compra`: Identificador único de la orden.
-    /// - `estado`: Estado actual de la orden (Pendiente, Enviado, Recibido, Cancelado).
+    /// - `estado`: Estado actual de la orden (Pendiente, Enviado, Recibido, Cancelado, EnDisputa, PropuestaPendiente).
     /// - `id_comprador`: `AccountId` del comprador.
     /// - `id_vendedor`: `AccountId` del vendedor.
     /// - `solicitud_cancelacion`: `Option<AccountId>` para registrar quién solicitó la cancelación.
     /// - `monto`: Monto total de la orden.
+    /// - `creada_en`: Timestamp del bloque en el que se generó la orden.
+    /// - `actualizada_en`: Timestamp del bloque en el que la orden cambió de estado por última vez.
+    /// - `expira_en`: Timestamp a partir del cual el comprador puede dar por expirada la orden si sigue pendiente.
+    /// - `historial`: Vector de tuplas [(estado, timestamp)] con cada cambio de estado de la orden.
+    /// - `envios`: Vector de tuplas [(id_producto, enviado)] con el progreso del envío de cada línea.
+    /// - `comision`: Parte del monto retenida por el marketplace al completarse la orden; el vendedor recibe `monto - comision`.
+    /// - `direccion_envio`: Dirección de envío indicada por el comprador, si la hay.
+    /// - `recepciones`: Vector de tuplas [(id_producto, recibido)] con las líneas que el comprador confirmó.
     ///
     #[derive(Debug, Clone)]
     #[ink::scale_derive(Encode, Decode, TypeInfo)]
@@ -220,6 +615,7 @@ mod usuarios_sistema {
     )]
     pub struct OrdenCompra {
         lista_productos: Vec<(u128, u32)>, // id del producto y la cantidad comprada.
+        lista_publicaciones: Vec<(u128, u32)>, // id de la publicación y la cantidad comprada.
         id_orden_compra: u128,
         estado: EstadoOrdenCompra,
         id_comprador: AccountId,
@@ -228,6 +624,14 @@ mod usuarios_sistema {
         monto:u32,
         puntuado_por_comprador: bool,
         puntuado_por_vendedor: bool,
+        creada_en: u64,
+        actualizada_en: u64,
+        expira_en: u64,
+        historial: Vec<(EstadoOrdenCompra, u64)>,
+        envios: Vec<(u128, bool)>,
+        comision: u32,
+        direccion_envio: Option<String>,
+        recepciones: Vec<(u128, bool)>,
     }
 
     /// # Estados de una orden de compra.
@@ -242,6 +646,102 @@ mod usuarios_sistema {
         Enviado,
         Recibido,
         Cancelado,
+        EnDisputa,
+        /// Orden propuesta por el vendedor que el comprador todavía no aceptó. No descuenta stock ni cuenta en el escrow.
+        PropuestaPendiente,
+    }
+
+    /// # Resumen de una orden de compra.
+    /// Versión liviana de `OrdenCompra` que devuelve `ver_mis_ordenes_resumen` para listados.
+    ///
+    /// # Campos
+    /// - `id_orden`: Identificador de la orden.
+    /// - `estado`: Estado actual de la orden.
+    /// - `monto`: Monto total de la orden.
+    /// - `contraparte`: `AccountId` del vendedor si el usuario es el comprador, o del comprador si es el vendedor.
+    /// - `cantidad_items`: Cantidad de líneas (productos distintos) de la orden.
+    ///
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(
+        feature = "std",
+        derive(ink::storage::traits::StorageLayout)
+    )]
+    #[derive(Clone, PartialEq, Debug)]
+    pub struct ResumenOrden {
+        id_orden: u128,
+        estado: EstadoOrdenCompra,
+        monto: u32,
+        contraparte: AccountId,
+        cantidad_items: u32,
+    }
+
+    /// # Reputación de un usuario.
+    /// Es devuelta por la funcion `ver_reputacion` de Sistema.
+    ///
+    /// # Campos
+    /// - `prom_vendedor`: Promedio redondeado de sus calificaciones como vendedor, o `None` si no tiene.
+    /// - `n_ventas`: Cantidad de órdenes recibidas en las que fue el vendedor.
+    /// - `prom_comprador`: Promedio redondeado de sus calificaciones como comprador, o `None` si no tiene.
+    /// - `n_compras`: Cantidad de órdenes recibidas en las que fue el comprador.
+    ///
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(
+        feature = "std",
+        derive(ink::storage::traits::StorageLayout)
+    )]
+    #[derive(Clone, PartialEq, Eq, Debug)]
+    pub struct Reputacion {
+        prom_vendedor: Option<u8>,
+        n_ventas: u32,
+        prom_comprador: Option<u8>,
+        n_compras: u32,
+    }
+
+    /// # Resumen de la tienda de un vendedor.
+    /// Es devuelta por la funcion `storefront` de Sistema.
+    ///
+    /// # Campos
+    /// - `nombre`: Nombre del vendedor.
+    /// - `prom_calificacion`: Promedio redondeado de sus calificaciones como vendedor, o `None` si no tiene.
+    /// - `n_publicaciones_activas`: Cantidad de publicaciones activas del vendedor.
+    /// - `n_ventas`: Cantidad de órdenes recibidas en las que fue el vendedor.
+    ///
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(
+        feature = "std",
+        derive(ink::storage::traits::StorageLayout)
+    )]
+    #[derive(Clone, PartialEq, Eq, Debug)]
+    pub struct Storefront {
+        nombre: String,
+        prom_calificacion: Option<u8>,
+        n_publicaciones_activas: u32,
+        n_ventas: u32,
+    }
+
+    /// # Estadísticas detalladas de una categoría.
+    /// Es devuelta por la funcion `estadisticas_por_categoria_detallado` de Sistema.
+    ///
+    /// # Campos
+    /// - `categoria`: Categoría a la que corresponden los datos.
+    /// - `unidades_vendidas`: Suma de `total_ventas` de los productos de la categoría.
+    /// - `ordenes`: Cantidad de órdenes recibidas que incluyen algún producto de la categoría.
+    /// - `ingreso_total`: Suma del monto de esas órdenes recibidas (una orden con varias categorías cuenta en cada una).
+    /// - `prom_calificacion`: Promedio redondeado de las puntuaciones de sus productos, o `None` si no tiene.
+    ///
+    /// Los campos son públicos para que ReportesView y otros contratos puedan leer el reporte.
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(
+        feature = "std",
+        derive(ink::storage::traits::StorageLayout)
+    )]
+    #[derive(Clone, PartialEq, Eq, Debug)]
+    pub struct EstadisticaCategoria {
+        pub categoria: Categoria,
+        pub unidades_vendidas: u32,
+        pub ordenes: u32,
+        pub ingreso_total: u128,
+        pub prom_calificacion: Option<u8>,
     }
 
     impl Sistema {
@@ -261,11 +761,24 @@ mod usuarios_sistema {
                 usuarios: Mapping::new(),
                 publicaciones: Vec::<Publicacion>::new(),
                 productos: Mapping::new(),
-                ordenes:Vec::new(),
+                ordenes: Mapping::new(),
+                id_ordenes: Vec::<u128>::new(),
+                ordenes_archivadas: Mapping::new(),
                 proximo_id_publicacion: 0,
                 proximo_id_producto: 0,
                 proximo_id_orden: 0,
                 reportes_view: None,
+                escrow_maximo: None,
+                comision_bps: 0,
+                comisiones_acumuladas: 0,
+                comisiones_por_categoria: Mapping::default(),
+                carritos: Mapping::new(),
+                resenas: Mapping::new(),
+                reservas: Mapping::new(),
+                bloqueados: Mapping::new(),
+                montos_minimos: Mapping::new(),
+                max_publicaciones_por_vendedor: u32::MAX,
+                direccion_exenta: Mapping::new(),
                 owner: Self::env().caller()
             }
         }
@@ -342,6 +855,22 @@ mod usuarios_sistema {
             }
         }
 
+        /// Indica si el usuario que llama puede publicar: debe estar registrado y ser vendedor o ambos.
+        /// Nunca devuelve error; un usuario no registrado devuelve `false`.
+        #[ink(message)]
+        pub fn puede_publicar(&self) -> bool {
+            let id = self.env().caller();
+            self._es_vendedor(id).unwrap_or(false)
+        }
+
+        /// Indica si el usuario que llama puede comprar: debe estar registrado y ser comprador o ambos.
+        /// Nunca devuelve error; un usuario no registrado devuelve `false`.
+        #[ink(message)]
+        pub fn puede_comprar(&self) -> bool {
+            let id = self.env().caller();
+            self._es_comprador(id).unwrap_or(false)
+        }
+
         //Funciones asociadas a usuarios.
 
         /// Registra un nuevo usuario en el sistema con los datos proporcionados.
@@ -368,7 +897,7 @@ mod usuarios_sistema {
                 return Err(ErrorSistema::UsuarioYaRegistrado);
             }                
             
-            self.usuarios.insert(id, &Usuario {nombre, apellido, email, id, rol, publicaciones: Vec::<u128>::new(), ordenes: Vec::<u128>::new(), productos: Vec::<u128>::new(), calificaciones_comprador: Vec::<u8>::new(), calificaciones_vendedor: Vec::<u8>::new()});
+            self.usuarios.insert(id, &Usuario {nombre, apellido, email, id, rol, publicaciones: Vec::<u128>::new(), ordenes: Vec::<u128>::new(), productos: Vec::<u128>::new(), calificaciones_comprador: Vec::<u8>::new(), calificaciones_vendedor: Vec::<u8>::new(), momentos_calificaciones_vendedor: Vec::<u64>::new(), favoritos: Vec::<u128>::new()});
             
             //Agrego el id al vector id_usuarios.
             self.id_usuarios.push(id);
@@ -377,6 +906,75 @@ mod usuarios_sistema {
         }
 
 
+        /// Registra al usuario que llama si todavía no existe y devuelve su perfil.
+        /// Si ya estaba registrado devuelve el perfil existente sin error, ignorando los datos recibidos.
+        ///
+        /// # Ejemplo
+        /// ```
+        ///      let usuario = sistema.registrar_o_obtener("Juan".to_string(), "Perez".to_string(), "juan@email.com".to_string(), Rol::Comprador)?;
+        /// ```
+        #[ink(message)]
+        pub fn registrar_o_obtener(&mut self, nombre: String, apellido: String, email: String, rol: Rol) -> Result<Usuario, ErrorSistema> {
+            let id = self.env().caller();
+            if let Some(usuario) = self.usuarios.get(id) {
+                return Ok(usuario);
+            }
+            self._registrar_usuario(nombre, apellido, email, rol, id)?;
+            self.usuarios.get(id).ok_or(ErrorSistema::UsuarioNoExiste)
+        }
+
+        /// Registra un lote de usuarios, cada uno con su propio AccountId. Pensado para cargas
+        /// iniciales o migraciones, por lo que sólo el owner puede llamarla.
+        /// Devuelve el resultado de cada registro en el mismo orden, así los fallos parciales quedan visibles.
+        #[ink(message)]
+        pub fn registrar_usuarios_batch(&mut self, usuarios: Vec<(AccountId, String, String, String, Rol)>) -> Result<Vec<Result<(), ErrorSistema>>, ErrorSistema> {
+            self.verificar_owner()?;
+
+            Ok(usuarios.into_iter()
+                .map(|(id, nombre, apellido, email, rol)| self._registrar_usuario(nombre, apellido, email, rol, id))
+                .collect())
+        }
+
+        /// Actualiza los datos de perfil del usuario que llama.
+        /// Sólo se modifican los campos recibidos como `Some`, el resto queda igual.
+        /// Retorna `UsuarioNoExiste` si el caller no está registrado, o `EmailInvalido` si el email no es válido.
+        #[ink(message)]
+        pub fn actualizar_perfil(&mut self, nombre: Option<String>, apellido: Option<String>, email: Option<String>) -> Result<(), ErrorSistema> {
+            let id = self.env().caller();
+            self._actualizar_perfil(nombre, apellido, email, id)
+        }
+
+        fn _actualizar_perfil(&mut self, nombre: Option<String>, apellido: Option<String>, email: Option<String>, id: AccountId) -> Result<(), ErrorSistema> {
+            let mut user = self.usuarios.get(id).ok_or(ErrorSistema::UsuarioNoExiste)?;
+
+            // Valido el email antes de modificar cualquier campo.
+            if let Some(email) = &email {
+                Self::validar_email(email)?;
+            }
+
+            if let Some(nombre) = nombre {
+                user.nombre = nombre;
+            }
+            if let Some(apellido) = apellido {
+                user.apellido = apellido;
+            }
+            if let Some(email) = email {
+                user.email = email;
+            }
+
+            self.usuarios.insert(id, &user);
+            Ok(())
+        }
+
+        /// Verifica que el email tenga un formato válido (algo@algo).
+        fn validar_email(email: &str) -> Result<(), ErrorSistema> {
+            match email.split_once('@') {
+                Some((usuario, dominio)) if !usuario.is_empty() && !dominio.is_empty() && !dominio.contains('@') => Ok(()),
+                _ => Err(ErrorSistema::EmailInvalido),
+            }
+        }
+
+
         /// Agrega un rol adicional al usuario que llama.
         /// Retorna `Ok(())` si el rol fue agregado, o un error si ya lo tiene o no existe.
         ///
@@ -402,6 +1000,37 @@ mod usuarios_sistema {
             }
         }
 
+        /// Quita un rol al usuario que llama, que debe tener el rol `Ambos`.
+        /// No se puede quitar `Vendedor` con publicaciones activas (`VendedorConPublicacionesActivas`),
+        /// ni `Comprador` con órdenes en curso (`CompradorConOrdenesEnCurso`).
+        #[ink(message)]
+        pub fn quitar_rol(&mut self, rol: Rol) -> Result<(), ErrorSistema> {
+            let id = self.env().caller();
+            self._quitar_rol(rol, id)
+        }
+
+        fn _quitar_rol(&mut self, rol: Rol, id: AccountId) -> Result<(), ErrorSistema> {
+            let mut user = self.usuarios.get(id).ok_or(ErrorSistema::UsuarioNoExiste)?;
+
+            match rol {
+                Rol::Vendedor => {
+                    if self.publicaciones.iter().any(|p| p.id_publicador == id && p.activa) {
+                        return Err(ErrorSistema::VendedorConPublicacionesActivas);
+                    }
+                }
+                Rol::Comprador => {
+                    if self.iter_ordenes().any(|o| o.id_comprador == id && o.esta_en_curso()) {
+                        return Err(ErrorSistema::CompradorConOrdenesEnCurso);
+                    }
+                }
+                Rol::Ambos => return Err(ErrorSistema::NoSePuedeQuitarRol),
+            }
+
+            user.quitar_rol(rol)?;
+            self.usuarios.insert(id, &user);
+            Ok(())
+        }
+
 
         /// La función se fija si el id de un produto es menor al id próximo del producto a
         /// cargar, comprobando si éste ya fue cargdo o no.
@@ -429,56 +1058,88 @@ mod usuarios_sistema {
 
 
         /// Crea un nuevo producto asociado al usuario que llama (debe ser vendedor).
+        /// Opcionalmente recibe una subcategoría libre, que se guarda en minúsculas.
         /// Retorna el id del producto creado o un error si no es vendedor.
         ///
         /// # Ejemplo
         /// ```
-        ///     let id_producto = sistema.nuevo_producto("Laptop".to_string(), "Laptop gamer".to_string(), Categoria::Tecnologia)?;
+        ///     let id_producto = sistema.nuevo_producto("Laptop".to_string(), "Laptop gamer".to_string(), Categoria::Tecnologia, Some("Notebooks".to_string()))?;
         /// ```
         #[ink(message)]
-        pub fn nuevo_producto(&mut self, nombre: String, descripcion: String, categoria: Categoria) -> Result<u128, ErrorSistema> {
+        pub fn nuevo_producto(&mut self, nombre: String, descripcion: String, categoria: Categoria, subcategoria: Option<String>) -> Result<u128, ErrorSistema> {
             // El usuario que genera el producto debe existir en el sistema, y ser vendedor.
+            // Lo leo una sola vez del mapping y verifico su rol localmente.
             let usuario_id = self.env().caller(); 
-            if let Err(e) = self._existe_usuario(usuario_id) {
-                return Err(e);
-            }
+            let mut usuario = self.usuarios.get(usuario_id).ok_or(ErrorSistema::UsuarioNoExiste)?;
 
-            if let Ok(false) = self.es_vendedor() {
+            if usuario.rol != Rol::Vendedor && usuario.rol != Rol::Ambos {
                 return Err(ErrorSistema::UsuarioNoEsVendedor);
             }
 
+            let subcategoria = match subcategoria {
+                Some(sub) => Some(Self::normalizar_subcategoria(&sub)?),
+                None => None,
+            };
+
             let id_producto = self.generar_id_producto()?;
 
             self.productos.insert(id_producto.clone(), &Producto {
                 nombre,
                 descripcion,
                 categoria,
+                subcategoria,
                 puntuaciones:Vec::<u8>::new(),
                 total_ventas:0,
 
             });
 
             // Agregar producto a lista personal del vendedor
-            
-            // Este unwrap se puede realizar sin problema porque la funcion es_vendedor() ya verifica si existe el usuario.
-            let mut usuario = self.usuarios.get(&usuario_id).unwrap();
-
             usuario.productos.push(id_producto.clone());
+            self.usuarios.insert(usuario_id, &usuario);
+
+            Ok(id_producto)
+        }
+
+        /// Recorta y pasa a minúsculas la subcategoría, rechazándola si queda vacía o supera `MAX_LARGO_SUBCATEGORIA`.
+        fn normalizar_subcategoria(subcategoria: &str) -> Result<String, ErrorSistema> {
+            let normalizada = subcategoria.trim().to_lowercase();
+            if normalizada.is_empty() || normalizada.len() > MAX_LARGO_SUBCATEGORIA {
+                return Err(ErrorSistema::SubcategoriaInvalida);
+            }
+            Ok(normalizada)
+        }
+
+        /// Elimina un producto propio del sistema y de la lista de productos de su vendedor.
+        /// Retorna `ProductoEnUso` si alguna publicación apunta al producto o alguna orden lo incluye, salvo que
+        /// esté cancelada o que ya la hayan puntuado comprador y vendedor.
+        #[ink(message)]
+        pub fn eliminar_producto(&mut self, id_producto: u128) -> Result<(), ErrorSistema> {
+            let caller = self.env().caller();
+            self._eliminar_producto(id_producto, caller)
+        }
+
+        fn _eliminar_producto(&mut self, id_producto: u128, caller: AccountId) -> Result<(), ErrorSistema> {
+            if !self.productos.contains(id_producto) {
+                return Err(ErrorSistema::ProductoInvalido);
+            }
+            let mut usuario = self.usuarios.get(caller).ok_or(ErrorSistema::UsuarioNoExiste)?;
+            if !usuario.productos.contains(&id_producto) {
+                return Err(ErrorSistema::UsuarioNoTieneProducto);
+            }
 
-            self.usuarios.insert(&usuario_id, &Usuario {
-                nombre: usuario.nombre,
-                apellido: usuario.apellido,
-                email: usuario.email,
-                id: usuario.id,
-                rol: usuario.rol,
-                publicaciones: usuario.publicaciones,
-                ordenes: usuario.ordenes,
-                productos: usuario.productos,
-                calificaciones_comprador: usuario.calificaciones_comprador,
-                calificaciones_vendedor: usuario.calificaciones_vendedor,
+            // No puede quedar ninguna publicación ni orden que todavía use el producto con un id_producto que ya no existe.
+            let publicado = self.publicaciones.iter().any(|publicacion| publicacion.id_producto == id_producto);
+            let en_uso = self.iter_ordenes().any(|orden| {
+                orden.usa_productos() && orden.lista_productos.iter().any(|(id, _)| *id == id_producto)
             });
+            if publicado || en_uso {
+                return Err(ErrorSistema::ProductoEnUso);
+            }
 
-            Ok(id_producto)
+            self.productos.remove(id_producto);
+            usuario.productos.retain(|id| *id != id_producto);
+            self.usuarios.insert(caller, &usuario);
+            Ok(())
         }
 
         // Publicación
@@ -512,7 +1173,7 @@ mod usuarios_sistema {
         pub fn _crear_publicacion(&mut self, id_producto: u128, precio: u32, stock: u32) -> Result<(), ErrorSistema> {
             let usuario_id = self.env().caller(); // Se busca con el AccountId de la cuenta asociada.
 
-            if let Ok(false) = self.es_vendedor() {
+            if !self._es_vendedor(usuario_id)? {
                 return Err(ErrorSistema::UsuarioNoEsVendedor);
             }
             
@@ -524,13 +1185,20 @@ mod usuarios_sistema {
                 return Err(ErrorSistema::StockInsuficiente);
             }
 
-            // Este unwrap se puede realizar sin problema porque la funcion es_vendedor() ya verifica si existe el usuario.
-            let mut usuario = self.usuarios.get(&usuario_id).unwrap();
+            if precio == 0 {
+                return Err(ErrorSistema::PrecioInvalido);
+            }
+
+            let mut usuario = self.usuarios.get(usuario_id).ok_or(ErrorSistema::UsuarioNoExiste)?;
 
             if !usuario.productos.contains(&id_producto) {
                 return Err(ErrorSistema::UsuarioNoTieneProducto);
             }
 
+            if !self.puede_activar_publicacion(usuario_id) {
+                return Err(ErrorSistema::LimiteDePublicacionesAlcanzado);
+            }
+
             // Agrego la publicación
             let id_publicacion = self.generar_id_publicacion()?;
 
@@ -541,2775 +1209,9418 @@ mod usuarios_sistema {
                 precio,
                 stock,
                 activa: true,
+                fecha_creacion: self.env().block_number(),
+                primera_venta: None,
+                descuentos: Vec::new(),
+                historial_precios: Vec::new(),
             });
 
-            // Agrego la publicación a la lista de publicaciones del usuario
-            
-
+            // Agrego la publicación a la lista de publicaciones del usuario y lo guardo modificado.
             usuario.publicaciones.push(id_publicacion);
-
-            self.usuarios.insert(&usuario_id, &Usuario {
-                nombre: usuario.nombre,
-                apellido: usuario.apellido,
-                email: usuario.email,
-                id: usuario.id,
-                rol: usuario.rol,
-                publicaciones: usuario.publicaciones,
-                ordenes: usuario.ordenes,
-                productos: usuario.productos,
-                calificaciones_comprador: usuario.calificaciones_comprador,
-                calificaciones_vendedor: usuario.calificaciones_vendedor,
-            });
+            self.usuarios.insert(usuario_id, &usuario);
 
             Ok(())
         }
 
-        // Orden de compra
-
-
-        /// Genera una nueva orden de compra para el usuario que llama.
-        /// Recibe una lista de tuplas (id_publicacion, cantidad).
-        /// Retorna la orden creada o un error si hay algún problema.
+        /// Modifica una publicación propia. Sólo se cambian los campos recibidos como `Some`.
+        /// `descuentos` es una lista de (cantidad_minima, descuento_en_bps) ordenada por cantidad mínima estrictamente
+        /// creciente; en cada línea de una compra se aplica el mayor descuento cuya cantidad mínima se alcance.
+        /// Retorna `PrecioInvalido` si el precio es cero o `DescuentoInvalido` si los descuentos no son válidos.
+        /// El stock no se puede pisar mientras haya reservas vigentes (retorna `OperacionNoValida`); para sumar
+        /// unidades en ese caso está `reabastecer`.
         ///
         /// # Ejemplo
         /// ```
-        ///     let orden = sistema.generar_orden_compra(vec![(0, 2), (1, 1)])?;
+        ///     // 5% de descuento desde 10 unidades y 10% desde 50.
+        ///     sistema.modificar_publicacion(0, None, None, Some(vec![(10, 500), (50, 1000)]))?;
         /// ```
         #[ink(message)]
-        pub fn generar_orden_compra(&mut self, lista_publicaciones_con_cantidades:Vec<(u128, u32)>, dinero_disponible: u32)->Result<OrdenCompra, ErrorSistema>{
+        pub fn modificar_publicacion(&mut self, id_publicacion: u128, precio: Option<u32>, stock: Option<u32>, descuentos: Option<Vec<(u32, u16)>>) -> Result<(), ErrorSistema> {
             let caller = self.env().caller();
-            return self._generar_orden_compra(lista_publicaciones_con_cantidades, dinero_disponible, caller);
+            self._modificar_publicacion(id_publicacion, precio, stock, descuentos, caller)
         }
-        
-        // Recibe un vector con las publicaciones y la cantidad de cada una para armar la orden.
-        fn _generar_orden_compra(&mut self, lista_publicaciones_con_cantidades:Vec<(u128, u32)> , dinero_disponible:u32, caller:AccountId) -> Result<OrdenCompra, ErrorSistema>{
-            // Chequeo si el usuario que está tratando de realizar la compra tiene el rol debido.
-            
-            // Si no existe el usuario se propaga el error:
-            self.es_comprador()?;
-            // Verifico que el usuario sea comprador.
-            // Si no es comprador, retorno un error.
-            if let comprador = self.es_comprador()? {
-                if !comprador {
-                    return Err(ErrorSistema::UsuarioNoEsComprador);
-                }
-            }
 
-            // Verifico que por lo menos exista una compra.
-            if lista_publicaciones_con_cantidades.is_empty() {
-                return Err(ErrorSistema::CompraSinItems);
+        fn _modificar_publicacion(&mut self, id_publicacion: u128, precio: Option<u32>, stock: Option<u32>, descuentos: Option<Vec<(u32, u16)>>, caller: AccountId) -> Result<(), ErrorSistema> {
+            // Valido todo antes de modificar cualquier campo.
+            if precio == Some(0) {
+                return Err(ErrorSistema::PrecioInvalido);
+            }
+            if let Some(descuentos) = &descuentos {
+                Self::validar_descuentos(descuentos)?;
             }
 
-            // Busco el id del vendedor.
-            let vendedor_actual:AccountId;
-            if let Some(publi) = self.publicaciones.iter().find(|x|x.id_publicacion == lista_publicaciones_con_cantidades[0].0) {
-                vendedor_actual = publi.id_publicador;
+            let ahora = self.env().block_timestamp();
+            let publicacion = self.buscar_publicacion(id_publicacion).ok_or(ErrorSistema::PublicacionNoValida)?;
+            if publicacion.id_publicador != caller {
+                return Err(ErrorSistema::UsuarioNoAutorizado);
             }
-            else {
-                return Err(ErrorSistema::PublicacionNoValida)
+            let se_activa = !publicacion.activa && stock.is_some_and(|stock| stock > 0);
+            if se_activa && !self.puede_activar_publicacion(caller) {
+                return Err(ErrorSistema::LimiteDePublicacionesAlcanzado);
             }
 
-            // Si el usuario que creó la publicación trata de realizar una compra hay error.
+            // Las reservas vigentes volverían al stock al vencer, sumándose al valor nuevo.
+            if stock.is_some() {
+                let reservas = self.reservas.get(id_publicacion).unwrap_or_default();
+                if reservas.iter().any(|(_, _, expira_en)| *expira_en > ahora) {
+                    return Err(ErrorSistema::OperacionNoValida);
+                }
+                // Las vencidas se descartan: el stock nuevo ya es el total que fija el vendedor.
+                self.reservas.remove(id_publicacion);
+            }
 
-            if vendedor_actual == caller {
-                return Err(ErrorSistema::NoPuedeComprarPublicacionPropia);
+            let publicacion = self.buscar_publicacion_mut(id_publicacion).ok_or(ErrorSistema::PublicacionNoValida)?;
+            if let Some(precio) = precio {
+                // Sólo un cambio real de precio queda en el historial.
+                if precio != publicacion.precio {
+                    publicacion.historial_precios.push((publicacion.precio, ahora));
+                    if publicacion.historial_precios.len() > MAX_HISTORIAL_PRECIOS {
+                        publicacion.historial_precios.remove(0);
+                    }
+                }
+                publicacion.precio = precio;
+            }
+            if let Some(stock) = stock {
+                publicacion.stock = stock;
+                publicacion.activa = stock > 0;
+            }
+            if let Some(descuentos) = descuentos {
+                publicacion.descuentos = descuentos;
             }
 
-            
-            self.validar_orden(lista_publicaciones_con_cantidades.clone(), vendedor_actual.clone())?;
+            Ok(())
+        }
 
-            let monto_total = self.validar_precio(lista_publicaciones_con_cantidades.clone(), dinero_disponible)?;
+        /// Devuelve los precios anteriores de una publicación como (precio_anterior, timestamp del cambio),
+        /// del más viejo al más nuevo. Guarda como máximo `MAX_HISTORIAL_PRECIOS` cambios.
+        #[ink(message)]
+        pub fn get_historial_precios(&self, id_publicacion: u128) -> Result<Vec<(u32, u64)>, ErrorSistema> {
+            self.buscar_publicacion(id_publicacion)
+                .map(|publicacion| publicacion.historial_precios.clone())
+                .ok_or(ErrorSistema::PublicacionNoValida)
+        }
 
+        /// Suma `cantidad` unidades al stock de una publicación propia, en lugar de pisarlo como `modificar_publicacion`.
+        /// Si la publicación se había desactivado por quedarse sin stock, vuelve a estar activa.
+        /// Retorna `LimiteDePublicacionesAlcanzado` si reactivarla supera el máximo de publicaciones activas del vendedor.
+        ///
+        /// # Ejemplo
+        /// ```
+        ///     sistema.reabastecer(0, 10)?;
+        /// ```
+        #[ink(message)]
+        pub fn reabastecer(&mut self, id_publicacion: u128, cantidad: u32) -> Result<(), ErrorSistema> {
+            let caller = self.env().caller();
+            self._reabastecer(id_publicacion, cantidad, caller)
+        }
 
-            // Una vez pasadas todas las validaciones, actualizo el stock.
+        fn _reabastecer(&mut self, id_publicacion: u128, cantidad: u32, caller: AccountId) -> Result<(), ErrorSistema> {
+            let publicacion = self.buscar_publicacion(id_publicacion).ok_or(ErrorSistema::PublicacionNoValida)?;
+            if publicacion.id_publicador != caller {
+                return Err(ErrorSistema::UsuarioNoAutorizado);
+            }
+            if !publicacion.activa && cantidad > 0 && !self.puede_activar_publicacion(caller) {
+                return Err(ErrorSistema::LimiteDePublicacionesAlcanzado);
+            }
 
-            let lista_compra = self.actualizar_stock_de_orden(lista_publicaciones_con_cantidades);
+            let publicacion = self.buscar_publicacion_mut(id_publicacion).ok_or(ErrorSistema::PublicacionNoValida)?;
+            publicacion.stock = publicacion.stock.checked_add(cantidad).ok_or(ErrorSistema::StockOverflow)?;
+            if publicacion.stock > 0 {
+                publicacion.activa = true;
+            }
+            Ok(())
+        }
 
+        /// Verifica que los escalones de descuento tengan cantidades mínimas positivas y estrictamente crecientes,
+        /// y descuentos de a lo sumo `MAX_BPS`.
+        fn validar_descuentos(descuentos: &[(u32, u16)]) -> Result<(), ErrorSistema> {
+            let mut minimo_anterior = 0;
+            for (minimo, bps) in descuentos {
+                if *minimo <= minimo_anterior || *bps > MAX_BPS {
+                    return Err(ErrorSistema::DescuentoInvalido);
+                }
+                minimo_anterior = *minimo;
+            }
+            Ok(())
+        }
 
-            let id_orden = self.generar_id_orden()?;
-            
+        /// Elimina una publicación del sistema y de la lista de publicaciones de su vendedor.
+        /// Sólo el vendedor que la creó puede eliminarla.
+        /// Retorna `OperacionNoValida` si alguna orden pendiente, enviada o propuesta incluye el producto publicado.
+        #[ink(message)]
+        pub fn eliminar_publicacion(&mut self, id_publicacion: u128) -> Result<(), ErrorSistema> {
+            let caller = self.env().caller();
+            self._eliminar_publicacion(id_publicacion, caller)
+        }
 
-            // Creo la orden.
+        fn _eliminar_publicacion(&mut self, id_publicacion: u128, caller: AccountId) -> Result<(), ErrorSistema> {
+            let posicion = self.publicaciones.iter().position(|x| x.id_publicacion == id_publicacion)
+                .ok_or(ErrorSistema::PublicacionNoValida)?;
+            let publicacion = self.publicaciones[posicion].clone();
 
-            let orden = OrdenCompra {
-                id_comprador: caller,
-                lista_productos: lista_compra,
-                id_orden_compra: id_orden,
-                estado: EstadoOrdenCompra::Pendiente,
-                id_vendedor: vendedor_actual,
-                solicitud_cancelacion: None,
-                monto: monto_total,
-                puntuado_por_comprador: false,
-                puntuado_por_vendedor: false,
-            };
-            
-            // Agrego la orden al vector de órdenes.
-            self.ordenes.push(orden.clone());
-        
-            // Agrego al vector de ambos usuarios.
-            self.agregar_orden_usuario(caller, id_orden)?;
-            self.agregar_orden_usuario(vendedor_actual, id_orden)?;
+            if publicacion.id_publicador != caller {
+                return Err(ErrorSistema::UsuarioNoAutorizado);
+            }
 
+            // No se puede eliminar si hay órdenes en curso o propuestas con el producto de la publicación.
+            let en_curso = self.iter_ordenes().any(|orden| {
+                orden.id_vendedor == caller
+                    && (orden.esta_en_curso() || orden.estado == EstadoOrdenCompra::PropuestaPendiente)
+                    && orden.lista_productos.iter().any(|(id_producto, _)| *id_producto == publicacion.id_producto)
+            });
+            if en_curso {
+                return Err(ErrorSistema::OperacionNoValida);
+            }
 
-            Ok(orden.clone())
-            
-        }
+            self.publicaciones.remove(posicion);
 
-        fn agregar_orden_usuario(&mut self, user_id:AccountId, id_orden:u128)->Result<(), ErrorSistema>{
-            if let Some(mut user) = self.usuarios.get(&user_id){
-                user.ordenes.push(id_orden);
-                self.usuarios.insert(&user_id, &user);
-                return Ok(())
-            }
-            else {
-                return Err(ErrorSistema::UsuarioNoExiste);
+            if let Some(mut usuario) = self.usuarios.get(caller) {
+                usuario.publicaciones.retain(|id| *id != id_publicacion);
+                self.usuarios.insert(caller, &usuario);
             }
 
+            Ok(())
         }
 
-        fn validar_orden(&self, lista_publicaciones_con_cantidades:Vec<(u128, u32)>, vendedor_actual:AccountId)->Result<(), ErrorSistema>{
-            // Itero sobre la lista de publicaciones con cantidades y voy chequeando si la compra es válida(id de publicaciones válida y cant válida).
+        /// Transfiere una publicación propia a otro vendedor, moviéndola entre las listas de publicaciones de ambos.
+        /// Retorna `UsuarioNoEsVendedor` si el destinatario no es vendedor, u `OperacionNoValida` si alguna orden
+        /// en curso o propuesta incluye la publicación o si se intenta transferir a uno mismo.
+        #[ink(message)]
+        pub fn transferir_publicacion(&mut self, id_publicacion: u128, nuevo_vendedor: AccountId) -> Result<(), ErrorSistema> {
+            let caller = self.env().caller();
+            self._transferir_publicacion(id_publicacion, nuevo_vendedor, caller)
+        }
 
-            let mut vistos = BTreeSet::new();
-            
-            for (id_publicacion_actual, cant_productos) in lista_publicaciones_con_cantidades {
+        fn _transferir_publicacion(&mut self, id_publicacion: u128, nuevo_vendedor: AccountId, caller: AccountId) -> Result<(), ErrorSistema> {
+            let publicacion = self.buscar_publicacion(id_publicacion).ok_or(ErrorSistema::PublicacionNoValida)?;
+            if publicacion.id_publicador != caller {
+                return Err(ErrorSistema::UsuarioNoAutorizado);
+            }
 
-                //Check de que no compre dos veces de la misma publicación
-                if !vistos.insert(id_publicacion_actual) {
-                    return Err(ErrorSistema::PublicacionRepetida)
-                }
+            if nuevo_vendedor == caller {
+                return Err(ErrorSistema::OperacionNoValida);
+            }
 
-                // Check que se trate de comprar aunque sea un item de la publicación.
-                if cant_productos==0 {
-                    return Err(ErrorSistema::NoPuedeComprarCero)
-                }
+            if !self._es_vendedor(nuevo_vendedor)? {
+                return Err(ErrorSistema::UsuarioNoEsVendedor);
+            }
 
-                if let Some(publicacion_actual) = self.publicaciones.iter().find(|x| x.id_publicacion == id_publicacion_actual){
+            // No se puede transferir si hay órdenes en curso o propuestas que incluyan la publicación.
+            let en_curso = self.iter_ordenes().any(|orden| {
+                (orden.esta_en_curso() || orden.estado == EstadoOrdenCompra::PropuestaPendiente)
+                    && orden.lista_publicaciones.iter().any(|(id, _)| *id == id_publicacion)
+            });
+            if en_curso {
+                return Err(ErrorSistema::OperacionNoValida);
+            }
 
-                    // Veo que todas las publicaciones sean del mismo vendedor.
-                    if publicacion_actual.id_publicador != vendedor_actual {
-                        return Err(ErrorSistema::VendedorDistinto)
-                    }
+            if let Some(publicacion) = self.buscar_publicacion_mut(id_publicacion) {
+                publicacion.id_publicador = nuevo_vendedor;
+            }
 
-                    // Veo que la publicación tenga el stock necesario para la compra.
-                    if !publicacion_actual.tiene_stock_suficiente(cant_productos) {
-                        return Err(ErrorSistema::StockInsuficiente)
-                    }
-                }
-                else {
-                    return Err(ErrorSistema::PublicacionNoValida)
-                }
+            if let Some(mut usuario) = self.usuarios.get(caller) {
+                usuario.publicaciones.retain(|id| *id != id_publicacion);
+                self.usuarios.insert(caller, &usuario);
             }
+
+            // El destinatario existe porque `_es_vendedor` ya lo verificó.
+            let mut destinatario = self.usuarios.get(nuevo_vendedor).ok_or(ErrorSistema::UsuarioNoExiste)?;
+            destinatario.publicaciones.push(id_publicacion);
+            self.usuarios.insert(nuevo_vendedor, &destinatario);
+
             Ok(())
-        
         }
 
-        fn validar_precio(&self, lista_publicaciones_con_cantidades:Vec<(u128, u32)>, dinero_disponible: u32)->Result<u32, ErrorSistema>{
-            let mut monto_total:u32=0;
-            for (id_publicacion, cant_productos) in lista_publicaciones_con_cantidades {
-                if let Some(publicacion_actual) = self.publicaciones.get(id_publicacion as usize){
-
-                    let monto_actual = match publicacion_actual.precio.checked_mul(cant_productos) {
-                        Some(val) => val,
-                        None => return Err(ErrorSistema::FueraDeRango),
-                    };
-                    monto_total = match monto_total.checked_add(monto_actual) {
-                        Some(val) => val,
-                        None => return Err(ErrorSistema::FueraDeRango),
-                    }
-                }
-                else {
-                    return Err(ErrorSistema::PublicacionNoValida);
-                }
-            }
+        // Favoritos
 
-            if dinero_disponible >= monto_total {
-                return Ok(monto_total)
+        /// Agrega una publicación a la lista de favoritos del usuario que llama.
+        /// Retorna `PublicacionNoValida` si la publicación no existe, o `FavoritoRepetido` si ya era favorita.
+        #[ink(message)]
+        pub fn agregar_favorito(&mut self, id_publicacion: u128) -> Result<(), ErrorSistema> {
+            let caller = self.env().caller();
+            self._agregar_favorito(id_publicacion, caller)
+        }
+
+        fn _agregar_favorito(&mut self, id_publicacion: u128, caller: AccountId) -> Result<(), ErrorSistema> {
+            let mut user = self.usuarios.get(caller).ok_or(ErrorSistema::UsuarioNoExiste)?;
+
+            if self.buscar_publicacion(id_publicacion).is_none() {
+                return Err(ErrorSistema::PublicacionNoValida);
             }
-            else {
-                return Err(ErrorSistema::DineroInsuficiente);
+
+            if user.favoritos.contains(&id_publicacion) {
+                return Err(ErrorSistema::FavoritoRepetido);
             }
+
+            user.favoritos.push(id_publicacion);
+            self.usuarios.insert(caller, &user);
+            Ok(())
         }
 
-        fn generar_id_orden(&mut self)->Result<u128, ErrorSistema>{
-            let proximo = self.proximo_id_orden.clone();
-            match self.proximo_id_orden.checked_add(1){
-                Some(val) => {
-                    self.proximo_id_orden = val;
-                    Ok(proximo)
-                }
-                None => Err(ErrorSistema::PublicacionesLleno)
-            }
+        /// Quita una publicación de la lista de favoritos del usuario que llama.
+        /// Retorna `PublicacionNoValida` si la publicación no estaba entre sus favoritos.
+        #[ink(message)]
+        pub fn quitar_favorito(&mut self, id_publicacion: u128) -> Result<(), ErrorSistema> {
+            let caller = self.env().caller();
+            self._quitar_favorito(id_publicacion, caller)
         }
 
-        fn actualizar_stock_de_orden(&mut self, lista_publicaciones_con_cantidades:Vec<(u128, u32)>)->Vec<(u128,u32)>{
-            let mut lista_productos = Vec::new();
-            for(id_publi, cant_productos) in lista_publicaciones_con_cantidades{
-                if let Some(posicion) = self.publicaciones.iter().position(|x| x.id_publicacion == id_publi){
+        fn _quitar_favorito(&mut self, id_publicacion: u128, caller: AccountId) -> Result<(), ErrorSistema> {
+            let mut user = self.usuarios.get(caller).ok_or(ErrorSistema::UsuarioNoExiste)?;
+
+            let posicion = user.favoritos.iter().position(|id| *id == id_publicacion)
+                .ok_or(ErrorSistema::PublicacionNoValida)?;
 
-                    if let Some(publicacion_actual) = self.publicaciones.get_mut(posicion) {
-                        publicacion_actual.actualizar_stock(cant_productos);
-                        lista_productos.push((publicacion_actual.id_producto, cant_productos));
+            user.favoritos.remove(posicion);
+            self.usuarios.insert(caller, &user);
+            Ok(())
+        }
+
+        /// Devuelve las publicaciones favoritas del usuario que llama.
+        /// Las favoritas cuya publicación ya fue eliminada se omiten.
+        #[ink(message)]
+        pub fn ver_favoritos(&self) -> Vec<Publicacion> {
+            let caller = self.env().caller();
+            self._ver_favoritos(caller)
+        }
+
+        fn _ver_favoritos(&self, caller: AccountId) -> Vec<Publicacion> {
+            let mut favoritos = Vec::new();
+            if let Some(user) = self.usuarios.get(caller) {
+                for id in user.favoritos {
+                    if let Some(publicacion) = self.buscar_publicacion(id) {
+                        favoritos.push(publicacion.clone());
                     }
                 }
             }
-            lista_productos
+            favoritos
         }
 
+        // Orden de compra
+
 
-        /// Marca una orden como enviada. Solo el vendedor puede hacerlo.
-        /// Retorna `Ok(())` si la operación fue exitosa, o un error si no corresponde.
+        /// Genera una nueva orden de compra para el usuario que llama.
+        /// Recibe una lista de tuplas (id_publicacion, cantidad) y, opcionalmente, la dirección de envío.
+        /// `dinero_disponible` es el monto que declara tener el comprador: la función no es `payable` y no recibe
+        /// fondos, así que el escrow es nominal y sólo limita la suma de los montos de las órdenes en curso.
+        /// Retorna la orden creada o un error si hay algún problema.
         ///
         /// # Ejemplo
         /// ```
-        ///      sistema.marcar_orden_como_enviada(0);
+        ///     let orden = sistema.generar_orden_compra(vec![(0, 2), (1, 1)], 1000, Some(String::from("Calle 1 123")))?;
         /// ```
         #[ink(message)]
-        pub fn marcar_orden_como_enviada(&mut self, id_actual:u128)->Result<(), ErrorSistema> {
+        pub fn generar_orden_compra(&mut self, lista_publicaciones_con_cantidades:Vec<(u128, u32)>, dinero_disponible: u32, direccion_envio: Option<String>)->Result<OrdenCompra, ErrorSistema>{
             let caller = self.env().caller();
-            self._marcar_orden_como_enviada(id_actual, caller)
+            self._generar_orden_compra(lista_publicaciones_con_cantidades, dinero_disponible, direccion_envio, caller)
         }
 
-        fn _marcar_orden_como_enviada(&mut self, id_actual:u128, caller:AccountId)->Result<(), ErrorSistema>{
-
-
-            if let Some(orden_actual) = self.ordenes.get_mut(id_actual as usize){
-                if orden_actual.id_vendedor != caller {
-                    return Err(ErrorSistema::OperacionNoValida)
-                } 
-                match &orden_actual.estado {
-                    EstadoOrdenCompra::Pendiente => Ok(orden_actual.estado = EstadoOrdenCompra::Enviado),
-                    _ => return Err(ErrorSistema::OperacionNoValida),
-                }
-                 
-            }
-            else {
-                return Err(ErrorSistema::IdDeOrdenNoValida);
-            }
-            
-        }
-
-
-        /// Marca una orden como recibida. Solo el comprador puede hacerlo.
-        /// Retorna `Ok(())` si la operación fue exitosa, o un error si no corresponde.
+        /// Igual que `generar_orden_compra`, pero si una publicación aparece varias veces suma sus cantidades
+        /// en una sola línea (en la posición de su primera aparición) antes de validar.
+        /// La línea fusionada sigue sujeta al stock y a `MAX_CANTIDAD_POR_LINEA`.
         ///
         /// # Ejemplo
         /// ```
-        ///      sistema.marcar_orden_como_recibida(0);
+        ///     // Equivale a comprar vec![(0, 3)].
+        ///     let orden = sistema.generar_orden_compra_fusionando(vec![(0, 1), (0, 2)], 1000, Some(String::from("Calle 1 123")))?;
         /// ```
         #[ink(message)]
-        pub fn marcar_orden_como_recibida(&mut self, id_actual:u128)->Result<(), ErrorSistema> {
+        pub fn generar_orden_compra_fusionando(&mut self, lista_publicaciones_con_cantidades:Vec<(u128, u32)>, dinero_disponible: u32, direccion_envio: Option<String>)->Result<OrdenCompra, ErrorSistema>{
             let caller = self.env().caller();
-            self._marcar_orden_como_recibida(id_actual, caller)
-        }
-
-        fn _marcar_orden_como_recibida(&mut self, id_actual:u128, caller:AccountId)->Result<(), ErrorSistema>{
-            
-
-            if let Some(orden_actual) = self.ordenes.get_mut(id_actual as usize){
-                if orden_actual.id_comprador != caller {
-                    return Err(ErrorSistema::OperacionNoValida)
-                } 
-                match &orden_actual.estado {
-                    EstadoOrdenCompra::Enviado => {
-                        for (id_producto, cantidad) in &orden_actual.lista_productos{
-                            let mut produc = self.productos.get(id_producto).unwrap();
-                            produc.total_ventas = produc.total_ventas.checked_add(*cantidad)
-                                .ok_or(ErrorSistema::FueraDeRango)?;
-                            self.productos.insert(id_producto, &produc);
-                        }
-                        return Ok(orden_actual.estado = EstadoOrdenCompra::Recibido);
-                    },
-                    _ => return Err(ErrorSistema::OperacionNoValida),
+            let mut fusionada: Vec<(u128, u32)> = Vec::new();
+            for (id_publicacion, cantidad) in lista_publicaciones_con_cantidades {
+                if let Some(item) = fusionada.iter_mut().find(|(id, _)| *id == id_publicacion) {
+                    item.1 = suma_segura_u32(item.1, cantidad)?;
+                } else {
+                    fusionada.push((id_publicacion, cantidad));
                 }
-                 
-            }
-            else {
-                return Err(ErrorSistema::IdDeOrdenNoValida);
             }
-            
+            self._generar_orden_compra(fusionada, dinero_disponible, direccion_envio, caller)
         }
 
-
-        /// Solicita la cancelación de una orden. Puede ser solicitada por comprador o vendedor.
-        /// Si ambos la solicitan, la orden se cancela.
-        /// Retorna `Ok(())` si la operación fue exitosa, o un error si no corresponde.
+        /// Simula una compra sin modificar el stock ni crear la orden.
+        /// Corre las mismas validaciones que `generar_orden_compra` y retorna el monto que se cobraría,
+        /// o el error que devolvería la compra real. Como la compra real, cuenta como disponibles las
+        /// reservas vigentes del comprador y las reservas vencidas.
         ///
         /// # Ejemplo
         /// ```
-        ///      sistema.cancelar_orden(0);
+        ///     let monto = sistema.simular_orden(vec![(0, 2), (1, 1)], 1000)?;
         /// ```
         #[ink(message)]
-        pub fn cancelar_orden(&mut self, id_actual:u128)->Result<(), ErrorSistema> {
+        pub fn simular_orden(&self, carrito: Vec<(u128, u32)>, dinero_disponible: u32) -> Result<u32, ErrorSistema> {
             let caller = self.env().caller();
-            self._cancelar_orden(id_actual, caller)
+            let ahora = self.env().block_timestamp();
+            let reservas_a_favor = self.reservas_a_favor(&carrito, caller, ahora);
+            let (_, monto_total) = self.validar_compra(&carrito, dinero_disponible, caller, &reservas_a_favor)?;
+            Ok(monto_total)
+        }
+
+        /// Devuelve, por publicación de la compra, las unidades reservadas que `tomar_reservas` devolvería al stock:
+        /// las reservas vencidas y la reserva vigente del comprador.
+        fn reservas_a_favor(&self, lista_publicaciones_con_cantidades: &[(u128, u32)], comprador: AccountId, ahora: u64) -> Vec<(u128, u32)> {
+            lista_publicaciones_con_cantidades.iter().map(|(id_publicacion, _)| {
+                let unidades = self.reservas.get(id_publicacion).unwrap_or_default().iter()
+                    .filter(|(id, _, expira_en)| *id == comprador || *expira_en <= ahora)
+                    .fold(0u32, |total, (_, cantidad, _)| total.saturating_add(*cantidad));
+                (*id_publicacion, unidades)
+            }).collect()
+        }
+
+        /// Reserva stock de una publicación para el comprador que llama durante `PLAZO_RESERVA`.
+        /// Las unidades reservadas se descuentan del stock hasta que el comprador las compre o la reserva venza.
+        /// Si el comprador ya tenía una reserva vigente en la publicación, se suma la cantidad y se renueva el plazo.
+        ///
+        /// # Ejemplo
+        /// ```
+        ///     sistema.reservar_stock(0, 2)?;
+        ///     sistema.generar_orden_compra(vec![(0, 2)], 1000, Some(String::from("Calle 1 123")))?;
+        /// ```
+        #[ink(message)]
+        pub fn reservar_stock(&mut self, id_publicacion: u128, cantidad: u32) -> Result<(), ErrorSistema> {
+            let caller = self.env().caller();
+            let ahora = self.env().block_timestamp();
+            self._reservar_stock(id_publicacion, cantidad, caller, ahora)
         }
 
-        fn _cancelar_orden(&mut self, id_actual:u128, caller:AccountId) -> Result<(), ErrorSistema> {
-            
+        fn _reservar_stock(&mut self, id_publicacion: u128, cantidad: u32, caller: AccountId, ahora: u64) -> Result<(), ErrorSistema> {
+            if !self._es_comprador(caller)? {
+                return Err(ErrorSistema::UsuarioNoEsComprador);
+            }
+            if cantidad == 0 {
+                return Err(ErrorSistema::NoPuedeComprarCero);
+            }
+            if cantidad > MAX_CANTIDAD_POR_LINEA {
+                return Err(ErrorSistema::CantidadExcedeLimite);
+            }
 
-            if let Some(orden_actual) = self.ordenes.get_mut(id_actual as usize) {
+            // Antes de mirar el stock, libero las reservas que ya vencieron.
+            let mut reservas = self.liberar_reservas_vencidas(id_publicacion, ahora);
 
-                if orden_actual.id_vendedor != caller && orden_actual.id_comprador != caller {
-                    return Err(ErrorSistema::UsuarioNoAutorizado);
-                }
-            
+            let id_publicador = self.buscar_publicacion(id_publicacion).ok_or(ErrorSistema::PublicacionNoValida)?.id_publicador;
+            if id_publicador == caller {
+                return Err(ErrorSistema::NoPuedeComprarPublicacionPropia);
+            }
+            if self.esta_bloqueado(id_publicador, caller) {
+                return Err(ErrorSistema::CompradorBloqueadoPorVendedor);
+            }
 
-                if orden_actual.estado == EstadoOrdenCompra::Cancelado {
-                    return Err(ErrorSistema::OrdenCancelada);
-                }
+            let publicacion = self.buscar_publicacion_mut(id_publicacion).ok_or(ErrorSistema::PublicacionNoValida)?;
+            if !publicacion.activa {
+                return Err(ErrorSistema::PublicacionInactiva);
+            }
+            if publicacion.stock == 0 {
+                return Err(ErrorSistema::SinStock);
+            }
+            if !publicacion.tiene_stock_suficiente(cantidad) {
+                return Err(ErrorSistema::StockInsuficienteDetalle {
+                    id_publicacion,
+                    solicitado: cantidad,
+                    disponible: publicacion.stock,
+                });
+            }
+            publicacion.actualizar_stock(cantidad)?;
 
-                if orden_actual.estado == EstadoOrdenCompra::Recibido {
-                    return Err(ErrorSistema::OperacionNoValida);
+            let expira_en = ahora.saturating_add(PLAZO_RESERVA);
+            match reservas.iter_mut().find(|(comprador, _, _)| *comprador == caller) {
+                Some(reserva) => {
+                    reserva.1 = reserva.1.saturating_add(cantidad);
+                    reserva.2 = expira_en;
                 }
+                None => reservas.push((caller, cantidad, expira_en)),
+            }
+            self.reservas.insert(id_publicacion, &reservas);
+            Ok(())
+        }
+        
+        // Recibe un vector con las publicaciones y la cantidad de cada una para armar la orden.
+        /// Corre todas las validaciones de una compra sin modificar el estado.
+        /// `reservas_a_favor` suma al stock de cada publicación las unidades reservadas que todavía no se devolvieron.
+        /// Retorna el vendedor de la orden y el monto total que se cobraría.
+        fn validar_compra(&self, lista_publicaciones_con_cantidades: &[(u128, u32)], dinero_disponible: u32, caller: AccountId, reservas_a_favor: &[(u128, u32)]) -> Result<(AccountId, u32), ErrorSistema> {
+            // Chequeo si el usuario que está tratando de realizar la compra tiene el rol debido.
+            
+            // Si no existe el usuario se propaga el error.
+            // Si no es comprador, retorno un error.
+            if !self._es_comprador(caller)? {
+                return Err(ErrorSistema::UsuarioNoEsComprador);
+            }
 
-                if let Some(id_anterior) = orden_actual.solicitud_cancelacion {
-                    if id_anterior == caller {
-                        return Err(ErrorSistema::CancelacionYaSolicitada);
-                    }
-                    else {
-                        if id_anterior == orden_actual.id_comprador || id_anterior == orden_actual.id_vendedor{
-                            self.ordenes.get_mut(id_actual as usize).unwrap().estado = EstadoOrdenCompra::Cancelado;
-                            return Ok(())
-                        }
-                    }
-                }
-                self.ordenes.get_mut(id_actual as usize).unwrap().solicitud_cancelacion = Some(caller);
-                return Ok(())
-                    
+            // Verifico que por lo menos exista una compra.
+            if lista_publicaciones_con_cantidades.is_empty() {
+                return Err(ErrorSistema::CompraSinItems);
+            }
+
+            // Busco el id del vendedor.
+            let vendedor_actual:AccountId;
+            if let Some(publi) = self.buscar_publicacion(lista_publicaciones_con_cantidades[0].0) {
+                vendedor_actual = publi.id_publicador;
             }
             else {
-                return Err(ErrorSistema::IdDeOrdenNoValida);
+                return Err(ErrorSistema::PublicacionNoValida)
             }
-            
-        }
 
+            self.validar_orden(lista_publicaciones_con_cantidades.to_vec(), vendedor_actual, caller, reservas_a_favor)?;
 
-        /// Permite a los usuarios puntuar al comprador o al vendedor después de finalizar una orden
-        /// Solo los usuarios involucrados en la orden pueden interactuar
-        /// Solo se puede puntuar una vez por usuario y por orden
-        /// La puntuación es entre 1 y 5
-        #[ink(message)]
-        pub fn puntuar_usuario_por_orden(&mut self, id_orden:u128, puntuacion:u8) -> Result<(), ErrorSistema> {
-            let caller = self.env().caller();
-            self._puntuar_usuario_por_orden(id_orden, puntuacion, caller)
+            let monto_total = self.validar_precio(lista_publicaciones_con_cantidades.to_vec(), dinero_disponible)?;
+
+            self.validar_escrow(monto_total)?;
+
+            // Verifico que ambos usuarios existan antes de modificar nada, así la orden se aplica completa o no se aplica.
+            if !self.usuarios.contains(caller) || !self.usuarios.contains(vendedor_actual) {
+                return Err(ErrorSistema::UsuarioNoExiste);
+            }
+
+            Ok((vendedor_actual, monto_total))
         }
 
-        fn _puntuar_usuario_por_orden(&mut self, id_orden:u128, puntuacion:u8, caller:AccountId)->Result<(), ErrorSistema>{
-            if puntuacion > 5 || puntuacion < 1 {
-                return Err(ErrorSistema::PuntuacionNoValida);
+        fn _generar_orden_compra(&mut self, lista_publicaciones_con_cantidades:Vec<(u128, u32)> , dinero_disponible:u32, direccion_envio: Option<String>, caller:AccountId) -> Result<OrdenCompra, ErrorSistema>{
+            // Las reservas vigentes del comprador vuelven al stock para que la compra las consuma.
+            let ahora = self.env().block_timestamp();
+            let reservas_tomadas = self.tomar_reservas(&lista_publicaciones_con_cantidades, caller, ahora);
+
+            let resultado = self.crear_orden_de_compra(lista_publicaciones_con_cantidades, dinero_disponible, direccion_envio, caller, ahora);
+            if resultado.is_err() {
+                // Si la compra no se puede hacer, por el motivo que sea, el comprador conserva sus reservas.
+                self.devolver_reservas(caller, reservas_tomadas);
             }
-            else {
-                if let Some(orden) = self.ordenes.get_mut(id_orden as usize) {
-                    if orden.estado != EstadoOrdenCompra::Recibido {
-                        return Err(ErrorSistema::NoPuedePuntuarOrdenSinRecibir);
+            resultado
+        }
+
+        /// Valida la compra y crea la orden, con las reservas del comprador ya devueltas al stock.
+        /// Cualquier error que retorne hace que `_generar_orden_compra` le devuelva las reservas al comprador.
+        fn crear_orden_de_compra(&mut self, lista_publicaciones_con_cantidades: Vec<(u128, u32)>, dinero_disponible: u32, direccion_envio: Option<String>, caller: AccountId, ahora: u64) -> Result<OrdenCompra, ErrorSistema> {
+            let (vendedor_actual, monto_total) = self.validar_compra(&lista_publicaciones_con_cantidades, dinero_disponible, caller, &[])?;
+
+            // Si se indicó una dirección de envío, verifico que sea válida.
+            // Si no, ninguna categoría de la orden puede exigirla.
+            match &direccion_envio {
+                Some(direccion) => Self::validar_direccion(direccion)?,
+                None => {
+                    if self.requiere_direccion(&lista_publicaciones_con_cantidades) {
+                        return Err(ErrorSistema::DireccionRequerida);
                     }
-                    else {
+                }
+            }
 
-                    
+            // Una vez pasadas todas las validaciones, actualizo el stock.
 
-                        if orden.id_comprador == caller {
-                            if orden.puntuado_por_comprador {
-                                return Err(ErrorSistema::OrdenYaPuntuada);
-                            }
-                            else {
-                                let mut user = self.usuarios.get(orden.id_vendedor).unwrap();
-                                user.calificaciones_vendedor.push(puntuacion);
-                                self.usuarios.insert(&orden.id_vendedor, &user);
-                                orden.puntuado_por_comprador = true;
+            let lista_compra = self.actualizar_stock_de_orden(lista_publicaciones_con_cantidades.clone())?;
 
 
-                                // Este for actualiza las puntuaciones por prodcuto de la lista
-                                for (id_producto, cantidad) in &orden.lista_productos {
-                                    let mut producto_actual = self.productos.get(id_producto).unwrap();
-                                    producto_actual.puntuaciones.push(puntuacion);
-                                    self.productos.insert(&id_producto, &producto_actual);
-                
-                                }
+            let id_orden = match self.generar_id_orden() {
+                Ok(id) => id,
+                Err(e) => {
+                    self.restaurar_stock_de_orden(lista_publicaciones_con_cantidades)?;
+                    return Err(e);
+                }
+            };
+            
 
-                                return Ok(());
-                            }
-                        }
-                        else 
-                            if orden.id_vendedor == caller {
-                                if orden.puntuado_por_vendedor {
-                                    return Err(ErrorSistema::OrdenYaPuntuada);
-                                }
-                                else {
-                                    let mut user = self.usuarios.get(orden.id_comprador).unwrap();
-                                    user.calificaciones_comprador.push(puntuacion);
-                                    self.usuarios.insert(&orden.id_comprador, &user);
-                                    orden.puntuado_por_vendedor = true;
-                                    return Ok(());
-                                }
-                            }
-
-                            else {
-                                return Err(ErrorSistema::UsuarioNoAutorizado);
-                            }
-                        
-                    }
-                }
-                else {
-                    return Err(ErrorSistema::IdDeOrdenNoValida);
-                }
-            }
+            // Creo la orden.
 
-        }
+            let envios: Vec<(u128, bool)> = lista_compra.iter().map(|(id_producto, _)| (*id_producto, false)).collect();
+            let recepciones = envios.clone();
+            let orden = OrdenCompra {
+                id_comprador: caller,
+                lista_productos: lista_compra,
+                lista_publicaciones: lista_publicaciones_con_cantidades,
+                id_orden_compra: id_orden,
+                estado: EstadoOrdenCompra::Pendiente,
+                id_vendedor: vendedor_actual,
+                solicitud_cancelacion: None,
+                monto: monto_total,
+                puntuado_por_comprador: false,
+                puntuado_por_vendedor: false,
+                creada_en: ahora,
+                actualizada_en: ahora,
+                expira_en: ahora.saturating_add(PLAZO_EXPIRACION_ORDEN),
+                historial: vec![(EstadoOrdenCompra::Pendiente, ahora)],
+                envios,
+                comision: 0,
+                direccion_envio,
+                recepciones,
+            };
+            
+            // Agrego la orden al mapping de órdenes.
+            self.guardar_orden(&orden);
+        
+            // Agrego al vector de ambos usuarios.
+            self.agregar_orden_usuario(caller, id_orden)?;
+            self.agregar_orden_usuario(vendedor_actual, id_orden)?;
 
-        #[ink(message)]
-        pub fn obtener_puntuacion_de_comprador(&self, id_usuario:AccountId)->Result<u8, ErrorSistema>{
-            if let Some(user) = self.usuarios.get(id_usuario) {
-                return Ok(user.calcular_puntaje_como_comprador());
-            }
-            else {
-                return Err(ErrorSistema::UsuarioNoExiste);
-            }
-        }
 
-        #[ink(message)]
-        pub fn obtener_puntuacion_de_vendedor(&self, id_usuario:AccountId)->Result<u8, ErrorSistema>{
-            if let Some(user) = self.usuarios.get(id_usuario) {
-                return Ok(user.calcular_puntaje_como_vendedor());
-            }
-            else {
-                return Err(ErrorSistema::UsuarioNoExiste);
-            }
+            Ok(orden.clone())
+            
         }
 
-
-        /// Devuelve la lista de todas las publicaciones existentes en el sistema.
+        /// Permite a un vendedor registrar una venta acordada por fuera del contrato, proponiéndole una orden a un comprador.
+        /// La orden queda en `PropuestaPendiente`: no descuenta stock ni cuenta en el escrow hasta que el comprador
+        /// la acepte con `aceptar_orden`, o la descarte con `rechazar_orden`.
+        /// Corre las mismas validaciones que una compra del comprador, y todas las publicaciones tienen que ser del vendedor que llama.
+        /// Retorna el id de la orden propuesta.
         ///
         /// # Ejemplo
         /// ```
-        ///      let publicaciones = sistema.get_publicaciones();
+        ///     let id_orden = sistema.crear_orden_para_comprador(comprador, vec![(0, 2)])?;
         /// ```
         #[ink(message)]
-        pub fn get_publicaciones(&self)->Vec<Publicacion>{
-            self.publicaciones.clone()
+        pub fn crear_orden_para_comprador(&mut self, comprador: AccountId, carrito: Vec<(u128, u32)>) -> Result<u128, ErrorSistema> {
+            let caller = self.env().caller();
+            self._crear_orden_para_comprador(comprador, carrito, caller)
         }
 
+        fn _crear_orden_para_comprador(&mut self, comprador: AccountId, carrito: Vec<(u128, u32)>, caller: AccountId) -> Result<u128, ErrorSistema> {
+            let ahora = self.env().block_timestamp();
+            if !self._es_vendedor(caller)? {
+                return Err(ErrorSistema::UsuarioNoEsVendedor);
+            }
 
-        /// Devuelve la lista de todas las publicaciones existentes en el sistema del vendedor que la llama.
-        ///
-        /// # Ejemplo
-        /// ```
-        ///      let mis_publicaciones = sistema.get_publicaciones_propias();
-        /// ```
+            // El comprador todavía no indicó cuánto dinero tiene, así que sólo se valida el resto de la compra.
+            let (vendedor, monto_total) = self.validar_compra(&carrito, u32::MAX, comprador, &[])?;
+            if vendedor != caller {
+                return Err(ErrorSistema::UsuarioNoAutorizado);
+            }
+
+            let mut lista_productos = Vec::new();
+            for (id_publicacion, cantidad) in &carrito {
+                let publicacion = self.buscar_publicacion(*id_publicacion).ok_or(ErrorSistema::PublicacionNoValida)?;
+                lista_productos.push((publicacion.id_producto, *cantidad));
+            }
+
+            let id_orden = self.generar_id_orden()?;
+            let envios: Vec<(u128, bool)> = lista_productos.iter().map(|(id_producto, _)| (*id_producto, false)).collect();
+            let recepciones = envios.clone();
+            let orden = OrdenCompra {
+                id_comprador: comprador,
+                lista_productos,
+                lista_publicaciones: carrito,
+                id_orden_compra: id_orden,
+                estado: EstadoOrdenCompra::PropuestaPendiente,
+                id_vendedor: caller,
+                solicitud_cancelacion: None,
+                monto: monto_total,
+                puntuado_por_comprador: false,
+                puntuado_por_vendedor: false,
+                creada_en: ahora,
+                actualizada_en: ahora,
+                expira_en: ahora.saturating_add(PLAZO_EXPIRACION_ORDEN),
+                historial: vec![(EstadoOrdenCompra::PropuestaPendiente, ahora)],
+                envios,
+                comision: 0,
+                direccion_envio: None,
+                recepciones,
+            };
+
+            self.guardar_orden(&orden);
+            self.agregar_orden_usuario(comprador, id_orden)?;
+            self.agregar_orden_usuario(caller, id_orden)?;
+
+            Ok(id_orden)
+        }
+
+        /// Permite al comprador aceptar una orden propuesta por el vendedor. Recién entonces se descuenta el stock
+        /// y la orden pasa a `Pendiente`, con el plazo de expiración recalculado al momento de aceptar.
+        /// Si alguna categoría de la orden exige dirección, el comprador tiene que cargarla antes con `actualizar_direccion`.
+        /// Retorna `OperacionNoValida` si la orden no es una propuesta pendiente, `PropuestaDesactualizada` si el monto
+        /// ya no coincide con el propuesto, o `VendedorDistinto` si las publicaciones cambiaron de vendedor.
         #[ink(message)]
-        pub fn get_publicaciones_propias(&self)-> Result<Vec<Publicacion>, ErrorSistema>{
+        pub fn aceptar_orden(&mut self, id_orden: u128) -> Result<(), ErrorSistema> {
             let caller = self.env().caller();
-            self._get_publicaciones_propias(caller)
+            self._aceptar_orden(id_orden, caller)
         }
 
-        fn _get_publicaciones_propias(&self, caller:AccountId)-> Result<Vec<Publicacion>, ErrorSistema> {
-            let mut publicaciones_propias = Vec::<Publicacion>::new();
-            // Verifico si el usuario existe.
+        fn _aceptar_orden(&mut self, id_orden: u128, caller: AccountId) -> Result<(), ErrorSistema> {
+            let ahora = self.env().block_timestamp();
+            let mut orden = self.ordenes.get(id_orden).ok_or(ErrorSistema::IdDeOrdenNoValida)?;
 
-            if let Err(e) = self._existe_usuario(caller) {
-                return Err(ErrorSistema::UsuarioNoExiste); // Si no existe, retorno un vector vacío.
-            } else {
-                if !self.es_vendedor().unwrap_or(false) {
-                    return Err(ErrorSistema::UsuarioNoEsVendedor); // Si no es vendedor, retorno un vector vacío.
-                }
+            if orden.id_comprador != caller {
+                return Err(ErrorSistema::UsuarioNoAutorizado);
+            }
+            if orden.estado != EstadoOrdenCompra::PropuestaPendiente {
+                return Err(ErrorSistema::OperacionNoValida);
             }
 
-            // Si el usuario existe y es vendedor, busco sus publicaciones.
-            // Itero sobre las publicaciones del usuario y las agrego al vector de publicaciones propias.
-            // Si el usuario no tiene publicaciones, el vector quedará vacío.
-            let mut publicaciones_propias = Vec::new();
-            for publicacion in self.publicaciones.iter() {
-                if publicacion.id_publicador == caller {
-                    publicaciones_propias.push(publicacion.clone());
-                }
+            // El stock o los precios pudieron cambiar desde la propuesta, así que vuelvo a validar la compra.
+            // El comprador acepta el monto propuesto, no uno nuevo.
+            let (vendedor, monto_total) = self.validar_compra(&orden.lista_publicaciones, u32::MAX, caller, &[])?;
+            if vendedor != orden.id_vendedor {
+                return Err(ErrorSistema::VendedorDistinto);
+            }
+            if monto_total != orden.monto {
+                return Err(ErrorSistema::PropuestaDesactualizada);
+            }
+            if orden.direccion_envio.is_none() && self.requiere_direccion(&orden.lista_publicaciones) {
+                return Err(ErrorSistema::DireccionRequerida);
             }
 
-            Ok(publicaciones_propias)
+            self.actualizar_stock_de_orden(orden.lista_publicaciones.clone())?;
+
+            orden.expira_en = ahora.saturating_add(PLAZO_EXPIRACION_ORDEN);
+            orden.cambiar_estado(EstadoOrdenCompra::Pendiente, ahora);
+            self.ordenes.insert(id_orden, &orden);
+            Ok(())
         }
 
-        /// Devuelve la lista de órdenes asociadas al usuario que llama.
-        ///
-        /// # Ejemplo
-        /// ```
-        ///   let mis_ordenes = sistema.ver_mis_ordenes();
-        /// ```
+        /// Permite al comprador rechazar una orden propuesta por el vendedor. La orden pasa a `Cancelado`
+        /// sin tocar el stock, que nunca se descontó.
+        /// Retorna `OperacionNoValida` si la orden no es una propuesta pendiente.
         #[ink(message)]
-        pub fn ver_mis_ordenes(&self)->Vec<OrdenCompra>{
+        pub fn rechazar_orden(&mut self, id_orden: u128) -> Result<(), ErrorSistema> {
             let caller = self.env().caller();
-            self._ver_mis_ordenes(caller)
+            self._rechazar_orden(id_orden, caller)
         }
 
-        fn _ver_mis_ordenes(&self, caller:AccountId)->Vec<OrdenCompra>{
-            let mut mis_ordenes = Vec::new();
-            if let Some(user) = self.usuarios.get(caller){
-                for id in user.ordenes {
-                    if let Some(orden) = self.ordenes.get(id as usize){
-                        mis_ordenes.push(orden.clone())
-                    }
-                    
-                }
+        fn _rechazar_orden(&mut self, id_orden: u128, caller: AccountId) -> Result<(), ErrorSistema> {
+            let ahora = self.env().block_timestamp();
+            let mut orden = self.ordenes.get(id_orden).ok_or(ErrorSistema::IdDeOrdenNoValida)?;
+
+            if orden.id_comprador != caller {
+                return Err(ErrorSistema::UsuarioNoAutorizado);
             }
-            mis_ordenes
+            if orden.estado != EstadoOrdenCompra::PropuestaPendiente {
+                return Err(ErrorSistema::OperacionNoValida);
+            }
+
+            orden.cambiar_estado(EstadoOrdenCompra::Cancelado, ahora);
+            self.ordenes.insert(id_orden, &orden);
+            Ok(())
         }
 
-        //
-        //
-        //  Funciones vinculadas a ReportesView
-        //
-        //
+        // Carrito
 
-        /// Verifica que el usuario que llama sea el propietario del contrato.
-        /// Se utiliza para el seteo del AccountId de ReportesView.
-        fn verificar_owner(&self) -> Result<(), ErrorSistema> {
+        /// Agrega una cantidad de una publicación al carrito del usuario que llama.
+        /// Si la publicación ya estaba en el carrito, se suman las cantidades.
+        #[ink(message)]
+        pub fn agregar_al_carrito(&mut self, id_publicacion: u128, cantidad: u32) -> Result<(), ErrorSistema> {
             let caller = self.env().caller();
+            self._agregar_al_carrito(id_publicacion, cantidad, caller)
+        }
 
-            if caller != self.owner {
-                return Err(ErrorSistema::AccesoDenegado);
+        fn _agregar_al_carrito(&mut self, id_publicacion: u128, cantidad: u32, caller: AccountId) -> Result<(), ErrorSistema> {
+            self._existe_usuario(caller)?;
+
+            if cantidad == 0 {
+                return Err(ErrorSistema::NoPuedeComprarCero);
             }
 
+            if self.buscar_publicacion(id_publicacion).is_none() {
+                return Err(ErrorSistema::PublicacionNoValida);
+            }
+
+            let mut carrito = self.carritos.get(caller).unwrap_or_default();
+            if let Some(item) = carrito.iter_mut().find(|(id, _)| *id == id_publicacion) {
+                item.1 = suma_segura_u32(item.1, cantidad)?;
+            } else {
+                carrito.push((id_publicacion, cantidad));
+            }
+            self.carritos.insert(caller, &carrito);
             Ok(())
         }
-        
-        /// (A fines de debug solamente) Retorna el AccountId establecido para ReportesView.
+
+        /// Devuelve el carrito del usuario que llama como tuplas (id_publicacion, cantidad).
         #[ink(message)]
-        pub fn get_reportes_view(&self) -> Result<Option<AccountId>, ErrorSistema> {
-            self.verificar_owner()?;
-            Ok(self.reportes_view)
+        pub fn ver_carrito(&self) -> Vec<(u128, u32)> {
+            let caller = self.env().caller();
+            self.carritos.get(caller).unwrap_or_default()
         }
 
-        /// Setea el AccountId de ReportesView.
-        /// Sólo el propietario del sistema puede llamar a esta función.
-        /// Retorna `Ok(())` si se estableció correctamente, o un error si no existe.
+        /// Vacía el carrito del usuario que llama.
         #[ink(message)]
-        pub fn set_reportes_view(&mut self, reportes_view: AccountId) -> Result<(), ErrorSistema> {
-            self.verificar_owner()?;
-            self._set_reportes_view(reportes_view)?;
-            Ok(())
+        pub fn vaciar_carrito(&mut self) {
+            let caller = self.env().caller();
+            self.carritos.remove(caller);
         }
 
-        fn _set_reportes_view(&mut self, reportes_view: AccountId) -> Result<(), ErrorSistema> {
-            if self.reportes_view.is_some() {
-                return Err(ErrorSistema::ReportesViewYaEstablecido);
-            }
-            
-            self.reportes_view = Some(reportes_view);
-            Ok(())
+        /// Genera una orden de compra con el contenido del carrito del usuario que llama.
+        /// Las líneas con cantidad cero se descartan; si no queda ninguna se retorna `CompraSinItems`.
+        /// Si la orden se genera correctamente se vacía el carrito y se retorna el id de la orden.
+        #[ink(message)]
+        pub fn comprar_carrito(&mut self, dinero_disponible: u32, direccion_envio: Option<String>) -> Result<u128, ErrorSistema> {
+            let caller = self.env().caller();
+            // Descarto las líneas sin cantidad. Si el carrito queda vacío, la validación de la compra
+            // lo rechaza con `CompraSinItems` en lugar de generar una orden vacía.
+            let carrito: Carrito = self.carritos.get(caller).unwrap_or_default()
+                .into_iter()
+                .filter(|(_, cantidad)| *cantidad > 0)
+                .collect();
+
+            let orden = self._generar_orden_compra(carrito, dinero_disponible, direccion_envio, caller)?;
+            self.carritos.remove(caller);
+
+            Ok(orden.id_orden_compra)
         }
 
-        /// Devuelve hasta 5 usuarios (Vendedor/Ambos) ordenados por puntaje como vendedor.
-        /// Solo accesible si se ha establecido ReportesView.
-         #[ink(message)]
-        pub fn consultar_top_5_vendedores(&self) -> Result<Vec<Usuario>,ErrorSistema> {
-            self.verificar_reportes_view()?;
-            self._consultar_top_5_vendedores()
+        /// Indica si alguna publicación de la lista es de un producto cuya categoría exige dirección de envío.
+        fn requiere_direccion(&self, lista_publicaciones_con_cantidades: &[(u128, u32)]) -> bool {
+            lista_publicaciones_con_cantidades.iter().any(|(id_publicacion, _)| {
+                self.buscar_publicacion(*id_publicacion)
+                    .and_then(|publicacion| self.productos.get(publicacion.id_producto))
+                    .is_some_and(|producto| !self.direccion_exenta.contains(producto.categoria))
+            })
         }
 
-        fn _consultar_top_5_vendedores(&self) -> Result<Vec<Usuario>,ErrorSistema> {
-            //Filtro los vendedores recorriendo el vector de id_usuarios y buscando en el mapping.
-            let mut vendedores: Vec<Usuario> = Vec::new(); //Creo un vector con los vendedores.
-            for id in &self.id_usuarios {
-                if let Some(user) = self.usuarios.get(id) {
-                    if user.rol == Rol::Vendedor || user.rol == Rol::Ambos {
-                        vendedores.push(user);
-                    }
-                }
+        /// Verifica que la dirección de envío no esté vacía ni supere `MAX_LARGO_DIRECCION`.
+        fn validar_direccion(direccion: &str) -> Result<(), ErrorSistema> {
+            if direccion.trim().is_empty() || direccion.len() > MAX_LARGO_DIRECCION {
+                return Err(ErrorSistema::DireccionInvalida);
             }
-
-            //Ordeno el vector en cuanto reputación de forma descendente.
-            vendedores.sort_by(|a, b| b.calcular_puntaje_como_vendedor().cmp(&a.calcular_puntaje_como_vendedor()));
-
-            //Recorto el tamaño del vector a 5.
-            vendedores.truncate(5);
-
-            Ok(vendedores)
+            Ok(())
         }
 
-        /// Devuelve hasta 5 usuarios (Vendedor/Ambos) ordenados por puntaje como vendedor.
-        /// Solo accesible si se ha establecido ReportesView.
-         #[ink(message)]
-        pub fn consultar_top_5_compradores(&self) -> Result<Vec<Usuario>,ErrorSistema> {
-            self.verificar_reportes_view()?;
-            self._consultar_top_5_compradores()
+        /// Permite al comprador cambiar la dirección de envío de una orden mientras siga pendiente.
+        #[ink(message)]
+        pub fn actualizar_direccion(&mut self, id_orden: u128, direccion: String) -> Result<(), ErrorSistema> {
+            let caller = self.env().caller();
+            self._actualizar_direccion(id_orden, direccion, caller)
         }
 
-        fn _consultar_top_5_compradores(&self) -> Result<Vec<Usuario>,ErrorSistema> {
-            //Filtro los compradores recorriendo el vector de id_usuarios y buscando en el mapping.
-            let mut compradores: Vec<Usuario> = Vec::new(); //Creo un vector con los compradores.
-            for id in &self.id_usuarios {
-                if let Some(user) = self.usuarios.get(id) {
-                    if user.rol == Rol::Comprador || user.rol == Rol::Ambos {
-                        compradores.push(user);
-                    }
-                }
-            }
+        fn _actualizar_direccion(&mut self, id_orden: u128, direccion: String, caller: AccountId) -> Result<(), ErrorSistema> {
+            let mut orden = self.ordenes.get(id_orden).ok_or(ErrorSistema::IdDeOrdenNoValida)?;
 
-            //Ordeno el vector en cuanto reputación de forma descendente.
-            compradores.sort_by(|a, b| b.calcular_puntaje_como_comprador().cmp(&a.calcular_puntaje_como_comprador()));
+            if orden.id_comprador != caller {
+                return Err(ErrorSistema::UsuarioNoAutorizado);
+            }
 
-            //Recorto el tamaño del vector a 5.
-            compradores.truncate(5);
+            if !matches!(orden.estado, EstadoOrdenCompra::Pendiente | EstadoOrdenCompra::PropuestaPendiente) {
+                return Err(ErrorSistema::OperacionNoValida);
+            }
 
-            Ok(compradores)
+            Self::validar_direccion(&direccion)?;
+            orden.direccion_envio = Some(direccion);
+            self.ordenes.insert(id_orden, &orden);
+            Ok(())
         }
 
-        /// Devuelve una lista de hasta 10 productos de una categoría específica ordenados por ventas en forma descendente.
-        /// Solo accesible si se ha establecido ReportesView.
-        ///
-        /// # Parámetros
-        /// - `categoria`: Categoría de los productos a buscar.
-        ///
-        #[ink(message)]
-        pub fn ver_productos_mas_vendidos(&self, categoria: Categoria) -> Result<Vec<(u128, u8)>, ErrorSistema>{
-            self.verificar_reportes_view()?;
-            Ok(self._ver_productos_mas_vendidos(categoria))
+        fn agregar_orden_usuario(&mut self, user_id:AccountId, id_orden:u128)->Result<(), ErrorSistema>{
+            if let Some(mut user) = self.usuarios.get(&user_id){
+                user.ordenes.push(id_orden);
+                self.usuarios.insert(&user_id, &user);
+                return Ok(())
+            }
+            else {
+                return Err(ErrorSistema::UsuarioNoExiste);
+            }
+
         }
 
-        fn _ver_productos_mas_vendidos(&self, categoria: Categoria) -> Vec<(u128, u8)> {
-            //Creo un vector para los productos.
-            let mut productos: Vec<(u128, u8)> = Vec::new();
+        fn validar_orden(&self, lista_publicaciones_con_cantidades:Vec<(u128, u32)>, vendedor_actual:AccountId, comprador:AccountId, reservas_a_favor: &[(u128, u32)])->Result<(), ErrorSistema>{
+            // Itero sobre la lista de publicaciones con cantidades y voy chequeando si la compra es válida(id de publicaciones válida y cant válida).
 
-            //Recorro el vector de ordenes -> Voy chequeando la lista de productos y completo el vector según eso
-            for orden in &self.ordenes {
+            if lista_publicaciones_con_cantidades.len() > MAX_ITEMS_POR_ORDEN {
+                return Err(ErrorSistema::DemasiadosItems);
+            }
 
-                //Recorro la lista de productos de la orden.
-                for tupla in &orden.lista_productos {
+            // El publicador tiene que seguir teniendo el rol de vendedor al momento de la compra.
+            if let Some(vendedor) = self.usuarios.get(vendedor_actual) {
+                if vendedor.rol != Rol::Vendedor && vendedor.rol != Rol::Ambos {
+                    return Err(ErrorSistema::VendedorNoDisponible);
+                }
+            }
 
-                    //Primero busco al producto en el mapping de productos del sistema (para ver su categoría).
-                    let producto = self.productos.get(tupla.0 as u128);
+            // El vendedor puede haber bloqueado al comprador.
+            if self.esta_bloqueado(vendedor_actual, comprador) {
+                return Err(ErrorSistema::CompradorBloqueadoPorVendedor);
+            }
 
-                    if producto.is_none() {
-                        continue; //Si el producto no existe, paso al siguiente.
+            let mut vistos = BTreeSet::new();
+            
+            for (id_publicacion_actual, cant_productos) in lista_publicaciones_con_cantidades {
+
+                //Check de que no compre dos veces de la misma publicación
+                if !vistos.insert(id_publicacion_actual) {
+                    return Err(ErrorSistema::PublicacionRepetida)
+                }
+
+                // Check que se trate de comprar aunque sea un item de la publicación.
+                if cant_productos==0 {
+                    return Err(ErrorSistema::NoPuedeComprarCero)
+                }
+
+                // Check de que no se supere el máximo de unidades por línea.
+                if cant_productos > MAX_CANTIDAD_POR_LINEA {
+                    return Err(ErrorSistema::CantidadExcedeLimite)
+                }
+
+                if let Some(publicacion_actual) = self.buscar_publicacion(id_publicacion_actual){
+
+                    // Si el que compra es quien creó la publicación hay error. Se chequea en cada línea.
+                    if publicacion_actual.id_publicador == comprador {
+                        return Err(ErrorSistema::NoPuedeComprarPublicacionPropia)
                     }
-                    let producto = producto.unwrap();
 
-                    if producto.categoria == categoria {
-                        //Si ya existe en mi vector contador, aumento la cantidad de ventas del mismo.
-                        if let Some(pos) = productos.iter().position(|(p, _)| *p == tupla.0) { //Busco la posición del producto en el vector.
-                            //productos[pos].1 += tupla.1 as u8; //Aumento la cantidad de ventas del producto.
-                            productos[pos].1 = productos[pos].1.wrapping_add(tupla.1 as u8);
-                        } else { //Si no existe, lo agrego.
-                            productos.push((tupla.0.clone(), tupla.1.try_into().unwrap())); //Agrego el producto al vector.
-                        }
+                    // Veo que todas las publicaciones sean del mismo vendedor.
+                    if publicacion_actual.id_publicador != vendedor_actual {
+                        return Err(ErrorSistema::VendedorDistinto)
+                    }
+
+                    // Veo que la publicación tenga el stock necesario para la compra.
+                    let reservado = reservas_a_favor.iter()
+                        .find(|(id, _)| *id == id_publicacion_actual)
+                        .map_or(0, |(_, unidades)| *unidades);
+                    let disponible = publicacion_actual.stock.saturating_add(reservado);
+                    if disponible == 0 {
+                        return Err(ErrorSistema::SinStock)
+                    }
+                    if disponible < cant_productos {
+                        return Err(ErrorSistema::StockInsuficienteDetalle {
+                            id_publicacion: id_publicacion_actual,
+                            solicitado: cant_productos,
+                            disponible,
+                        })
+                    }
+
+                    // Una publicación desactivada no se puede comprar aunque tenga stock.
+                    if !publicacion_actual.activa {
+                        return Err(ErrorSistema::PublicacionInactiva)
                     }
                 }
+                else {
+                    return Err(ErrorSistema::PublicacionNoValida)
+                }
             }
+            Ok(())
+        
+        }
 
-            //Ordeno el vector en cuanto cantidad de ventas de forma descendente.
-            productos.sort_by(|a, b| b.1.cmp(&a.1));
+        fn validar_precio(&self, lista_publicaciones_con_cantidades:Vec<(u128, u32)>, dinero_disponible: u32)->Result<u32, ErrorSistema>{
+            let mut monto_total:u32=0;
+            let mut vendedor: Option<AccountId> = None;
+            for (id_publicacion, cant_productos) in lista_publicaciones_con_cantidades {
+                if let Some(publicacion_actual) = self.buscar_publicacion(id_publicacion){
+                    vendedor = Some(publicacion_actual.id_publicador);
 
-            //Recorto el tamaño del vector a 10 (como máximo).
-            productos.truncate(10);
+                    let monto_actual = publicacion_actual.precio_con_descuento(cant_productos)?;
+                    monto_total = suma_segura_u32(monto_total, monto_actual)?;
+                }
+                else {
+                    return Err(ErrorSistema::PublicacionNoValida);
+                }
+            }
 
-            productos
+            // Si el vendedor configuró un monto mínimo, la orden tiene que alcanzarlo.
+            if let Some(minimo) = vendedor.and_then(|vendedor| self.montos_minimos.get(vendedor)) {
+                if monto_total < minimo {
+                    return Err(ErrorSistema::MontoMinimoNoAlcanzado);
+                }
+            }
+
+            if dinero_disponible >= monto_total {
+                return Ok(monto_total)
+            }
+            else {
+                return Err(ErrorSistema::FondosInsuficientes { requerido: monto_total, disponible: dinero_disponible });
+            }
         }
 
-        /// Devuelve un vector con las estadisticas por categoría, con la cantidad total de ventas y la suma de puntuaciones.
-        #[ink(message)]
-        pub fn estadisticas_por_categoria(&self) -> Result<Vec<(Categoria, u32, u8)>, ErrorSistema> { 
-            self.verificar_reportes_view()?;
-            self._estadisticas_por_categoria()
+        /// Busca una publicación por su id.
+        fn buscar_publicacion(&self, id: u128) -> Option<&Publicacion> {
+            self.publicaciones.iter().find(|x| x.id_publicacion == id)
         }
 
-        fn _estadisticas_por_categoria(&self) -> Result<Vec<(Categoria, u32, u8)>, ErrorSistema>{
-            let mut ventas_por_categoria: BTreeMap<Categoria, u32> = BTreeMap::new();
-            let mut suma_puntajes: BTreeMap<Categoria, u8> = BTreeMap::new();
-            let mut cantidad_puntajes: BTreeMap<Categoria, u8> = BTreeMap::new();
+        /// Busca una publicación por su id y la devuelve para modificarla.
+        fn buscar_publicacion_mut(&mut self, id: u128) -> Option<&mut Publicacion> {
+            self.publicaciones.iter_mut().find(|x| x.id_publicacion == id)
+        }
 
-            let mut id: u128 = 0;
-            while id < self.proximo_id_producto {
-                if let Some(producto) = self.productos.get(&id) {
-                    let key = producto.categoria.clone();
-                    let entry = ventas_por_categoria.entry(key.clone()).or_insert(0u32);
-                    let nueva_ventas = entry
-                        .checked_add(producto.total_ventas)
-                        .ok_or(ErrorSistema::FueraDeRango)?;
-                    *entry = nueva_ventas;
+        /// Guarda una orden nueva en el mapping y agrega su id al índice de órdenes.
+        fn guardar_orden(&mut self, orden: &OrdenCompra) {
+            self.ordenes.insert(orden.id_orden_compra, orden);
+            self.id_ordenes.push(orden.id_orden_compra);
+        }
 
-                    let suma: u8 = producto.puntuaciones.iter().map(|&x| x as u8).sum();
-                    let cantidad = producto.puntuaciones.len() as u8;
+        /// Recorre todas las órdenes siguiendo el índice de ids.
+        fn iter_ordenes(&self) -> impl Iterator<Item = OrdenCompra> + '_ {
+            self.id_ordenes.iter().filter_map(|id| self.ordenes.get(id))
+        }
 
-                    let s_entry = suma_puntajes.entry(key.clone()).or_insert(0u8);
-                    let nueva_suma = s_entry
-                        .checked_add(suma)
-                        .ok_or(ErrorSistema::FueraDeRango)?;
-                    *s_entry = nueva_suma;
+        fn generar_id_orden(&mut self)->Result<u128, ErrorSistema>{
+            let proximo = self.proximo_id_orden.clone();
+            match self.proximo_id_orden.checked_add(1){
+                Some(val) => {
+                    self.proximo_id_orden = val;
+                    Ok(proximo)
+                }
+                None => Err(ErrorSistema::OrdenesLleno)
+            }
+        }
 
-                    let c_entry = cantidad_puntajes.entry(key.clone()).or_insert(0u8);
-                    let nueva_cant = c_entry
-                        .checked_add(cantidad)
-                        .ok_or(ErrorSistema::FueraDeRango)?;
-                    *c_entry = nueva_cant;
+        fn actualizar_stock_de_orden(&mut self, lista_publicaciones_con_cantidades:Vec<(u128, u32)>)->Result<Vec<(u128,u32)>, ErrorSistema>{
+            let mut lista_productos = Vec::new();
+            let mut descontadas = Vec::new();
+            let mut agotadas = Vec::new();
+            for(id_publi, cant_productos) in lista_publicaciones_con_cantidades{
+                if let Some(publicacion_actual) = self.buscar_publicacion_mut(id_publi) {
+                    if let Err(e) = publicacion_actual.actualizar_stock(cant_productos) {
+                        // Devuelvo el stock ya descontado para no dejar la orden a medias.
+                        self.restaurar_stock_de_orden(descontadas)?;
+                        return Err(e);
+                    }
+                    // Si se agotó el stock, la publicación deja de estar activa.
+                    if publicacion_actual.stock == 0 {
+                        publicacion_actual.activa = false;
+                        agotadas.push((id_publi, publicacion_actual.id_publicador));
+                    }
+                    lista_productos.push((publicacion_actual.id_producto, cant_productos));
+                    descontadas.push((id_publi, cant_productos));
                 }
-                id = id.checked_add(1).ok_or(ErrorSistema::FueraDeRango)?;
             }
 
-            let mut resultado = Vec::new();
-            for categoria in ventas_por_categoria.keys() {
-                let ventas = *ventas_por_categoria.get(categoria).unwrap_or(&0);
-                let suma = *suma_puntajes.get(categoria).unwrap_or(&0);
-                let cantidad = *cantidad_puntajes.get(categoria).unwrap_or(&0);
-                let promedio = match suma.checked_div(cantidad) {
-                    Some(promedio) => promedio,
-                    None => 0,
-                };
-                resultado.push((categoria.clone(), ventas, promedio));
+            for (id_publicacion, publicador) in agotadas {
+                self.env().emit_event(SinStock { id_publicacion, publicador });
             }
-            Ok(resultado)
+            Ok(lista_productos)
         }
 
-        /// Devuelve un vector con la cantidad de órdenes de todos los usuarios registrados.
+
+        /// Marca una orden como enviada. Solo el vendedor puede hacerlo.
+        /// Retorna `Ok(())` si la operación fue exitosa, o un error si no corresponde.
+        ///
+        /// # Ejemplo
+        /// ```
+        ///      sistema.marcar_orden_como_enviada(0);
+        /// ```
         #[ink(message)]
-        pub fn cantidad_ordenes_por_usuario(&self) -> Result<Vec<(AccountId, u128)>, ErrorSistema> {
-            self.verificar_reportes_view()?;
-            Ok(self._cantidad_ordenes_por_usuario())
+        pub fn marcar_orden_como_enviada(&mut self, id_actual:u128)->Result<(), ErrorSistema> {
+            let caller = self.env().caller();
+            self._marcar_orden_como_enviada(id_actual, caller)
         }
 
-        fn _cantidad_ordenes_por_usuario(&self) -> Vec<(AccountId, u128)> {
-            let mut res: Vec<(AccountId, u128)> = Vec::new();
-            for id in &self.id_usuarios {
-                if let Some(user) = self.usuarios.get(id) {
-                    res.push((id.clone(), user.ordenes.len() as u128));
-                } else {
-                    // Si por alguna razón el id no existe en el mapping, devolver 0.
-                    res.push((id.clone(), 0));
+        fn _marcar_orden_como_enviada(&mut self, id_actual:u128, caller:AccountId)->Result<(), ErrorSistema>{
+            let ahora = self.env().block_timestamp();
+
+            if let Some(mut orden_actual) = self.ordenes.get(id_actual){
+                if orden_actual.id_vendedor != caller {
+                    return Err(ErrorSistema::UsuarioNoAutorizado)
+                } 
+                match &orden_actual.estado {
+                    EstadoOrdenCompra::Pendiente => {
+                        for envio in orden_actual.envios.iter_mut() {
+                            envio.1 = true;
+                        }
+                        orden_actual.cambiar_estado(EstadoOrdenCompra::Enviado, ahora);
+                        self.ordenes.insert(id_actual, &orden_actual);
+                        Ok(())
+                    },
+                    // Se distingue la orden cancelada y la ya enviada del resto de los estados, igual que en `_cancelar_orden`.
+                    EstadoOrdenCompra::Cancelado => Err(ErrorSistema::OrdenCancelada),
+                    EstadoOrdenCompra::Enviado => Err(ErrorSistema::OrdenYaEnviada),
+                    EstadoOrdenCompra::PropuestaPendiente => Err(ErrorSistema::CompradorNoAceptoOrden),
+                    _ => return Err(ErrorSistema::OperacionNoValida),
                 }
+                 
             }
-            res
+            else {
+                return Err(ErrorSistema::IdDeOrdenNoValida);
+            }
+            
         }
-        
-        /// Verifica que el usuario que llama es el mismo que se estableció como ReportesView.
-        /// Si no es el caso, devuelve ErrorSistema::AccesoDenegado.
-        /// Si ReportesView no ha sido establecido devuelve ErrorSistema::ReportesViewNoEstablecido.
-        fn verificar_reportes_view(&self) -> Result<(), ErrorSistema> {
+
+        /// Marca como enviada una línea de una orden pendiente. Solo el vendedor puede hacerlo.
+        /// Cuando todas las líneas fueron enviadas la orden pasa a `Enviado`.
+        #[ink(message)]
+        pub fn marcar_item_enviado(&mut self, id_orden: u128, id_producto: u128) -> Result<(), ErrorSistema> {
             let caller = self.env().caller();
+            self._marcar_item_enviado(id_orden, id_producto, caller)
+        }
 
-            match self.reportes_view {
-                Some(reportes_view) => {
-                    if caller != reportes_view {
-                        return Err(ErrorSistema::AccesoDenegado);
-                    }
-                }
-                None => {
-                    return Err(ErrorSistema::ReportesViewNoEstablecido);
-                }
+        fn _marcar_item_enviado(&mut self, id_orden: u128, id_producto: u128, caller: AccountId) -> Result<(), ErrorSistema> {
+            let ahora = self.env().block_timestamp();
+            let mut orden = self.ordenes.get(id_orden).ok_or(ErrorSistema::IdDeOrdenNoValida)?;
+
+            if orden.id_vendedor != caller {
+                return Err(ErrorSistema::UsuarioNoAutorizado);
+            }
+
+            if orden.estado == EstadoOrdenCompra::PropuestaPendiente {
+                return Err(ErrorSistema::CompradorNoAceptoOrden);
+            }
+            if orden.estado != EstadoOrdenCompra::Pendiente {
+                return Err(ErrorSistema::OperacionNoValida);
+            }
+
+            if !orden.envios.iter().any(|(id, _)| *id == id_producto) {
+                return Err(ErrorSistema::ProductoInvalido);
             }
+
+            // Marco la primera línea de ese producto que todavía no fue enviada.
+            match orden.envios.iter_mut().find(|(id, enviado)| *id == id_producto && !*enviado) {
+                Some(envio) => envio.1 = true,
+                None => return Err(ErrorSistema::OperacionNoValida),
+            }
+
+            if orden.envios.iter().all(|(_, enviado)| *enviado) {
+                orden.cambiar_estado(EstadoOrdenCompra::Enviado, ahora);
+            }
+            self.ordenes.insert(id_orden, &orden);
             Ok(())
         }
-        
-    }
 
-    impl Usuario {
-        pub fn agregar_rol(&mut self, rol: Rol) -> Result<(), ErrorSistema> { 
-            if self.rol == rol || self.rol == Rol::Ambos{
-                return Err(ErrorSistema::RolYaEnUso);
+        /// Devuelve el progreso del envío de una orden como tuplas (id_producto, enviado).
+        /// Retorna un vector vacío si la orden no existe.
+        #[ink(message)]
+        pub fn get_progreso_envio(&self, id_orden: u128) -> Vec<(u128, bool)> {
+            match self.ordenes.get(id_orden) {
+                Some(orden) => orden.envios,
+                None => Vec::new(),
             }
-            // Agrega el nuevo rol al usuario.
-            self.rol = match (self.rol.clone(), rol.clone()) {
-                (Rol::Comprador, Rol::Vendedor) | (Rol::Vendedor, Rol::Comprador) => Rol::Ambos,
-                _ => rol,
-            };
-            Ok(())
         }
 
-        fn calcular_puntaje_como_comprador(&self) -> u8 {
-            if self.calificaciones_comprador.is_empty() {
-                return 0;
+
+        /// Marca una orden como recibida. Solo el comprador puede hacerlo.
+        /// Retorna `Ok(())` si la operación fue exitosa, o un error si no corresponde.
+        ///
+        /// # Ejemplo
+        /// ```
+        ///      sistema.marcar_orden_como_recibida(0);
+        /// ```
+        #[ink(message)]
+        pub fn marcar_orden_como_recibida(&mut self, id_actual:u128)->Result<(), ErrorSistema> {
+            let caller = self.env().caller();
+            self._marcar_orden_como_recibida(id_actual, caller)
+        }
+
+        fn _marcar_orden_como_recibida(&mut self, id_actual:u128, caller:AccountId)->Result<(), ErrorSistema>{
+
+            if let Some(orden_actual) = self.ordenes.get(id_actual){
+                if orden_actual.id_comprador != caller {
+                    return Err(ErrorSistema::UsuarioNoAutorizado)
+                } 
+                match orden_actual.estado.clone() {
+                    EstadoOrdenCompra::Enviado => self.completar_orden(id_actual),
+                    EstadoOrdenCompra::EnDisputa => Err(ErrorSistema::OrdenEnDisputa),
+                    EstadoOrdenCompra::PropuestaPendiente => Err(ErrorSistema::CompradorNoAceptoOrden),
+                    _ => Err(ErrorSistema::OperacionNoValida),
+                }
+                 
             }
-            let sum: u32 = self.calificaciones_comprador.iter().map(|&x| x as u32).sum();
-            let count = self.calificaciones_comprador.len() as u32;
+            else {
+                return Err(ErrorSistema::IdDeOrdenNoValida);
+            }
+            
+        }
 
-            // Usar checked_div para evitar división por cero
-            match sum.checked_div(count) {
-                Some(avg) => avg as u8,
-                None => 0, // En caso de que ocurra un error, aunque no debería
+        /// Confirma la recepción de una línea de una orden enviada. Solo el comprador puede hacerlo.
+        /// Cuando todas las líneas fueron confirmadas la orden pasa a `Recibido`.
+        /// El monto completo sigue en el escrow hasta esa confirmación final: no se libera de forma proporcional.
+        #[ink(message)]
+        pub fn marcar_item_recibido(&mut self, id_orden: u128, id_producto: u128) -> Result<(), ErrorSistema> {
+            let caller = self.env().caller();
+            self._marcar_item_recibido(id_orden, id_producto, caller)
+        }
+
+        fn _marcar_item_recibido(&mut self, id_orden: u128, id_producto: u128, caller: AccountId) -> Result<(), ErrorSistema> {
+            let mut orden = self.ordenes.get(id_orden).ok_or(ErrorSistema::IdDeOrdenNoValida)?;
+
+            if orden.id_comprador != caller {
+                return Err(ErrorSistema::UsuarioNoAutorizado);
+            }
+
+            match orden.estado {
+                EstadoOrdenCompra::Enviado => {},
+                EstadoOrdenCompra::EnDisputa => return Err(ErrorSistema::OrdenEnDisputa),
+                EstadoOrdenCompra::PropuestaPendiente => return Err(ErrorSistema::CompradorNoAceptoOrden),
+                _ => return Err(ErrorSistema::OperacionNoValida),
+            }
+
+            if !orden.recepciones.iter().any(|(id, _)| *id == id_producto) {
+                return Err(ErrorSistema::ProductoInvalido);
+            }
+
+            // Marco la primera línea de ese producto que todavía no fue confirmada.
+            match orden.recepciones.iter_mut().find(|(id, recibido)| *id == id_producto && !*recibido) {
+                Some(recepcion) => recepcion.1 = true,
+                None => return Err(ErrorSistema::OperacionNoValida),
+            }
+
+            let completa = orden.recepciones.iter().all(|(_, recibido)| *recibido);
+            self.ordenes.insert(id_orden, &orden);
+            if completa {
+                self.completar_orden(id_orden)?;
             }
+            Ok(())
         }
 
-        fn calcular_puntaje_como_vendedor(&self) -> u8 {
-            if self.calificaciones_vendedor.is_empty() {
-                return 0;
+        /// Registra las ventas de los productos de una orden y la marca como recibida.
+        fn completar_orden(&mut self, id_orden: u128) -> Result<(), ErrorSistema> {
+            let bloque_actual = self.env().block_number();
+            let ahora = self.env().block_timestamp();
+
+            let mut orden = self.ordenes.get(id_orden).ok_or(ErrorSistema::IdDeOrdenNoValida)?;
+            let lista_productos = orden.lista_productos.clone();
+            let id_vendedor = orden.id_vendedor;
+
+            for (id_producto, cantidad) in &lista_productos {
+                if let Some(mut produc) = self.productos.get(id_producto) {
+                    produc.total_ventas = suma_segura_u32(produc.total_ventas, *cantidad)?;
+                    self.productos.insert(id_producto, &produc);
+                }
+
+                // Registro la primera venta de las publicaciones del vendedor con este producto.
+                for publicacion in self.publicaciones.iter_mut() {
+                    if publicacion.id_publicador == id_vendedor && publicacion.id_producto == *id_producto && publicacion.primera_venta.is_none() {
+                        publicacion.primera_venta = Some(bloque_actual);
+                    }
+                }
             }
-            let sum: u32 = self.calificaciones_vendedor.iter().map(|&x| x as u32).sum();
-            let count = self.calificaciones_vendedor.len() as u32;
 
-            // idem comprador
-            match sum.checked_div(count) {
-                Some(avg) => avg as u8,
-                None => 0,
+            // Al liberarse el escrow se retiene la comisión del marketplace.
+            let comision = self.calcular_comision(orden.monto, self.comision_bps_de_orden(&orden));
+            self.comisiones_acumuladas = suma_segura_u128(self.comisiones_acumuladas, comision as u128)?;
+            orden.comision = comision;
+
+            for recepcion in orden.recepciones.iter_mut() {
+                recepcion.1 = true;
             }
+            orden.cambiar_estado(EstadoOrdenCompra::Recibido, ahora);
+            self.ordenes.insert(id_orden, &orden);
+            Ok(())
         }
-    }
 
-    impl Publicacion {
-        fn actualizar_stock(&mut self, cant:u32)->Result<(),ErrorSistema>{
-            match self.stock.checked_sub(cant){
-                Some(val) => {
-                    self.stock = val;
-                    Ok(())
-                }
-                None => Err(ErrorSistema::PublicacionesLleno)
-            }
-        }
+        /// Permite al comprador abrir una disputa sobre una orden enviada que todavía no recibió.
+        /// Mientras dure la disputa la orden no puede marcarse como recibida ni cancelarse.
+        #[ink(message)]
+        pub fn abrir_disputa(&mut self, id_orden: u128) -> Result<(), ErrorSistema> {
+            let caller = self.env().caller();
+            self._abrir_disputa(id_orden, caller)
+        }
+
+        fn _abrir_disputa(&mut self, id_orden: u128, caller: AccountId) -> Result<(), ErrorSistema> {
+            let ahora = self.env().block_timestamp();
+            let mut orden = self.ordenes.get(id_orden).ok_or(ErrorSistema::IdDeOrdenNoValida)?;
+
+            if orden.id_comprador != caller {
+                return Err(ErrorSistema::UsuarioNoAutorizado);
+            }
+
+            if orden.estado != EstadoOrdenCompra::Enviado {
+                return Err(ErrorSistema::OperacionNoValida);
+            }
+
+            orden.cambiar_estado(EstadoOrdenCompra::EnDisputa, ahora);
+            self.ordenes.insert(id_orden, &orden);
+            Ok(())
+        }
+
+        /// Resuelve una disputa abierta. Sólo el propietario del sistema puede llamar a esta función.
+        /// A favor del comprador la orden se cancela y se le devuelve el monto; a favor del vendedor
+        /// la orden se da por recibida y se le libera el monto.
+        #[ink(message)]
+        pub fn resolver_disputa(&mut self, id_orden: u128, a_favor_comprador: bool) -> Result<(), ErrorSistema> {
+            self.verificar_owner()?;
+            self._resolver_disputa(id_orden, a_favor_comprador)
+        }
+
+        fn _resolver_disputa(&mut self, id_orden: u128, a_favor_comprador: bool) -> Result<(), ErrorSistema> {
+            let ahora = self.env().block_timestamp();
+            let mut orden = self.ordenes.get(id_orden).ok_or(ErrorSistema::IdDeOrdenNoValida)?;
+
+            if orden.estado != EstadoOrdenCompra::EnDisputa {
+                return Err(ErrorSistema::OperacionNoValida);
+            }
+
+            if a_favor_comprador {
+                orden.cambiar_estado(EstadoOrdenCompra::Cancelado, ahora);
+                self.ordenes.insert(id_orden, &orden);
+                Ok(())
+            } else {
+                self.completar_orden(id_orden)
+            }
+        }
+
+
+        /// Solicita la cancelación de una orden. Puede ser solicitada por comprador o vendedor.
+        /// Si ambos la solicitan, la orden se cancela.
+        /// Retorna `Ok(())` si la operación fue exitosa, o un error si no corresponde.
+        ///
+        /// # Ejemplo
+        /// ```
+        ///      sistema.cancelar_orden(0);
+        /// ```
+        #[ink(message)]
+        pub fn cancelar_orden(&mut self, id_actual:u128)->Result<(), ErrorSistema> {
+            let caller = self.env().caller();
+            self._cancelar_orden(id_actual, caller)
+        }
+
+        fn _cancelar_orden(&mut self, id_actual:u128, caller:AccountId) -> Result<(), ErrorSistema> {
+            let ahora = self.env().block_timestamp();
+
+            if let Some(mut orden_actual) = self.ordenes.get(id_actual) {
+
+                if orden_actual.id_vendedor != caller && orden_actual.id_comprador != caller {
+                    return Err(ErrorSistema::UsuarioNoAutorizado);
+                }
+            
+
+                if orden_actual.estado == EstadoOrdenCompra::Cancelado {
+                    return Err(ErrorSistema::OrdenCancelada);
+                }
+
+                if orden_actual.estado == EstadoOrdenCompra::Recibido {
+                    return Err(ErrorSistema::OperacionNoValida);
+                }
+
+                if orden_actual.estado == EstadoOrdenCompra::EnDisputa {
+                    return Err(ErrorSistema::OrdenEnDisputa);
+                }
+
+                // Una propuesta sin aceptar no se cancela de común acuerdo: el comprador la descarta con `rechazar_orden`.
+                if orden_actual.estado == EstadoOrdenCompra::PropuestaPendiente {
+                    return Err(ErrorSistema::CompradorNoAceptoOrden);
+                }
+
+                if let Some(id_anterior) = orden_actual.solicitud_cancelacion {
+                    if id_anterior == caller {
+                        return Err(ErrorSistema::CancelacionYaSolicitada);
+                    }
+                    else {
+                        if id_anterior == orden_actual.id_comprador || id_anterior == orden_actual.id_vendedor{
+                            orden_actual.cambiar_estado(EstadoOrdenCompra::Cancelado, ahora);
+                            self.ordenes.insert(id_actual, &orden_actual);
+                            return Ok(())
+                        }
+                    }
+                }
+                orden_actual.solicitud_cancelacion = Some(caller);
+                self.ordenes.insert(id_actual, &orden_actual);
+                return Ok(())
+                    
+            }
+            else {
+                return Err(ErrorSistema::IdDeOrdenNoValida);
+            }
+            
+        }
+
+
+        /// Permite al comprador cancelar por su cuenta una orden pendiente, mientras el vendedor no haya enviado nada.
+        /// La orden se cancela en el momento, se devuelve el stock y su monto deja de contar en el escrow.
+        /// Las órdenes enviadas siguen requiriendo la confirmación de ambas partes mediante `cancelar_orden`.
+        /// Retorna `OperacionNoValida` si la orden no está pendiente o ya tiene productos enviados.
+        #[ink(message)]
+        pub fn cancelar_como_comprador(&mut self, id_orden: u128) -> Result<(), ErrorSistema> {
+            let caller = self.env().caller();
+            self._cancelar_como_comprador(id_orden, caller)
+        }
+
+        fn _cancelar_como_comprador(&mut self, id_orden: u128, caller: AccountId) -> Result<(), ErrorSistema> {
+            let ahora = self.env().block_timestamp();
+            let mut orden = self.ordenes.get(id_orden).ok_or(ErrorSistema::IdDeOrdenNoValida)?;
+
+            if orden.id_comprador != caller {
+                return Err(ErrorSistema::UsuarioNoAutorizado);
+            }
+
+            if orden.estado == EstadoOrdenCompra::PropuestaPendiente {
+                return Err(ErrorSistema::CompradorNoAceptoOrden);
+            }
+            if orden.estado != EstadoOrdenCompra::Pendiente || orden.envios.iter().any(|(_, enviado)| *enviado) {
+                return Err(ErrorSistema::OperacionNoValida);
+            }
+
+            self.restaurar_stock_de_orden(orden.lista_publicaciones.clone())?;
+
+            orden.cambiar_estado(EstadoOrdenCompra::Cancelado, ahora);
+            self.ordenes.insert(id_orden, &orden);
+            Ok(())
+        }
+
+        /// Permite al comprador dar por expirada una orden que el vendedor no envió a tiempo.
+        /// Si ya pasó `expira_en` y la orden sigue pendiente, se cancela y se devuelve el stock a las publicaciones.
+        /// Retorna `OperacionNoValida` si el plazo todavía no venció o la orden ya no está pendiente.
+        #[ink(message)]
+        pub fn expirar_orden(&mut self, id_orden: u128) -> Result<(), ErrorSistema> {
+            let caller = self.env().caller();
+            self._expirar_orden(id_orden, caller)
+        }
+
+        fn _expirar_orden(&mut self, id_orden: u128, caller: AccountId) -> Result<(), ErrorSistema> {
+            let ahora = self.env().block_timestamp();
+            let mut orden = self.ordenes.get(id_orden).ok_or(ErrorSistema::IdDeOrdenNoValida)?;
+
+            if orden.id_comprador != caller {
+                return Err(ErrorSistema::UsuarioNoAutorizado);
+            }
+
+            if orden.estado != EstadoOrdenCompra::Pendiente || ahora <= orden.expira_en {
+                return Err(ErrorSistema::OperacionNoValida);
+            }
+
+            self.restaurar_stock_de_orden(orden.lista_publicaciones.clone())?;
+
+            orden.cambiar_estado(EstadoOrdenCompra::Cancelado, ahora);
+            self.ordenes.insert(id_orden, &orden);
+            Ok(())
+        }
+
+        /// Saca las reservas vencidas de una publicación y devuelve sus unidades al stock.
+        /// Retorna las reservas que siguen vigentes.
+        fn liberar_reservas_vencidas(&mut self, id_publicacion: u128, ahora: u64) -> Reservas {
+            let reservas = self.reservas.get(id_publicacion).unwrap_or_default();
+            let (vigentes, vencidas): (Vec<_>, Vec<_>) = reservas.into_iter().partition(|(_, _, expira_en)| *expira_en > ahora);
+            if !vencidas.is_empty() {
+                let liberadas = vencidas.iter().fold(0u32, |total, (_, cantidad, _)| total.saturating_add(*cantidad));
+                if let Some(publicacion) = self.buscar_publicacion_mut(id_publicacion) {
+                    publicacion.stock = publicacion.stock.saturating_add(liberadas);
+                }
+                self.reservas.insert(id_publicacion, &vigentes);
+            }
+            vigentes
+        }
+
+        /// Libera las reservas vencidas de las publicaciones de una compra y devuelve al stock las reservas
+        /// vigentes del comprador, para que la compra las consuma.
+        /// Retorna las reservas tomadas, por si hay que devolverlas.
+        fn tomar_reservas(&mut self, lista_publicaciones_con_cantidades: &[(u128, u32)], comprador: AccountId, ahora: u64) -> Vec<(u128, u32, u64)> {
+            let mut tomadas = Vec::new();
+            for (id_publicacion, _) in lista_publicaciones_con_cantidades {
+                let mut reservas = self.liberar_reservas_vencidas(*id_publicacion, ahora);
+                if let Some(posicion) = reservas.iter().position(|(id, _, _)| *id == comprador) {
+                    let (_, cantidad, expira_en) = reservas.remove(posicion);
+                    if let Some(publicacion) = self.buscar_publicacion_mut(*id_publicacion) {
+                        publicacion.stock = publicacion.stock.saturating_add(cantidad);
+                    }
+                    self.reservas.insert(*id_publicacion, &reservas);
+                    tomadas.push((*id_publicacion, cantidad, expira_en));
+                }
+            }
+            tomadas
+        }
+
+        /// Vuelve a reservar para el comprador las unidades que `tomar_reservas` había devuelto al stock.
+        fn devolver_reservas(&mut self, comprador: AccountId, tomadas: Vec<(u128, u32, u64)>) {
+            for (id_publicacion, cantidad, expira_en) in tomadas {
+                if let Some(publicacion) = self.buscar_publicacion_mut(id_publicacion) {
+                    publicacion.stock = publicacion.stock.saturating_sub(cantidad);
+                }
+                let mut reservas = self.reservas.get(id_publicacion).unwrap_or_default();
+                reservas.push((comprador, cantidad, expira_en));
+                self.reservas.insert(id_publicacion, &reservas);
+            }
+        }
+
+        /// Devuelve a cada publicación el stock que se le había descontado en una orden.
+        fn restaurar_stock_de_orden(&mut self, lista_publicaciones_con_cantidades: Vec<(u128, u32)>) -> Result<(), ErrorSistema> {
+            for (id_publi, cant_productos) in lista_publicaciones_con_cantidades {
+                // Si se había desactivado por quedarse sin stock, vuelve a estar activa mientras el vendedor no esté en su límite.
+                let puede_activarse = match self.buscar_publicacion(id_publi) {
+                    Some(publicacion) => publicacion.activa || self.puede_activar_publicacion(publicacion.id_publicador),
+                    None => false,
+                };
+                if let Some(publicacion) = self.buscar_publicacion_mut(id_publi) {
+                    publicacion.stock = publicacion.stock.checked_add(cant_productos).ok_or(ErrorSistema::StockOverflow)?;
+                    if publicacion.stock > 0 && puede_activarse {
+                        publicacion.activa = true;
+                    }
+                }
+            }
+            Ok(())
+        }
+
+        /// Permite a los usuarios puntuar al comprador o al vendedor después de finalizar una orden
+        /// Solo los usuarios involucrados en la orden pueden interactuar
+        /// Una orden cuyo comprador y vendedor coinciden no puede puntuarse
+        /// Solo se puede puntuar una vez por usuario y por orden
+        /// La puntuación es entre 1 y 5
+        #[ink(message)]
+        pub fn puntuar_usuario_por_orden(&mut self, id_orden:u128, puntuacion:u8) -> Result<(), ErrorSistema> {
+            let caller = self.env().caller();
+            self._puntuar_usuario_por_orden(id_orden, puntuacion, caller)
+        }
+
+        fn _puntuar_usuario_por_orden(&mut self, id_orden:u128, puntuacion:u8, caller:AccountId)->Result<(), ErrorSistema>{
+            if puntuacion > 5 || puntuacion < 1 {
+                return Err(ErrorSistema::PuntuacionNoValida);
+            }
+            else {
+                if let Some(mut orden) = self.ordenes.get(id_orden) {
+                    if orden.estado != EstadoOrdenCompra::Recibido {
+                        return Err(ErrorSistema::NoPuedePuntuarOrdenSinRecibir);
+                    }
+                    else {
+
+                        // Una orden en la que el mismo usuario es comprador y vendedor no puede puntuarse,
+                        // ya que permitiría auto-calificarse desde ambos lados.
+                        if orden.id_comprador == orden.id_vendedor {
+                            return Err(ErrorSistema::OperacionNoValida);
+                        }
+
+                        if orden.id_comprador == caller {
+                            if orden.puntuado_por_comprador {
+                                return Err(ErrorSistema::OrdenYaPuntuada);
+                            }
+                            else {
+                                let mut user = self.usuarios.get(orden.id_vendedor).unwrap();
+                                user.calificaciones_vendedor.push(puntuacion);
+                                user.momentos_calificaciones_vendedor.push(orden.actualizada_en);
+                                self.usuarios.insert(&orden.id_vendedor, &user);
+                                orden.puntuado_por_comprador = true;
+
+
+                                // Este for actualiza las puntuaciones por prodcuto de la lista
+                                for (id_producto, cantidad) in &orden.lista_productos {
+                                    if let Some(mut producto_actual) = self.productos.get(id_producto) {
+                                        producto_actual.puntuaciones.push(puntuacion);
+                                        self.productos.insert(id_producto, &producto_actual);
+                                    }
+                
+                                }
+
+                                self.ordenes.insert(id_orden, &orden);
+                                self.env().emit_event(Calificacion {
+                                    calificado: orden.id_vendedor,
+                                    calificador: caller,
+                                    id_orden,
+                                    puntuacion,
+                                    es_vendedor: true,
+                                });
+                                return Ok(());
+                            }
+                        }
+                        else 
+                            if orden.id_vendedor == caller {
+                                if orden.puntuado_por_vendedor {
+                                    return Err(ErrorSistema::OrdenYaPuntuada);
+                                }
+                                else {
+                                    let mut user = self.usuarios.get(orden.id_comprador).unwrap();
+                                    user.calificaciones_comprador.push(puntuacion);
+                                    self.usuarios.insert(&orden.id_comprador, &user);
+                                    orden.puntuado_por_vendedor = true;
+                                    self.ordenes.insert(id_orden, &orden);
+                                    self.env().emit_event(Calificacion {
+                                        calificado: orden.id_comprador,
+                                        calificador: caller,
+                                        id_orden,
+                                        puntuacion,
+                                        es_vendedor: false,
+                                    });
+                                    return Ok(());
+                                }
+                            }
+
+                            else {
+                                return Err(ErrorSistema::UsuarioNoAutorizado);
+                            }
+                        
+                    }
+                }
+                else {
+                    return Err(ErrorSistema::IdDeOrdenNoValida);
+                }
+            }
+
+        }
+
+        /// Permite al comprador de una orden recibida dejar una reseña sobre uno de sus productos.
+        /// Sólo se puede dejar una reseña por producto, con una puntuación entre 1 y 5.
+        #[ink(message)]
+        pub fn dejar_resena(&mut self, id_orden: u128, id_producto: u128, texto: String, puntuacion: u8) -> Result<(), ErrorSistema> {
+            let caller = self.env().caller();
+            self._dejar_resena(id_orden, id_producto, texto, puntuacion, caller)
+        }
+
+        fn _dejar_resena(&mut self, id_orden: u128, id_producto: u128, texto: String, puntuacion: u8, caller: AccountId) -> Result<(), ErrorSistema> {
+            if !(1..=5).contains(&puntuacion) {
+                return Err(ErrorSistema::PuntuacionNoValida);
+            }
+
+            if texto.len() > MAX_LARGO_RESENA {
+                return Err(ErrorSistema::ResenaDemasiadoLarga);
+            }
+
+            let orden = self.ordenes.get(id_orden).ok_or(ErrorSistema::IdDeOrdenNoValida)?;
+
+            if orden.id_comprador != caller {
+                return Err(ErrorSistema::UsuarioNoAutorizado);
+            }
+
+            if orden.estado != EstadoOrdenCompra::Recibido {
+                return Err(ErrorSistema::NoPuedePuntuarOrdenSinRecibir);
+            }
+
+            if !orden.lista_productos.iter().any(|(id, _)| *id == id_producto) {
+                return Err(ErrorSistema::ProductoInvalido);
+            }
+
+            let mut resenas = self.resenas.get(id_producto).unwrap_or_default();
+            if resenas.iter().any(|resena| resena.autor == caller) {
+                return Err(ErrorSistema::ResenaYaRealizada);
+            }
+
+            resenas.push(ResenaProducto { autor: caller, id_producto, texto, puntuacion });
+            self.resenas.insert(id_producto, &resenas);
+            Ok(())
+        }
+
+        /// Devuelve las reseñas que recibió un producto.
+        #[ink(message)]
+        pub fn ver_resenas(&self, id_producto: u128) -> Vec<ResenaProducto> {
+            self.resenas.get(id_producto).unwrap_or_default()
+        }
+
+        #[ink(message)]
+        pub fn obtener_puntuacion_de_comprador(&self, id_usuario:AccountId)->Result<u8, ErrorSistema>{
+            if let Some(user) = self.usuarios.get(id_usuario) {
+                return Ok(user.calcular_puntaje_como_comprador());
+            }
+            else {
+                return Err(ErrorSistema::UsuarioNoExiste);
+            }
+        }
+
+        #[ink(message)]
+        pub fn obtener_puntuacion_de_vendedor(&self, id_usuario:AccountId)->Result<u8, ErrorSistema>{
+            if let Some(user) = self.usuarios.get(id_usuario) {
+                return Ok(user.calcular_puntaje_como_vendedor());
+            }
+            else {
+                return Err(ErrorSistema::UsuarioNoExiste);
+            }
+        }
+
+
+        /// Devuelve el promedio redondeado de las calificaciones recibidas por un vendedor,
+        /// o `None` si todavía no tiene calificaciones.
+        #[ink(message)]
+        pub fn promedio_calificacion_vendedor(&self, vendedor: AccountId) -> Result<Option<u8>, ErrorSistema> {
+            if !self._es_vendedor(vendedor)? {
+                return Err(ErrorSistema::UsuarioNoEsVendedor);
+            }
+            let user = self.usuarios.get(vendedor).ok_or(ErrorSistema::UsuarioNoExiste)?;
+            Ok(Usuario::promedio_redondeado(&user.calificaciones_vendedor))
+        }
+
+        /// Devuelve el promedio de las calificaciones de un vendedor ponderado por antigüedad,
+        /// o `None` si todavía no tiene calificaciones.
+        ///
+        /// Cada calificación pesa `1 + (PESO_MAXIMO_REPUTACION - 1) * (VENTANA_REPUTACION - edad) / VENTANA_REPUTACION`,
+        /// donde `edad` es el tiempo transcurrido desde que se recibió la orden (acotado a la ventana).
+        /// Así una calificación reciente pesa 10 y una de 90 días o más pesa 1. El resultado se redondea
+        /// igual que `promedio_calificacion_vendedor`.
+        #[ink(message)]
+        pub fn promedio_ponderado_vendedor(&self, vendedor: AccountId) -> Result<Option<u8>, ErrorSistema> {
+            if !self._es_vendedor(vendedor)? {
+                return Err(ErrorSistema::UsuarioNoEsVendedor);
+            }
+            let user = self.usuarios.get(vendedor).ok_or(ErrorSistema::UsuarioNoExiste)?;
+            Ok(user.promedio_ponderado_vendedor(self.env().block_timestamp()))
+        }
+
+        /// Devuelve en una sola llamada los promedios de calificación y la cantidad de órdenes recibidas
+        /// de un usuario, tanto como vendedor como comprador. Incluye las órdenes archivadas.
+        #[ink(message)]
+        pub fn ver_reputacion(&self, usuario: AccountId) -> Result<Reputacion, ErrorSistema> {
+            let user = self.usuarios.get(usuario).ok_or(ErrorSistema::UsuarioNoExiste)?;
+
+            let mut n_ventas: u32 = 0;
+            let mut n_compras: u32 = 0;
+            for id in user.ordenes.iter() {
+                let orden = match self.ordenes.get(id).or_else(|| self.ordenes_archivadas.get(id)) {
+                    Some(orden) => orden,
+                    None => continue,
+                };
+                if orden.estado != EstadoOrdenCompra::Recibido {
+                    continue;
+                }
+                if orden.id_vendedor == usuario {
+                    n_ventas = n_ventas.saturating_add(1);
+                }
+                if orden.id_comprador == usuario {
+                    n_compras = n_compras.saturating_add(1);
+                }
+            }
+
+            Ok(Reputacion {
+                prom_vendedor: Usuario::promedio_redondeado(&user.calificaciones_vendedor),
+                n_ventas,
+                prom_comprador: Usuario::promedio_redondeado(&user.calificaciones_comprador),
+                n_compras,
+            })
+        }
+
+        /// Devuelve la cantidad de órdenes del vendedor que llama en cada estado, como
+        /// (Pendiente, Enviado, Recibido, Cancelado). Las órdenes en disputa, las propuestas sin aceptar y las archivadas no se cuentan.
+        #[ink(message)]
+        pub fn contar_ventas_por_estado(&self) -> (u32, u32, u32, u32) {
+            let caller = self.env().caller();
+            self._contar_ventas_por_estado(caller)
+        }
+
+        fn _contar_ventas_por_estado(&self, caller: AccountId) -> (u32, u32, u32, u32) {
+            let mut conteo = (0u32, 0u32, 0u32, 0u32);
+            for orden in self.iter_ordenes().filter(|orden| orden.id_vendedor == caller) {
+                match orden.estado {
+                    EstadoOrdenCompra::Pendiente => conteo.0 = conteo.0.saturating_add(1),
+                    EstadoOrdenCompra::Enviado => conteo.1 = conteo.1.saturating_add(1),
+                    EstadoOrdenCompra::Recibido => conteo.2 = conteo.2.saturating_add(1),
+                    EstadoOrdenCompra::Cancelado => conteo.3 = conteo.3.saturating_add(1),
+                    EstadoOrdenCompra::EnDisputa | EstadoOrdenCompra::PropuestaPendiente => {}
+                }
+            }
+            conteo
+        }
+
+        /// Devuelve las órdenes recibidas del vendedor que llama cuya última actualización (`actualizada_en`)
+        /// cae dentro de `[desde, hasta]`. Incluye las órdenes archivadas.
+        /// Retorna `RangoInvalido` si `desde > hasta`.
+        ///
+        /// # Ejemplo
+        /// ```
+        ///     let ventas = sistema.ventas_entre(0, 1000)?;
+        /// ```
+        #[ink(message)]
+        pub fn ventas_entre(&self, desde: u64, hasta: u64) -> Result<Vec<OrdenCompra>, ErrorSistema> {
+            let caller = self.env().caller();
+            self._ventas_entre(desde, hasta, caller)
+        }
+
+        fn _ventas_entre(&self, desde: u64, hasta: u64, caller: AccountId) -> Result<Vec<OrdenCompra>, ErrorSistema> {
+            if desde > hasta {
+                return Err(ErrorSistema::RangoInvalido);
+            }
+            if !self._es_vendedor(caller)? {
+                return Err(ErrorSistema::UsuarioNoEsVendedor);
+            }
+            let user = self.usuarios.get(caller).ok_or(ErrorSistema::UsuarioNoExiste)?;
+
+            Ok(user.ordenes.iter()
+                .filter_map(|id| self.ordenes.get(id).or_else(|| self.ordenes_archivadas.get(id)))
+                .filter(|orden| {
+                    orden.id_vendedor == caller
+                        && orden.estado == EstadoOrdenCompra::Recibido
+                        && orden.actualizada_en >= desde
+                        && orden.actualizada_en <= hasta
+                })
+                .collect())
+        }
+
+        /// Devuelve en una sola llamada el resumen de la tienda de un vendedor: su nombre, su calificación,
+        /// sus publicaciones activas y sus ventas.
+        /// Retorna `UsuarioNoEsVendedor` si el usuario no vende.
+        #[ink(message)]
+        pub fn storefront(&self, vendedor: AccountId) -> Result<Storefront, ErrorSistema> {
+            let prom_calificacion = self.promedio_calificacion_vendedor(vendedor)?;
+            let reputacion = self.ver_reputacion(vendedor)?;
+            let user = self.usuarios.get(vendedor).ok_or(ErrorSistema::UsuarioNoExiste)?;
+
+            Ok(Storefront {
+                nombre: user.nombre,
+                prom_calificacion,
+                n_publicaciones_activas: self.contar_publicaciones_activas(vendedor),
+                n_ventas: reputacion.n_ventas,
+            })
+        }
+
+        /// Devuelve el promedio redondeado de las calificaciones recibidas por un comprador,
+        /// o `None` si todavía no tiene calificaciones.
+        #[ink(message)]
+        pub fn promedio_calificacion_comprador(&self, comprador: AccountId) -> Result<Option<u8>, ErrorSistema> {
+            if !self._es_comprador(comprador)? {
+                return Err(ErrorSistema::UsuarioNoEsComprador);
+            }
+            let user = self.usuarios.get(comprador).ok_or(ErrorSistema::UsuarioNoExiste)?;
+            Ok(Usuario::promedio_redondeado(&user.calificaciones_comprador))
+        }
+
+
+        /// Devuelve la lista de todas las publicaciones existentes en el sistema.
+        ///
+        /// # Ejemplo
+        /// ```
+        ///      let publicaciones = sistema.get_publicaciones();
+        /// ```
+        #[ink(message)]
+        pub fn get_publicaciones(&self)->Vec<Publicacion>{
+            self.publicaciones.clone()
+        }
+
+
+        /// Devuelve las publicaciones activas que tienen stock disponible.
+        /// Una publicación puede seguir activa con stock 0 mientras otro comprador tiene reservadas todas sus unidades.
+        ///
+        /// # Ejemplo
+        /// ```
+        ///      let disponibles = sistema.get_publicaciones_con_stock();
+        /// ```
+        #[ink(message)]
+        pub fn get_publicaciones_con_stock(&self) -> Vec<Publicacion> {
+            self.publicaciones.iter()
+                .filter(|publicacion| publicacion.activa && publicacion.stock > 0)
+                .cloned()
+                .collect()
+        }
+
+
+        /// Devuelve las publicaciones cuyo producto tiene la subcategoría indicada.
+        /// La comparación no distingue mayúsculas de minúsculas.
+        ///
+        /// # Ejemplo
+        /// ```
+        ///      let notebooks = sistema.get_publicaciones_por_subcategoria("Notebooks".to_string());
+        /// ```
+        #[ink(message)]
+        pub fn get_publicaciones_por_subcategoria(&self, subcategoria: String) -> Vec<Publicacion> {
+            let buscada = match Self::normalizar_subcategoria(&subcategoria) {
+                Ok(sub) => sub,
+                Err(_) => return Vec::new(),
+            };
+
+            self.publicaciones.iter()
+                .filter(|publicacion| {
+                    self.productos.get(publicacion.id_producto)
+                        .is_some_and(|producto| producto.subcategoria.as_ref() == Some(&buscada))
+                })
+                .cloned()
+                .collect()
+        }
+
+
+        /// Devuelve las publicaciones activas de un vendedor, para que cualquier usuario pueda ver su tienda.
+        ///
+        /// # Ejemplo
+        /// ```
+        ///      let tienda = sistema.get_publicaciones_de(vendedor);
+        /// ```
+        #[ink(message)]
+        pub fn get_publicaciones_de(&self, vendedor: AccountId) -> Vec<Publicacion> {
+            self.publicaciones.iter()
+                .filter(|publicacion| publicacion.id_publicador == vendedor && publicacion.activa)
+                .cloned()
+                .collect()
+        }
+
+
+        /// Devuelve una página del catálogo: las publicaciones activas de todos los vendedores, cada una junto a su producto.
+        /// Las publicaciones cuyo producto ya no existe se omiten. `limit` se recorta a `MAX_TAMANO_PAGINA`.
+        ///
+        /// # Ejemplo
+        /// ```
+        ///      let primera_pagina = sistema.get_catalogo(0, 20);
+        /// ```
+        #[ink(message)]
+        pub fn get_catalogo(&self, offset: u32, limit: u32) -> Vec<(Publicacion, Producto)> {
+            let limite = limit.min(MAX_TAMANO_PAGINA) as usize;
+            self.publicaciones.iter()
+                .filter(|publicacion| publicacion.activa)
+                .filter_map(|publicacion| {
+                    self.productos.get(publicacion.id_producto)
+                        .map(|producto| (publicacion.clone(), producto))
+                })
+                .skip(offset as usize)
+                .take(limite)
+                .collect()
+        }
+
+
+        /// Devuelve la lista de todas las publicaciones existentes en el sistema del vendedor que la llama.
+        ///
+        /// # Ejemplo
+        /// ```
+        ///      let mis_publicaciones = sistema.get_publicaciones_propias();
+        /// ```
+        #[ink(message)]
+        pub fn get_publicaciones_propias(&self)-> Result<Vec<Publicacion>, ErrorSistema>{
+            let caller = self.env().caller();
+            self._get_publicaciones_propias(caller)
+        }
+
+        fn _get_publicaciones_propias(&self, caller:AccountId)-> Result<Vec<Publicacion>, ErrorSistema> {
+            let mut publicaciones_propias = Vec::<Publicacion>::new();
+            // Verifico si el usuario existe.
+
+            if let Err(e) = self._existe_usuario(caller) {
+                return Err(ErrorSistema::UsuarioNoExiste); // Si no existe, retorno un vector vacío.
+            } else {
+                if !self.es_vendedor().unwrap_or(false) {
+                    return Err(ErrorSistema::UsuarioNoEsVendedor); // Si no es vendedor, retorno un vector vacío.
+                }
+            }
+
+            // Si el usuario existe y es vendedor, busco sus publicaciones.
+            // Itero sobre las publicaciones del usuario y las agrego al vector de publicaciones propias.
+            // Si el usuario no tiene publicaciones, el vector quedará vacío.
+            let mut publicaciones_propias = Vec::new();
+            for publicacion in self.publicaciones.iter() {
+                if publicacion.id_publicador == caller {
+                    publicaciones_propias.push(publicacion.clone());
+                }
+            }
+
+            Ok(publicaciones_propias)
+        }
+
+        /// Devuelve una publicación junto con el puntaje promedio de su vendedor.
+        /// Retorna `PublicacionNoValida` si no existe una publicación con ese id.
+        #[ink(message)]
+        pub fn publicacion_con_reputacion(&self, id_publicacion: u128) -> Result<(Publicacion, u8), ErrorSistema> {
+            let publicacion = match self.buscar_publicacion(id_publicacion) {
+                Some(publi) => publi.clone(),
+                None => return Err(ErrorSistema::PublicacionNoValida),
+            };
+
+            let reputacion = match self.usuarios.get(publicacion.id_publicador) {
+                Some(vendedor) => vendedor.calcular_puntaje_como_vendedor(),
+                None => 0,
+            };
+
+            Ok((publicacion, reputacion))
+        }
+
+        /// Devuelve todas las categorías disponibles, en el orden en que están declaradas.
+        /// Permite que los front ends armen los filtros sin tener la lista escrita a mano.
+        #[ink(message)]
+        pub fn get_categorias(&self) -> Vec<Categoria> {
+            vec![
+                Categoria::Limpieza,
+                Categoria::Tecnologia,
+                Categoria::Musica,
+                Categoria::Ropa,
+                Categoria::Calzado,
+                Categoria::Otros,
+            ]
+        }
+
+        /// Devuelve, para el vendedor que llama, el stock total de sus publicaciones activas agrupado por la categoría del producto.
+        /// Sólo se incluyen las categorías que tienen stock.
+        #[ink(message)]
+        pub fn stock_por_categoria(&self) -> Vec<(Categoria, u32)> {
+            let caller = self.env().caller();
+            self._stock_por_categoria(caller)
+        }
+
+        fn _stock_por_categoria(&self, caller: AccountId) -> Vec<(Categoria, u32)> {
+            let mut stock_por_categoria: BTreeMap<Categoria, u32> = BTreeMap::new();
+            for publicacion in self.publicaciones.iter().filter(|p| p.id_publicador == caller && p.activa && p.stock > 0) {
+                if let Some(producto) = self.productos.get(publicacion.id_producto) {
+                    let total = stock_por_categoria.entry(producto.categoria).or_insert(0);
+                    *total = total.saturating_add(publicacion.stock);
+                }
+            }
+            stock_por_categoria.into_iter().collect()
+        }
+
+        /// Devuelve los productos del vendedor que llama ordenados de forma descendente por unidades vendidas
+        /// en las órdenes generadas desde el timestamp `desde`, sin contar las canceladas ni las propuestas sin aceptar.
+        /// Sólo se incluyen los productos que tuvieron ventas en ese período.
+        /// Las órdenes archivadas con `archivar_orden` no se cuentan.
+        #[ink(message)]
+        pub fn mis_productos_tendencia(&self, desde: Timestamp) -> Result<Vec<(u128, u32)>, ErrorSistema> {
+            let caller = self.env().caller();
+            self._mis_productos_tendencia(desde, caller)
+        }
+
+        fn _mis_productos_tendencia(&self, desde: Timestamp, caller: AccountId) -> Result<Vec<(u128, u32)>, ErrorSistema> {
+            if !self._es_vendedor(caller)? {
+                return Err(ErrorSistema::UsuarioNoEsVendedor);
+            }
+
+            let mut ventas: BTreeMap<u128, u32> = BTreeMap::new();
+            for orden in self.iter_ordenes().filter(|o| {
+                o.id_vendedor == caller
+                    && o.creada_en >= desde
+                    && !matches!(o.estado, EstadoOrdenCompra::Cancelado | EstadoOrdenCompra::PropuestaPendiente)
+            }) {
+                for (id_producto, cantidad) in &orden.lista_productos {
+                    let entry = ventas.entry(*id_producto).or_insert(0);
+                    *entry = suma_segura_u32(*entry, *cantidad)?;
+                }
+            }
+
+            let mut tendencia: Vec<(u128, u32)> = ventas.into_iter().collect();
+            tendencia.sort_by_key(|(_, unidades)| core::cmp::Reverse(*unidades));
+
+            Ok(tendencia)
+        }
+
+        /// Devuelve los productos creados por el vendedor que llama, junto con su id, estén publicados o no.
+        #[ink(message)]
+        pub fn get_mis_productos(&self) -> Result<Vec<(u128, Producto)>, ErrorSistema> {
+            let caller = self.env().caller();
+            self._get_mis_productos(caller)
+        }
+
+        fn _get_mis_productos(&self, caller: AccountId) -> Result<Vec<(u128, Producto)>, ErrorSistema> {
+            if !self._es_vendedor(caller)? {
+                return Err(ErrorSistema::UsuarioNoEsVendedor);
+            }
+            let user = self.usuarios.get(caller).ok_or(ErrorSistema::UsuarioNoExiste)?;
+            Ok(user.productos.iter()
+                .filter_map(|id| self.productos.get(id).map(|producto| (*id, producto)))
+                .collect())
+        }
+
+        /// Devuelve, para el vendedor que llama, los compradores de un producto y la cantidad que compró cada uno
+        /// en sus órdenes recibidas. Sólo puede consultarlo el vendedor que creó el producto; a cualquier otro
+        /// usuario le retorna `OperacionNoValida`.
+        /// Las órdenes archivadas con `archivar_orden` no se cuentan.
+        ///
+        /// # Ejemplo
+        /// ```
+        ///     let compradores = sistema.ver_compras_de_producto(0)?;
+        /// ```
+        #[ink(message)]
+        pub fn ver_compras_de_producto(&self, id_producto: u128) -> Result<Vec<(AccountId, u32)>, ErrorSistema> {
+            let caller = self.env().caller();
+            self._ver_compras_de_producto(id_producto, caller)
+        }
+
+        fn _ver_compras_de_producto(&self, id_producto: u128, caller: AccountId) -> Result<Vec<(AccountId, u32)>, ErrorSistema> {
+            if !self.productos.contains(id_producto) {
+                return Err(ErrorSistema::ProductoInvalido);
+            }
+            let usuario = self.usuarios.get(caller).ok_or(ErrorSistema::UsuarioNoExiste)?;
+            if !usuario.productos.contains(&id_producto) {
+                return Err(ErrorSistema::OperacionNoValida);
+            }
+
+            let mut compras: Vec<(AccountId, u32)> = Vec::new();
+            for orden in self.iter_ordenes().filter(|o| o.id_vendedor == caller && o.estado == EstadoOrdenCompra::Recibido) {
+                for (id, cantidad) in &orden.lista_productos {
+                    if *id != id_producto {
+                        continue;
+                    }
+                    match compras.iter_mut().find(|(comprador, _)| *comprador == orden.id_comprador) {
+                        Some(compra) => compra.1 = suma_segura_u32(compra.1, *cantidad)?,
+                        None => compras.push((orden.id_comprador, *cantidad)),
+                    }
+                }
+            }
+
+            Ok(compras)
+        }
+
+        /// Bloquea a un comprador para que no pueda comprarle ni reservarle stock al vendedor que llama.
+        /// Bloquear a alguien que ya estaba bloqueado no tiene efecto.
+        #[ink(message)]
+        pub fn bloquear_comprador(&mut self, comprador: AccountId) -> Result<(), ErrorSistema> {
+            let caller = self.env().caller();
+            self._bloquear_comprador(comprador, caller)
+        }
+
+        fn _bloquear_comprador(&mut self, comprador: AccountId, caller: AccountId) -> Result<(), ErrorSistema> {
+            if !self._es_vendedor(caller)? {
+                return Err(ErrorSistema::UsuarioNoEsVendedor);
+            }
+            if comprador == caller {
+                return Err(ErrorSistema::OperacionNoValida);
+            }
+
+            let mut bloqueados = self.bloqueados.get(caller).unwrap_or_default();
+            if !bloqueados.contains(&comprador) {
+                bloqueados.push(comprador);
+                self.bloqueados.insert(caller, &bloqueados);
+            }
+            Ok(())
+        }
+
+        /// Desbloquea a un comprador bloqueado por el vendedor que llama.
+        /// Desbloquear a alguien que no estaba bloqueado no tiene efecto.
+        #[ink(message)]
+        pub fn desbloquear_comprador(&mut self, comprador: AccountId) -> Result<(), ErrorSistema> {
+            let caller = self.env().caller();
+            self._desbloquear_comprador(comprador, caller)
+        }
+
+        fn _desbloquear_comprador(&mut self, comprador: AccountId, caller: AccountId) -> Result<(), ErrorSistema> {
+            if !self._es_vendedor(caller)? {
+                return Err(ErrorSistema::UsuarioNoEsVendedor);
+            }
+
+            let mut bloqueados = self.bloqueados.get(caller).unwrap_or_default();
+            bloqueados.retain(|id| *id != comprador);
+            self.bloqueados.insert(caller, &bloqueados);
+            Ok(())
+        }
+
+        fn esta_bloqueado(&self, vendedor: AccountId, comprador: AccountId) -> bool {
+            self.bloqueados.get(vendedor).is_some_and(|bloqueados| bloqueados.contains(&comprador))
+        }
+
+        /// Configura el monto mínimo que tiene que alcanzar una orden al vendedor que llama.
+        /// Con `None` se quita el mínimo.
+        #[ink(message)]
+        pub fn set_monto_minimo(&mut self, monto_minimo: Option<u32>) -> Result<(), ErrorSistema> {
+            let caller = self.env().caller();
+            self._set_monto_minimo(monto_minimo, caller)
+        }
+
+        fn _set_monto_minimo(&mut self, monto_minimo: Option<u32>, caller: AccountId) -> Result<(), ErrorSistema> {
+            if !self._es_vendedor(caller)? {
+                return Err(ErrorSistema::UsuarioNoEsVendedor);
+            }
+            match monto_minimo {
+                Some(monto) => { self.montos_minimos.insert(caller, &monto); }
+                None => self.montos_minimos.remove(caller),
+            }
+            Ok(())
+        }
+
+        /// Devuelve una orden de compra por su id. Sólo el comprador o el vendedor de la orden pueden consultarla.
+        #[ink(message)]
+        pub fn get_orden(&self, id_orden: u128) -> Result<OrdenCompra, ErrorSistema> {
+            let caller = self.env().caller();
+            self._get_orden(id_orden, caller)
+        }
+
+        fn _get_orden(&self, id_orden: u128, caller: AccountId) -> Result<OrdenCompra, ErrorSistema> {
+            let orden = self.ordenes.get(id_orden)
+                .or_else(|| self.ordenes_archivadas.get(id_orden))
+                .ok_or(ErrorSistema::IdDeOrdenNoValida)?;
+
+            if orden.id_comprador != caller && orden.id_vendedor != caller {
+                return Err(ErrorSistema::UsuarioNoAutorizado);
+            }
+
+            Ok(orden.clone())
+        }
+
+        /// Devuelve sólo el estado de una orden, para las interfaces que consultan periódicamente.
+        /// Sólo el comprador o el vendedor de la orden pueden consultarlo.
+        #[ink(message)]
+        pub fn get_estado_orden(&self, id_orden: u128) -> Result<EstadoOrdenCompra, ErrorSistema> {
+            let caller = self.env().caller();
+            Ok(self._get_orden(id_orden, caller)?.estado)
+        }
+
+        /// Devuelve el estado de varias órdenes en una sola llamada, con un resultado por id.
+        /// Cada id respeta la misma autorización que `get_estado_orden`.
+        /// Sólo se resuelven los primeros `MAX_IDS_POR_CONSULTA` ids.
+        #[ink(message)]
+        pub fn get_estados_ordenes(&self, ids: Vec<u128>) -> Vec<(u128, Result<EstadoOrdenCompra, ErrorSistema>)> {
+            let caller = self.env().caller();
+            ids.into_iter()
+                .take(MAX_IDS_POR_CONSULTA)
+                .map(|id| (id, self._get_orden(id, caller).map(|orden| orden.estado)))
+                .collect()
+        }
+
+        /// Devuelve el historial de estados de una orden junto con el timestamp de cada transición.
+        /// Sólo el comprador o el vendedor de la orden pueden consultarlo.
+        #[ink(message)]
+        pub fn get_historial_estados(&self, id_orden: u128) -> Result<Vec<(EstadoOrdenCompra, u64)>, ErrorSistema> {
+            let caller = self.env().caller();
+            Ok(self._get_orden(id_orden, caller)?.historial)
+        }
+
+        /// Devuelve la lista de órdenes asociadas al usuario que llama.
+        ///
+        /// # Ejemplo
+        /// ```
+        ///   let mis_ordenes = sistema.ver_mis_ordenes();
+        /// ```
+        #[ink(message)]
+        pub fn ver_mis_ordenes(&self)->Vec<OrdenCompra>{
+            let caller = self.env().caller();
+            self._ver_mis_ordenes(caller)
+        }
+
+        /// Devuelve una página de las órdenes del usuario que llama, en el mismo orden que `ver_mis_ordenes`.
+        /// `limit` se recorta a `MAX_TAMANO_PAGINA`; si `offset` supera la cantidad de órdenes se devuelve un vector vacío.
+        ///
+        /// # Ejemplo
+        /// ```
+        ///   let segunda_pagina = sistema.ver_mis_ordenes_paginado(10, 10);
+        /// ```
+        #[ink(message)]
+        pub fn ver_mis_ordenes_paginado(&self, offset: u32, limit: u32) -> Vec<OrdenCompra> {
+            let caller = self.env().caller();
+            self._ver_mis_ordenes_paginado(offset, limit, caller)
+        }
+
+        fn _ver_mis_ordenes_paginado(&self, offset: u32, limit: u32, caller: AccountId) -> Vec<OrdenCompra> {
+            let limite = limit.min(MAX_TAMANO_PAGINA) as usize;
+            match self.usuarios.get(caller) {
+                Some(user) => user.ordenes.iter()
+                    .filter_map(|id| self.ordenes.get(id))
+                    .skip(offset as usize)
+                    .take(limite)
+                    .collect(),
+                None => Vec::new(),
+            }
+        }
+
+        fn _ver_mis_ordenes(&self, caller:AccountId)->Vec<OrdenCompra>{
+            let mut mis_ordenes = Vec::new();
+            if let Some(user) = self.usuarios.get(caller){
+                for id in user.ordenes {
+                    if let Some(orden) = self.ordenes.get(id){
+                        mis_ordenes.push(orden)
+                    }
+                    
+                }
+            }
+            mis_ordenes
+        }
+
+        /// Devuelve las órdenes en las que el usuario que llama es el vendedor.
+        #[ink(message)]
+        pub fn ver_ordenes_como_vendedor(&self) -> Vec<OrdenCompra> {
+            let caller = self.env().caller();
+            self._ver_mis_ordenes(caller).into_iter().filter(|orden| orden.id_vendedor == caller).collect()
+        }
+
+        /// Devuelve las órdenes en las que el usuario que llama es el comprador.
+        #[ink(message)]
+        pub fn ver_ordenes_como_comprador(&self) -> Vec<OrdenCompra> {
+            let caller = self.env().caller();
+            self._ver_mis_ordenes(caller).into_iter().filter(|orden| orden.id_comprador == caller).collect()
+        }
+
+        /// Devuelve un resumen de cada orden asociada al usuario que llama, sin el detalle de productos ni el historial.
+        /// Para ver una orden completa usar `get_orden`.
+        #[ink(message)]
+        pub fn ver_mis_ordenes_resumen(&self) -> Vec<ResumenOrden> {
+            let caller = self.env().caller();
+            self._ver_mis_ordenes_resumen(caller)
+        }
+
+        fn _ver_mis_ordenes_resumen(&self, caller: AccountId) -> Vec<ResumenOrden> {
+            self._ver_mis_ordenes(caller).into_iter().map(|orden| {
+                let contraparte = if orden.id_comprador == caller { orden.id_vendedor } else { orden.id_comprador };
+                ResumenOrden {
+                    id_orden: orden.id_orden_compra,
+                    estado: orden.estado,
+                    monto: orden.monto,
+                    contraparte,
+                    cantidad_items: orden.lista_productos.len() as u32,
+                }
+            }).collect()
+        }
+
+        /// Devuelve los ids de las órdenes recibidas del usuario que llama que todavía no calificó,
+        /// como comprador o como vendedor según su papel en cada orden.
+        /// No incluye las órdenes archivadas ni aquellas en las que comprador y vendedor coinciden, que no se pueden puntuar.
+        #[ink(message)]
+        pub fn ordenes_pendientes_de_calificar(&self) -> Vec<u128> {
+            let caller = self.env().caller();
+            self._ordenes_pendientes_de_calificar(caller)
+        }
+
+        fn _ordenes_pendientes_de_calificar(&self, caller: AccountId) -> Vec<u128> {
+            let user = match self.usuarios.get(caller) {
+                Some(user) => user,
+                None => return Vec::new(),
+            };
+            user.ordenes.iter()
+                .filter_map(|id| self.ordenes.get(id))
+                .filter(|orden| orden.estado == EstadoOrdenCompra::Recibido && orden.id_comprador != orden.id_vendedor)
+                .filter(|orden| {
+                    (orden.id_comprador == caller && !orden.puntuado_por_comprador)
+                        || (orden.id_vendedor == caller && !orden.puntuado_por_vendedor)
+                })
+                .map(|orden| orden.id_orden_compra)
+                .collect()
+        }
+
+        /// Devuelve la lista de órdenes archivadas asociadas al usuario que llama.
+        #[ink(message)]
+        pub fn ver_mis_ordenes_archivadas(&self) -> Vec<OrdenCompra> {
+            let caller = self.env().caller();
+            self._ver_mis_ordenes_archivadas(caller)
+        }
+
+        fn _ver_mis_ordenes_archivadas(&self, caller: AccountId) -> Vec<OrdenCompra> {
+            match self.usuarios.get(caller) {
+                Some(user) => user.ordenes.iter().filter_map(|id| self.ordenes_archivadas.get(id)).collect(),
+                None => Vec::new(),
+            }
+        }
+
+        /// Archiva una orden recibida o cancelada, sacándola de las órdenes activas que recorren los reportes.
+        /// Puede hacerlo el comprador o el vendedor. La orden sigue disponible por id con `get_orden`.
+        /// Una orden recibida sólo se archiva después de que la puntuaron comprador y vendedor, para que
+        /// ninguna de las partes le quite a la otra la posibilidad de calificar.
+        #[ink(message)]
+        pub fn archivar_orden(&mut self, id_orden: u128) -> Result<(), ErrorSistema> {
+            let caller = self.env().caller();
+            self._archivar_orden(id_orden, caller)
+        }
+
+        fn _archivar_orden(&mut self, id_orden: u128, caller: AccountId) -> Result<(), ErrorSistema> {
+            let orden = self.ordenes.get(id_orden).ok_or(ErrorSistema::IdDeOrdenNoValida)?;
+
+            if orden.id_comprador != caller && orden.id_vendedor != caller {
+                return Err(ErrorSistema::UsuarioNoAutorizado);
+            }
+
+            let archivable = match orden.estado {
+                EstadoOrdenCompra::Cancelado => true,
+                EstadoOrdenCompra::Recibido => orden.puntuado_por_comprador && orden.puntuado_por_vendedor,
+                _ => false,
+            };
+            if !archivable {
+                return Err(ErrorSistema::OperacionNoValida);
+            }
+
+            self.ordenes.remove(id_orden);
+            self.id_ordenes.retain(|id| *id != id_orden);
+            self.ordenes_archivadas.insert(id_orden, &orden);
+            Ok(())
+        }
+
+        /// Devuelve la orden de mayor monto en la que participa el usuario que llama (como comprador o vendedor).
+        /// En caso de empate devuelve la más reciente. Retorna `None` si no tiene órdenes.
+        #[ink(message)]
+        pub fn mi_orden_mayor(&self) -> Option<OrdenCompra> {
+            let caller = self.env().caller();
+            self._mi_orden_mayor(caller)
+        }
+
+        fn _mi_orden_mayor(&self, caller: AccountId) -> Option<OrdenCompra> {
+            self.iter_ordenes()
+                .filter(|orden| orden.id_comprador == caller || orden.id_vendedor == caller)
+                .max_by_key(|orden| (orden.monto, orden.id_orden_compra))
+        }
+
+        /// Devuelve las categorías distintas de los productos que compró un usuario en sus órdenes recibidas.
+        /// Las órdenes archivadas con `archivar_orden` no se cuentan.
+        /// Retorna `UsuarioNoExiste` si la cuenta no está registrada.
+        #[ink(message)]
+        pub fn categorias_compradas(&self, comprador: AccountId) -> Result<Vec<Categoria>, ErrorSistema> {
+            self._existe_usuario(comprador)?;
+
+            let mut categorias = BTreeSet::new();
+            for orden in self.iter_ordenes().filter(|o| o.id_comprador == comprador && o.estado == EstadoOrdenCompra::Recibido) {
+                for (id_producto, _) in &orden.lista_productos {
+                    if let Some(producto) = self.productos.get(id_producto) {
+                        categorias.insert(producto.categoria);
+                    }
+                }
+            }
+
+            Ok(categorias.into_iter().collect())
+        }
+
+        /// Devuelve el promedio de bloques transcurridos entre la creación de una publicación y su primera venta recibida,
+        /// considerando sólo las publicaciones que ya vendieron.
+        /// Sólo el propietario del sistema puede llamar a esta función. Retorna `SinDatos` si ninguna publicación vendió.
+        #[ink(message)]
+        pub fn tiempo_promedio_venta(&self) -> Result<u64, ErrorSistema> {
+            self.verificar_owner()?;
+            self._tiempo_promedio_venta()
+        }
+
+        fn _tiempo_promedio_venta(&self) -> Result<u64, ErrorSistema> {
+            let mut suma: u64 = 0;
+            let mut cantidad: u64 = 0;
+            for publicacion in &self.publicaciones {
+                if let Some(primera_venta) = publicacion.primera_venta {
+                    let bloques = primera_venta.saturating_sub(publicacion.fecha_creacion) as u64;
+                    suma = suma.checked_add(bloques).ok_or(ErrorSistema::Overflow)?;
+                    cantidad = cantidad.checked_add(1).ok_or(ErrorSistema::Overflow)?;
+                }
+            }
+
+            suma.checked_div(cantidad).ok_or(ErrorSistema::SinDatos)
+        }
+
+        /// Devuelve el total vendido por un vendedor, sumando el monto de sus órdenes recibidas.
+        /// Si la suma desborda se devuelve el máximo representable.
+        /// Las órdenes archivadas con `archivar_orden` no se cuentan, a diferencia de `ver_reputacion`,
+        /// que recorre todas las órdenes del usuario.
+        #[ink(message)]
+        pub fn total_vendido(&self, vendedor: AccountId) -> u128 {
+            self.iter_ordenes()
+                .filter(|orden| orden.id_vendedor == vendedor && orden.estado == EstadoOrdenCompra::Recibido)
+                .fold(0u128, |total, orden| total.saturating_add(orden.monto as u128))
+        }
+
+        /// Indica si el vendedor puede sumar una publicación activa más sin pasarse de `max_publicaciones_por_vendedor`.
+        fn puede_activar_publicacion(&self, vendedor: AccountId) -> bool {
+            self.contar_publicaciones_activas(vendedor) < self.max_publicaciones_por_vendedor
+        }
+
+        /// Devuelve la cantidad de publicaciones activas (`activa == true`) de un vendedor.
+        #[ink(message)]
+        pub fn contar_publicaciones_activas(&self, vendedor: AccountId) -> u32 {
+            self.publicaciones.iter()
+                .filter(|publicacion| publicacion.id_publicador == vendedor && publicacion.activa)
+                .count() as u32
+        }
+
+        /// Devuelve un resumen del marketplace como
+        /// (total_usuarios, total_publicaciones_activas, total_ordenes, total_ordenes_recibidas).
+        /// Se consideran activas las publicaciones con `activa == true`, aunque tengan todo su stock reservado.
+        /// `total_ordenes` y `total_ordenes_recibidas` no cuentan las órdenes archivadas con `archivar_orden`.
+        #[ink(message)]
+        pub fn metricas_generales(&self) -> (u128, u128, u128, u128) {
+            let total_usuarios = self.id_usuarios.len() as u128;
+            let publicaciones_activas = self.publicaciones.iter().filter(|p| p.activa).count() as u128;
+
+            let mut total_ordenes: u128 = 0;
+            let mut ordenes_recibidas: u128 = 0;
+            for orden in self.iter_ordenes() {
+                total_ordenes = total_ordenes.saturating_add(1);
+                if orden.estado == EstadoOrdenCompra::Recibido {
+                    ordenes_recibidas = ordenes_recibidas.saturating_add(1);
+                }
+            }
+
+            (total_usuarios, publicaciones_activas, total_ordenes, ordenes_recibidas)
+        }
+
+        //
+        //
+        //  Funciones vinculadas al escrow
+        //
+        //
+
+        /// Devuelve el monto retenido en escrow, es decir, la suma de los montos de las órdenes
+        /// que todavía no fueron recibidas ni canceladas (pendientes, enviadas o en disputa).
+        /// El escrow es nominal: el contrato no recibe ni guarda fondos, sólo lleva la cuenta de esos montos.
+        #[ink(message)]
+        pub fn escrow_actual(&self) -> u128 {
+            self.iter_ordenes()
+                .filter(|orden| orden.esta_en_curso())
+                .fold(0u128, |total, orden| total.saturating_add(orden.monto as u128))
+        }
+
+        /// Setea el monto máximo de escrow que admite el sistema. Con `None` se quita el límite.
+        /// Sólo el propietario del sistema puede llamar a esta función.
+        #[ink(message)]
+        pub fn set_escrow_maximo(&mut self, escrow_maximo: Option<u128>) -> Result<(), ErrorSistema> {
+            self.verificar_owner()?;
+            self.escrow_maximo = escrow_maximo;
+            Ok(())
+        }
+
+        /// Setea la cantidad máxima de publicaciones activas que puede tener cada vendedor.
+        /// Por defecto es `u32::MAX`, es decir, sin límite en la práctica.
+        /// Sólo el propietario del sistema puede llamar a esta función.
+        #[ink(message)]
+        pub fn set_max_publicaciones_por_vendedor(&mut self, maximo: u32) -> Result<(), ErrorSistema> {
+            self.verificar_owner()?;
+            self.max_publicaciones_por_vendedor = maximo;
+            Ok(())
+        }
+
+        /// Verifica que una nueva orden de `monto` no haga superar el escrow máximo configurado.
+        /// Compara montos declarados, no valores transferidos (ver `escrow_actual`). Retorna `EscrowLleno` si lo supera.
+        fn validar_escrow(&self, monto: u32) -> Result<(), ErrorSistema> {
+            if let Some(maximo) = self.escrow_maximo {
+                let total = suma_segura_u128(self.escrow_actual(), monto as u128)?;
+                if total > maximo {
+                    return Err(ErrorSistema::EscrowLleno);
+                }
+            }
+            Ok(())
+        }
+
+        /// Setea la comisión del marketplace en puntos básicos (10000 = 100%).
+        /// Sólo el propietario del sistema puede llamar a esta función. Retorna `ComisionInvalida` si supera `MAX_BPS`.
+        #[ink(message)]
+        pub fn set_comision(&mut self, bps: u16) -> Result<(), ErrorSistema> {
+            self.verificar_owner()?;
+            if bps > MAX_BPS {
+                return Err(ErrorSistema::ComisionInvalida);
+            }
+            self.comision_bps = bps;
+            Ok(())
+        }
+
+        /// Setea una comisión propia para las órdenes de una categoría, que reemplaza a la comisión global.
+        /// Sólo el propietario del sistema puede llamar a esta función. Retorna `ComisionInvalida` si supera `MAX_BPS`.
+        #[ink(message)]
+        pub fn set_comision_categoria(&mut self, categoria: Categoria, bps: u16) -> Result<(), ErrorSistema> {
+            self.verificar_owner()?;
+            if bps > MAX_BPS {
+                return Err(ErrorSistema::ComisionInvalida);
+            }
+            self.comisiones_por_categoria.insert(categoria, &bps);
+            Ok(())
+        }
+
+        /// Indica si las órdenes con productos de una categoría tienen que incluir dirección de envío.
+        /// Por defecto todas las categorías la exigen, porque son de productos físicos; el owner puede eximir
+        /// las que no se envían. Sólo el propietario del sistema puede llamar a esta función.
+        #[ink(message)]
+        pub fn set_direccion_requerida(&mut self, categoria: Categoria, requerida: bool) -> Result<(), ErrorSistema> {
+            self.verificar_owner()?;
+            if requerida {
+                self.direccion_exenta.remove(categoria);
+            } else {
+                self.direccion_exenta.insert(categoria, &true);
+            }
+            Ok(())
+        }
+
+        /// Liquida las comisiones acumuladas: pone el acumulado en cero y devuelve el monto liquidado.
+        /// Las comisiones son sólo contables, el contrato no recibe ni transfiere fondos, así que el pago
+        /// al owner se hace fuera del contrato por el monto devuelto.
+        /// Sólo el propietario del sistema puede llamar a esta función.
+        #[ink(message)]
+        pub fn liquidar_comisiones(&mut self) -> Result<u128, ErrorSistema> {
+            self.verificar_owner()?;
+            let total = self.comisiones_acumuladas;
+            self.comisiones_acumuladas = 0;
+            Ok(total)
+        }
+
+        /// Calcula la comisión que corresponde a un monto con la tasa indicada en puntos básicos.
+        fn calcular_comision(&self, monto: u32, bps: u16) -> u32 {
+            // Como bps <= 10000, el resultado nunca supera al monto.
+            (monto as u128 * bps as u128 / 10000) as u32
+        }
+
+        /// Devuelve la comisión que corresponde a una orden: la de su categoría dominante si tiene una propia,
+        /// o la global en caso contrario. La categoría dominante es la de mayor cantidad de unidades; ante
+        /// un empate se toma la primera en el orden de declaración de `Categoria`.
+        fn comision_bps_de_orden(&self, orden: &OrdenCompra) -> u16 {
+            let mut unidades_por_categoria: BTreeMap<Categoria, u32> = BTreeMap::new();
+            for (id_producto, cantidad) in &orden.lista_productos {
+                if let Some(producto) = self.productos.get(id_producto) {
+                    let unidades = unidades_por_categoria.entry(producto.categoria).or_insert(0);
+                    *unidades = unidades.saturating_add(*cantidad);
+                }
+            }
+
+            let mut dominante: Option<(Categoria, u32)> = None;
+            for (categoria, unidades) in unidades_por_categoria {
+                if dominante.as_ref().is_none_or(|(_, maximo)| unidades > *maximo) {
+                    dominante = Some((categoria, unidades));
+                }
+            }
+
+            dominante
+                .and_then(|(categoria, _)| self.comisiones_por_categoria.get(categoria))
+                .unwrap_or(self.comision_bps)
+        }
+
+        //
+        //
+        //  Funciones vinculadas a ReportesView
+        //
+        //
+
+        /// Verifica que el usuario que llama sea el propietario del contrato.
+        /// Se utiliza para el seteo del AccountId de ReportesView.
+        fn verificar_owner(&self) -> Result<(), ErrorSistema> {
+            let caller = self.env().caller();
+
+            if caller != self.owner {
+                return Err(ErrorSistema::AccesoDenegado);
+            }
+
+            Ok(())
+        }
+        
+        /// (A fines de debug solamente) Retorna el AccountId establecido para ReportesView.
+        #[ink(message)]
+        pub fn get_reportes_view(&self) -> Result<Option<AccountId>, ErrorSistema> {
+            self.verificar_owner()?;
+            Ok(self.reportes_view)
+        }
+
+        /// Setea el AccountId de ReportesView.
+        /// Sólo el propietario del sistema puede llamar a esta función.
+        /// Retorna `Ok(())` si se estableció correctamente, o un error si no existe.
+        #[ink(message)]
+        pub fn set_reportes_view(&mut self, reportes_view: AccountId) -> Result<(), ErrorSistema> {
+            self.verificar_owner()?;
+            self._set_reportes_view(reportes_view)?;
+            Ok(())
+        }
+
+        fn _set_reportes_view(&mut self, reportes_view: AccountId) -> Result<(), ErrorSistema> {
+            if self.reportes_view.is_some() {
+                return Err(ErrorSistema::ReportesViewYaEstablecido);
+            }
+            
+            self.reportes_view = Some(reportes_view);
+            Ok(())
+        }
+
+        /// Devuelve hasta 5 usuarios (Vendedor/Ambos) ordenados por puntaje como vendedor.
+        /// Solo accesible si se ha establecido ReportesView.
+         #[ink(message)]
+        pub fn consultar_top_5_vendedores(&self) -> Result<Vec<Usuario>,ErrorSistema> {
+            self.verificar_reportes_view()?;
+            self._consultar_top_5_vendedores()
+        }
+
+        fn _consultar_top_5_vendedores(&self) -> Result<Vec<Usuario>,ErrorSistema> {
+            //Filtro los vendedores recorriendo el vector de id_usuarios y buscando en el mapping.
+            let mut vendedores: Vec<Usuario> = Vec::new(); //Creo un vector con los vendedores.
+            for id in &self.id_usuarios {
+                if let Some(user) = self.usuarios.get(id) {
+                    if user.rol == Rol::Vendedor || user.rol == Rol::Ambos {
+                        vendedores.push(user);
+                    }
+                }
+            }
+
+            //Ordeno el vector en cuanto reputación de forma descendente.
+            vendedores.sort_by(|a, b| b.calcular_puntaje_como_vendedor().cmp(&a.calcular_puntaje_como_vendedor()));
+
+            //Recorto el tamaño del vector a 5.
+            vendedores.truncate(5);
+
+            Ok(vendedores)
+        }
+
+        /// Devuelve una página del ranking completo de vendedores (Vendedor/Ambos) como
+        /// (vendedor, prom_calificacion, n_ventas), ordenado por calificación y luego por ventas, de mayor a menor.
+        /// Los empates conservan el orden de registro. `limit` se recorta a `MAX_TAMANO_PAGINA`.
+        ///
+        /// # Ejemplo
+        /// ```
+        ///   let segunda_pagina = sistema.ranking_vendedores(10, 10);
+        /// ```
+        #[ink(message)]
+        pub fn ranking_vendedores(&self, offset: u32, limit: u32) -> Vec<(AccountId, u8, u32)> {
+            let limite = limit.min(MAX_TAMANO_PAGINA) as usize;
+            let mut ranking: Vec<(AccountId, u8, u32)> = Vec::new();
+            for id in &self.id_usuarios {
+                if let Some(user) = self.usuarios.get(id) {
+                    if user.rol == Rol::Vendedor || user.rol == Rol::Ambos {
+                        let n_ventas = self.ver_reputacion(*id).map(|reputacion| reputacion.n_ventas).unwrap_or(0);
+                        ranking.push((*id, user.calcular_puntaje_como_vendedor(), n_ventas));
+                    }
+                }
+            }
+
+            //sort_by es estable, así que los empates quedan en orden de registro.
+            ranking.sort_by(|a, b| b.1.cmp(&a.1).then(b.2.cmp(&a.2)));
+
+            ranking.into_iter().skip(offset as usize).take(limite).collect()
+        }
+
+        /// Devuelve hasta 5 usuarios (Vendedor/Ambos) ordenados por puntaje como vendedor.
+        /// Solo accesible si se ha establecido ReportesView.
+         #[ink(message)]
+        pub fn consultar_top_5_compradores(&self) -> Result<Vec<Usuario>,ErrorSistema> {
+            self.verificar_reportes_view()?;
+            self._consultar_top_5_compradores()
+        }
+
+        fn _consultar_top_5_compradores(&self) -> Result<Vec<Usuario>,ErrorSistema> {
+            //Filtro los compradores recorriendo el vector de id_usuarios y buscando en el mapping.
+            let mut compradores: Vec<Usuario> = Vec::new(); //Creo un vector con los compradores.
+            for id in &self.id_usuarios {
+                if let Some(user) = self.usuarios.get(id) {
+                    if user.rol == Rol::Comprador || user.rol == Rol::Ambos {
+                        compradores.push(user);
+                    }
+                }
+            }
+
+            //Ordeno el vector en cuanto reputación de forma descendente.
+            compradores.sort_by(|a, b| b.calcular_puntaje_como_comprador().cmp(&a.calcular_puntaje_como_comprador()));
+
+            //Recorto el tamaño del vector a 5.
+            compradores.truncate(5);
+
+            Ok(compradores)
+        }
+
+        /// Devuelve una lista de hasta 10 productos de una categoría específica ordenados por ventas en forma descendente.
+        /// No cuenta las órdenes canceladas, las propuestas sin aceptar ni las archivadas con `archivar_orden`.
+        /// Solo accesible si se ha establecido ReportesView.
+        ///
+        /// # Parámetros
+        /// - `codigo_categoria`: Código (`Categoria::codigo`) de la categoría de los productos a buscar.
+        /// - `version`: `VERSION_API` con la que se compiló ReportesView.
+        ///
+        /// Retorna `VersionIncompatible` si la versión no coincide y `CategoriaInvalida` si el código no corresponde a ninguna categoría.
+        #[ink(message)]
+        pub fn ver_productos_mas_vendidos(&self, codigo_categoria: u8, version: u32) -> Result<Vec<(u128, u8)>, ErrorSistema>{
+            self.verificar_reportes_view()?;
+            if version != VERSION_API {
+                return Err(ErrorSistema::VersionIncompatible);
+            }
+            let categoria = Categoria::desde_codigo(codigo_categoria)?;
+            Ok(self._ver_productos_mas_vendidos(categoria))
+        }
+
+        /// Devuelve la `VERSION_API` del contrato, para que ReportesView verifique que interpreta igual sus tipos.
+        #[ink(message)]
+        pub fn version(&self) -> u32 {
+            VERSION_API
+        }
+
+        fn _ver_productos_mas_vendidos(&self, categoria: Categoria) -> Vec<(u128, u8)> {
+            //Creo un vector para los productos.
+            let mut productos: Vec<(u128, u8)> = Vec::new();
+
+            //Recorro el vector de ordenes -> Voy chequeando la lista de productos y completo el vector según eso
+            //Las órdenes canceladas y las propuestas que el comprador todavía no aceptó no son ventas.
+            for orden in self.iter_ordenes().filter(|orden| !matches!(orden.estado, EstadoOrdenCompra::Cancelado | EstadoOrdenCompra::PropuestaPendiente)) {
+
+                //Recorro la lista de productos de la orden.
+                for tupla in &orden.lista_productos {
+
+                    //Primero busco al producto en el mapping de productos del sistema (para ver su categoría).
+                    let producto = self.productos.get(tupla.0 as u128);
+
+                    if producto.is_none() {
+                        continue; //Si el producto no existe, paso al siguiente.
+                    }
+                    let producto = producto.unwrap();
+
+                    if producto.categoria == categoria {
+                        //Si ya existe en mi vector contador, aumento la cantidad de ventas del mismo.
+                        if let Some(pos) = productos.iter().position(|(p, _)| *p == tupla.0) { //Busco la posición del producto en el vector.
+                            //productos[pos].1 += tupla.1 as u8; //Aumento la cantidad de ventas del producto.
+                            productos[pos].1 = productos[pos].1.wrapping_add(tupla.1 as u8);
+                        } else { //Si no existe, lo agrego.
+                            productos.push((tupla.0.clone(), tupla.1.try_into().unwrap())); //Agrego el producto al vector.
+                        }
+                    }
+                }
+            }
+
+            //Ordeno el vector en cuanto cantidad de ventas de forma descendente.
+            productos.sort_by(|a, b| b.1.cmp(&a.1));
+
+            //Recorto el tamaño del vector a 10 (como máximo).
+            productos.truncate(10);
+
+            productos
+        }
+
+        /// Devuelve un vector con las estadisticas por categoría, con la cantidad total de ventas y la suma de puntuaciones.
+        #[ink(message)]
+        pub fn estadisticas_por_categoria(&self) -> Result<Vec<(Categoria, u32, u8)>, ErrorSistema> { 
+            self.verificar_reportes_view()?;
+            self._estadisticas_por_categoria()
+        }
+
+        fn _estadisticas_por_categoria(&self) -> Result<Vec<(Categoria, u32, u8)>, ErrorSistema>{
+            let mut ventas_por_categoria: BTreeMap<Categoria, u32> = BTreeMap::new();
+            let mut suma_puntajes: BTreeMap<Categoria, u8> = BTreeMap::new();
+            let mut cantidad_puntajes: BTreeMap<Categoria, u8> = BTreeMap::new();
+
+            let mut id: u128 = 0;
+            while id < self.proximo_id_producto {
+                if let Some(producto) = self.productos.get(&id) {
+                    let key = producto.categoria.clone();
+                    let entry = ventas_por_categoria.entry(key.clone()).or_insert(0u32);
+                    let nueva_ventas = suma_segura_u32(*entry, producto.total_ventas)?;
+                    *entry = nueva_ventas;
+
+                    let suma: u8 = producto.puntuaciones.iter().map(|&x| x as u8).sum();
+                    let cantidad = producto.puntuaciones.len() as u8;
+
+                    let s_entry = suma_puntajes.entry(key.clone()).or_insert(0u8);
+                    let nueva_suma = s_entry
+                        .checked_add(suma)
+                        .ok_or(ErrorSistema::Overflow)?;
+                    *s_entry = nueva_suma;
+
+                    let c_entry = cantidad_puntajes.entry(key.clone()).or_insert(0u8);
+                    let nueva_cant = c_entry
+                        .checked_add(cantidad)
+                        .ok_or(ErrorSistema::Overflow)?;
+                    *c_entry = nueva_cant;
+                }
+                id = suma_segura_u128(id, 1)?;
+            }
+
+            let mut resultado = Vec::new();
+            for categoria in ventas_por_categoria.keys() {
+                let ventas = *ventas_por_categoria.get(categoria).unwrap_or(&0);
+                let suma = *suma_puntajes.get(categoria).unwrap_or(&0);
+                let cantidad = *cantidad_puntajes.get(categoria).unwrap_or(&0);
+                let promedio = match suma.checked_div(cantidad) {
+                    Some(promedio) => promedio,
+                    None => 0,
+                };
+                resultado.push((categoria.clone(), ventas, promedio));
+            }
+            Ok(resultado)
+        }
+
+        /// Devuelve las estadísticas de cada categoría con productos como `EstadisticaCategoria`,
+        /// con nombres de campo explícitos en lugar de la tupla de `estadisticas_por_categoria`.
+        /// `unidades_vendidas` sale del contador de ventas de cada producto e incluye las órdenes archivadas;
+        /// `ordenes` e `ingreso_total` recorren las órdenes recibidas y no cuentan las archivadas con `archivar_orden`.
+        /// Solo accesible si se ha establecido ReportesView.
+        #[ink(message)]
+        pub fn estadisticas_por_categoria_detallado(&self) -> Result<Vec<EstadisticaCategoria>, ErrorSistema> {
+            self.verificar_reportes_view()?;
+            self._estadisticas_por_categoria_detallado()
+        }
+
+        fn _estadisticas_por_categoria_detallado(&self) -> Result<Vec<EstadisticaCategoria>, ErrorSistema> {
+            // (unidades vendidas, puntuaciones, órdenes, ingreso)
+            let mut acumulado: BTreeMap<Categoria, (u32, Vec<u8>, u32, u128)> = BTreeMap::new();
+
+            let mut id: u128 = 0;
+            while id < self.proximo_id_producto {
+                if let Some(producto) = self.productos.get(id) {
+                    let entry = acumulado.entry(producto.categoria.clone()).or_insert((0, Vec::new(), 0, 0));
+                    entry.0 = suma_segura_u32(entry.0, producto.total_ventas)?;
+                    entry.1.extend_from_slice(&producto.puntuaciones);
+                }
+                id = suma_segura_u128(id, 1)?;
+            }
+
+            for orden in self.iter_ordenes().filter(|orden| orden.estado == EstadoOrdenCompra::Recibido) {
+                //Cada categoría cuenta la orden una sola vez, aunque tenga varios productos de ella.
+                let mut categorias_orden: Vec<Categoria> = Vec::new();
+                for (id_producto, _) in &orden.lista_productos {
+                    if let Some(producto) = self.productos.get(id_producto) {
+                        if !categorias_orden.contains(&producto.categoria) {
+                            categorias_orden.push(producto.categoria);
+                        }
+                    }
+                }
+                for categoria in categorias_orden {
+                    let entry = acumulado.entry(categoria).or_insert((0, Vec::new(), 0, 0));
+                    entry.2 = suma_segura_u32(entry.2, 1)?;
+                    entry.3 = suma_segura_u128(entry.3, orden.monto as u128)?;
+                }
+            }
+
+            Ok(acumulado
+                .into_iter()
+                .map(|(categoria, (unidades_vendidas, puntuaciones, ordenes, ingreso_total))| EstadisticaCategoria {
+                    categoria,
+                    unidades_vendidas,
+                    ordenes,
+                    ingreso_total,
+                    prom_calificacion: Usuario::promedio_redondeado(&puntuaciones),
+                })
+                .collect())
+        }
+
+        /// Devuelve un vector con la cantidad de órdenes de todos los usuarios registrados.
+        #[ink(message)]
+        pub fn cantidad_ordenes_por_usuario(&self) -> Result<Vec<(AccountId, u128)>, ErrorSistema> {
+            self.verificar_reportes_view()?;
+            Ok(self._cantidad_ordenes_por_usuario())
+        }
+
+        fn _cantidad_ordenes_por_usuario(&self) -> Vec<(AccountId, u128)> {
+            let mut res: Vec<(AccountId, u128)> = Vec::new();
+            for id in &self.id_usuarios {
+                if let Some(user) = self.usuarios.get(id) {
+                    res.push((id.clone(), user.ordenes.len() as u128));
+                } else {
+                    // Si por alguna razón el id no existe en el mapping, devolver 0.
+                    res.push((id.clone(), 0));
+                }
+            }
+            res
+        }
+        
+        /// Verifica que el usuario que llama es el mismo que se estableció como ReportesView.
+        /// Si no es el caso, devuelve ErrorSistema::AccesoDenegado.
+        /// Si ReportesView no ha sido establecido devuelve ErrorSistema::ReportesViewNoEstablecido.
+        fn verificar_reportes_view(&self) -> Result<(), ErrorSistema> {
+            let caller = self.env().caller();
+
+            match self.reportes_view {
+                Some(reportes_view) => {
+                    if caller != reportes_view {
+                        return Err(ErrorSistema::AccesoDenegado);
+                    }
+                }
+                None => {
+                    return Err(ErrorSistema::ReportesViewNoEstablecido);
+                }
+            }
+            Ok(())
+        }
+        
+    }
+
+    impl Usuario {
+        /// Agrega un rol al usuario. Un rol simple más el otro rol simple deja al usuario como `Ambos`.
+        /// No se puede pedir `Ambos` directamente desde un rol simple, para que pasar a vendedor (o a comprador)
+        /// sea siempre un paso explícito: retorna `MismoRolAmbosRedundante`.
+        /// Retorna `RolYaEnUso` si el usuario ya tenía el rol pedido.
+        pub fn agregar_rol(&mut self, rol: Rol) -> Result<(), ErrorSistema> { 
+            // Cada par (actual, nuevo) está listado explícitamente para que agregar una variante obligue a revisarlo.
+            self.rol = match (self.rol.clone(), rol) {
+                (Rol::Comprador, Rol::Comprador) => return Err(ErrorSistema::RolYaEnUso),
+                (Rol::Vendedor, Rol::Vendedor) => return Err(ErrorSistema::RolYaEnUso),
+                (Rol::Ambos, Rol::Comprador | Rol::Vendedor | Rol::Ambos) => return Err(ErrorSistema::RolYaEnUso),
+                (Rol::Comprador, Rol::Vendedor) => Rol::Ambos,
+                (Rol::Vendedor, Rol::Comprador) => Rol::Ambos,
+                (Rol::Comprador, Rol::Ambos) => return Err(ErrorSistema::MismoRolAmbosRedundante),
+                (Rol::Vendedor, Rol::Ambos) => return Err(ErrorSistema::MismoRolAmbosRedundante),
+            };
+            Ok(())
+        }
+
+        /// Deja al usuario con el rol opuesto al que se quita. Sólo es posible desde `Ambos`.
+        fn quitar_rol(&mut self, rol: Rol) -> Result<(), ErrorSistema> {
+            self.rol = match (self.rol.clone(), rol) {
+                (Rol::Ambos, Rol::Vendedor) => Rol::Comprador,
+                (Rol::Ambos, Rol::Comprador) => Rol::Vendedor,
+                _ => return Err(ErrorSistema::NoSePuedeQuitarRol),
+            };
+            Ok(())
+        }
+
+        /// Promedio de una lista de calificaciones redondeado al entero más cercano (los .5 hacia arriba).
+        /// Devuelve `None` si la lista está vacía.
+        fn promedio_redondeado(calificaciones: &[u8]) -> Option<u8> {
+            let suma: u32 = calificaciones.iter().map(|&x| x as u32).sum();
+            let cantidad = calificaciones.len() as u32;
+            // (2 * suma + cantidad) / (2 * cantidad) redondea sin usar punto flotante.
+            suma.checked_mul(2)?
+                .checked_add(cantidad)?
+                .checked_div(cantidad.checked_mul(2)?)
+                .map(|promedio| promedio as u8)
+        }
+
+        fn promedio_ponderado_vendedor(&self, ahora: u64) -> Option<u8> {
+            let mut suma: u128 = 0;
+            let mut pesos: u128 = 0;
+            for (i, &calificacion) in self.calificaciones_vendedor.iter().enumerate() {
+                // Una calificación sin momento registrado se considera antigua.
+                let peso = match self.momentos_calificaciones_vendedor.get(i) {
+                    Some(&momento) => {
+                        let edad = ahora.saturating_sub(momento).min(VENTANA_REPUTACION);
+                        1 + (PESO_MAXIMO_REPUTACION - 1) * (VENTANA_REPUTACION - edad) / VENTANA_REPUTACION
+                    }
+                    None => 1,
+                };
+                suma = suma.checked_add((peso as u128).checked_mul(calificacion as u128)?)?;
+                pesos = pesos.checked_add(peso as u128)?;
+            }
+            suma.checked_mul(2)?
+                .checked_add(pesos)?
+                .checked_div(pesos.checked_mul(2)?)
+                .map(|promedio| promedio as u8)
+        }
+
+        fn calcular_puntaje_como_comprador(&self) -> u8 {
+            if self.calificaciones_comprador.is_empty() {
+                return 0;
+            }
+            let sum: u32 = self.calificaciones_comprador.iter().map(|&x| x as u32).sum();
+            let count = self.calificaciones_comprador.len() as u32;
+
+            // Usar checked_div para evitar división por cero
+            match sum.checked_div(count) {
+                Some(avg) => avg as u8,
+                None => 0, // En caso de que ocurra un error, aunque no debería
+            }
+        }
+
+        fn calcular_puntaje_como_vendedor(&self) -> u8 {
+            if self.calificaciones_vendedor.is_empty() {
+                return 0;
+            }
+            let sum: u32 = self.calificaciones_vendedor.iter().map(|&x| x as u32).sum();
+            let count = self.calificaciones_vendedor.len() as u32;
+
+            // idem comprador
+            match sum.checked_div(count) {
+                Some(avg) => avg as u8,
+                None => 0,
+            }
+        }
+    }
+
+    impl OrdenCompra {
+        /// Indica si la orden todavía no llegó a un estado final (recibida o cancelada).
+        fn esta_en_curso(&self) -> bool {
+            matches!(self.estado, EstadoOrdenCompra::Pendiente | EstadoOrdenCompra::Enviado | EstadoOrdenCompra::EnDisputa)
+        }
+
+        /// Indica si la orden todavía puede necesitar sus productos: sólo deja de usarlos una vez cancelada
+        /// o cuando ya la puntuaron comprador y vendedor.
+        fn usa_productos(&self) -> bool {
+            match self.estado {
+                EstadoOrdenCompra::Cancelado => false,
+                EstadoOrdenCompra::Recibido => !(self.puntuado_por_comprador && self.puntuado_por_vendedor),
+                _ => true,
+            }
+        }
+
+        /// Cambia el estado de la orden y registra la transición en su historial.
+        fn cambiar_estado(&mut self, estado: EstadoOrdenCompra, ahora: u64) {
+            self.historial.push((estado.clone(), ahora));
+            self.estado = estado;
+            self.actualizada_en = ahora;
+        }
+    }
+
+    impl Publicacion {
+        fn actualizar_stock(&mut self, cant:u32)->Result<(),ErrorSistema>{
+            match self.stock.checked_sub(cant){
+                Some(val) => {
+                    self.stock = val;
+                    Ok(())
+                }
+                None => Err(ErrorSistema::StockOverflow)
+            }
+        }
+
+        fn tiene_stock_suficiente(&self, cant:u32)->bool{
+            self.stock >= cant
+        }
+
+        /// Calcula el precio de `cant` unidades aplicando el mejor descuento por cantidad disponible.
+        fn precio_con_descuento(&self, cant:u32)->Result<u32, ErrorSistema>{
+            let bruto = mul_segura_u32(self.precio, cant)?;
+
+            let mejor_descuento = self.descuentos.iter()
+                .filter(|(minimo, _)| cant >= *minimo)
+                .map(|(_, bps)| *bps)
+                .max()
+                .unwrap_or(0);
+
+            // bruto * bps entra en u64, y el resultado nunca supera a bruto.
+            let descuento = (bruto as u64) * (mejor_descuento as u64) / 10000;
+            Ok(bruto - descuento as u32)
+        }
+    } 
+
+    #[cfg(test)]
+    mod tests {
+
+        use super::*;
+
+        //TESTS REGISTRAR USUARIO:
+        #[ink::test]
+        fn test_registrar_usuario_comprador_okay() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+
+            let mut sistema = Sistema::new();
+
+            assert!(sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Comprador).is_ok());
+
+            //Chequeamos que el usuario se haya registrado correctamente.
+            let usuario = sistema.usuarios.get(&alice);
+            assert!(usuario.is_some());
+        }
+
+        #[ink::test]
+        fn test_registrar_usuario_vendedor_okay() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+
+            let mut sistema = Sistema::new();
+
+            assert!(sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor).is_ok());
+
+            //Chequeamos que el usuario se haya registrado correctamente.
+            let usuario = sistema.usuarios.get(&alice);
+            assert!(usuario.is_some());
+        }
+
+        #[ink::test]
+        fn test_registrar_usuario_ambos_okay() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+
+            let mut sistema = Sistema::new();
+
+            assert!(sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Ambos).is_ok());
+
+            //Chequeamos que el usuario se haya registrado correctamente.
+            let usuario = sistema.usuarios.get(&alice);
+            assert!(usuario.is_some());
+        }
+
+         /// No se puede registrar un usuario que ya está registrado
+         #[ink::test]
+         fn test_registrar_usuario_not_okay() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+ 
+            let mut sistema = Sistema::new();
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Comprador);
+ 
+            assert!(sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Comprador).is_err());
+
+            //Chequeamos que el usuario no se haya registrado nuevamente.
+            assert!(sistema.usuarios.get(&alice).is_some());
+         }
+
+        #[ink::test]
+        //Test que verifica que actualizar_perfil modifique sólo los campos recibidos.
+        fn test_actualizar_perfil_parcial() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+
+            let mut sistema = Sistema::new();
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Ambos).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 10, 5).unwrap();
+
+            assert!(sistema.actualizar_perfil(Some(String::from("Alicia")), None, None).is_ok());
+            let usuario = sistema.usuarios.get(alice).unwrap();
+            assert_eq!(usuario.nombre, "Alicia");
+            assert_eq!(usuario.apellido, "Surname");
+            assert_eq!(usuario.email, "alice.email");
+
+            assert!(sistema.actualizar_perfil(None, Some(String::from("Apellido")), Some(String::from("alice@mail.com"))).is_ok());
+            let usuario = sistema.usuarios.get(alice).unwrap();
+            assert_eq!(usuario.nombre, "Alicia");
+            assert_eq!(usuario.apellido, "Apellido");
+            assert_eq!(usuario.email, "alice@mail.com");
+
+            //El resto de los datos del usuario no se modifican.
+            assert_eq!(usuario.rol, Rol::Ambos);
+            assert_eq!(usuario.publicaciones, vec![0]);
+            assert!(usuario.ordenes.is_empty());
+        }
+
+        #[ink::test]
+        //Test que verifica que un email inválido sea rechazado sin modificar ningún campo.
+        fn test_actualizar_perfil_email_invalido() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+
+            let mut sistema = Sistema::new();
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Comprador).unwrap();
+
+            let error = sistema.actualizar_perfil(Some(String::from("Alicia")), None, Some(String::from("alice.mail.com"))).unwrap_err();
+            assert_eq!(error, ErrorSistema::EmailInvalido);
+
+            let usuario = sistema.usuarios.get(alice).unwrap();
+            assert_eq!(usuario.nombre, "Alice");
+            assert_eq!(usuario.email, "alice.email");
+        }
+
+        #[ink::test]
+        //Test que verifica que un usuario no registrado no pueda actualizar su perfil.
+        fn test_actualizar_perfil_usuario_inexistente() {
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+
+            let mut sistema = Sistema::new();
+            let error = sistema.actualizar_perfil(Some(String::from("Bob")), None, None).unwrap_err();
+            assert_eq!(error, ErrorSistema::UsuarioNoExiste);
+        }
+
+        //-------------------------------------------------------------------------------------
+        //TESTS PRODUCTOS:
+
+        #[ink::test]
+        fn test_nuevo_producto_usuario_inexistente() {
+            //Se testea que un usuario que no existe en la plataforma no pueda crear un producto.
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            let mut sistema = Sistema::new();
+
+            assert!(sistema.nuevo_producto(String::from("Laptop"), String::from("Laptop gamer"), Categoria::Tecnologia, None).is_err());
+            // El usuario no existe, por lo tanto no puede crear un producto.
+
+            //Chequear el estado posterior del sistema (no debería haber ningún producto).
+            assert!(sistema.productos.get(0).is_none());
+        }
+
+        #[ink::test]
+        fn test_nuevo_producto_error() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Comprador);
+
+            let error = sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None).unwrap_err();
+            assert_eq!(error, ErrorSistema::UsuarioNoEsVendedor);//Chequear el estado posterior del sistema (no debería haber ningún producto).
+            assert!(sistema.productos.get(0).is_none());
+
+            //Chequear el estado posterior del sistema (no debería haber ningún producto).
+            assert!(sistema.productos.get(0).is_none());
+        }
+
+        #[ink::test]
+        //Test en el que se registra un producto correctamente desde un usuario que es vendedor.
+        fn test_nuevo_producto_usuario_vendedor() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor);
+
+            let id_producto = sistema.nuevo_producto(String::from("Laptop"), String::from("Laptop gamer"), Categoria::Tecnologia, None).unwrap();
+            // Verifico que el producto se haya registrado correctamente.
+            let producto = sistema.productos.get(&id_producto);
+            assert!(producto.is_some());
+        }
+
+        #[ink::test]
+        //Test en el que se registra un producto correctamente desde un usuario con ambos roles.
+        fn test_nuevo_producto_usuario_ambos() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
+
+            let id_producto = sistema.nuevo_producto(String::from("Laptop"), String::from("Laptop gamer"), Categoria::Tecnologia, None).unwrap();
+            // Verifico que el producto se haya registrado correctamente.
+            let producto = sistema.productos.get(&id_producto);
+            assert!(producto.is_some());
+        }
+
+        #[ink::test]
+        // Test para corroborar que al crear un producto, se agregue tambien al vector del usuario
+        fn test_vector_de_productos_de_usuario(){
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None);
+
+            let user = sistema.usuarios.get(&charlie).unwrap();
+
+            assert_eq!(user.productos.len(),1);
+
+        }
+
+        #[ink::test]
+        // test para no permitir que un usuario cree una publicacion con un producto que no haya creado este
+        fn test_publicacion_con_usuario_sin_producto_seleccionado(){
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None);
+
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Ambos);
+
+            if let Err(e) = sistema.crear_publicacion(0, 20, 1) {
+                assert_eq!(e, ErrorSistema::UsuarioNoTieneProducto);
+            }
+        }
+
+       //-------------------------------------------------------------------------------------
+       //TESTS FUNCIONES INTERNAS:
+
+        #[ink::test]
+        fn test_existe_usuario() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+
+            let mut sistema = Sistema::new();
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Comprador);
+
+            assert!(sistema._existe_usuario(alice).is_ok());
+        }
+
+        #[ink::test]
+        fn test_no_existe_usuaro() {
+            let mut sistema = Sistema::new();
+
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+
+            //Pruebo con un usuario (bob) que no esté en el sistema.
+            assert!(sistema._existe_usuario(bob).is_err());
+        }
+
+        #[ink::test]
+        //Registro un usuario en el sistema, que es vendedor y verifico que exista (con ese rol).
+        fn test_es_vendedor() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+
+            let mut sistema = Sistema::new();
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor);
+
+            //Pruebo con un usuario (alice) que esté en el sistema y sea vendedor.
+            assert!(matches!(sistema.es_vendedor(), Ok(true)));
+        }
+
+        #[ink::test]
+        //Registro un usuario en el sistema, que no es vendedor y verifico exista sin ese rol (que el modulo es_vendedor retorne falso).
+        fn test_no_es_vendedor() {
+            let mut sistema = Sistema::new();
+
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Comprador);
+
+            //Pruebo con un usuario (charlie) que esté en el sistema pero no sea vendedor.
+            assert!(matches!(sistema.es_vendedor(), Ok(false)));
+        }
+
+        #[ink::test]
+        //No registro a un usuario en el sistema, y verifico que no exista (que el modulo es_vendedor retorne error).
+        fn test_es_vendedor_usuario_inexistente() {
+            let mut sistema = Sistema::new(); 
+            
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+
+            //Pruebo con un usuario (bob) que no esté en el sistema.
+            assert!(sistema.es_vendedor().is_err());
+        }
+
+
+        #[ink::test]
+        //Registro un usuario en el sistema, que es comprador y verifico que exista (con ese rol).
+        fn test_es_comprador() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+
+            let mut sistema = Sistema::new();
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Comprador);
+
+            //Pruebo con un usuario (alice) que esté en el sistema y sea comprador.
+            assert!(matches!(sistema.es_comprador(), Ok(true)));
+        }
+
+        #[ink::test]
+        //No registro a un usuario en el sistema, y verifico que no exista (que el modulo es_comprador retorne error).
+        fn test_es_comprador_usuario_inexistente() {
+            let mut sistema = Sistema::new(); 
+
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+
+            //Pruebo con un usuario (bob) que no esté en el sistema.
+            assert!(sistema.es_comprador().is_err());
+        }
+
+        #[ink::test]
+        //Registro un usuario en el sistema, que no es comprador y verifico exista sin ese rol (que el modulo es_comprador retorne falso).
+        fn test_no_es_comprador() {
+            let mut sistema = Sistema::new();
+
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor);
+
+            //Pruebo con un usuario (charlie) que esté en el sistema pero no sea vendedor.
+            assert!(matches!(sistema.es_comprador(), Ok(false)));
+        }
+
+        //-------------------------------------------------------------------------------------
+        //TESTS AGREGAR_ROL:
+        #[ink::test]
+        //Se testea que se pueda agregar el rol de vendedor a un usuario que es comprador.
+        fn test_agregar_roles_a_vendedor() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+
+            let mut sistema = Sistema::new();
+            //Inicializa alice como comprador.
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Comprador);
+
+            //Se agrega el rol de vendedor (pasa a tener ambos).
+            assert!(sistema.agregar_rol(Rol::Vendedor).is_ok());
+            if let Some(user) = sistema.usuarios.get(&alice) {
+                assert!(user.rol == Rol::Ambos);
+            }
+        }
+        #[ink::test]
+        //Se testea que se pueda agregar el rol de comprador a un usuario que es vendedor.
+        fn test_agregar_roles_a_comprador() {
+            //Inicializa bob como vendedor.
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+
+            let mut sistema = Sistema::new();
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Vendedor);
+
+            //Se agrega el rol de vendedor (pasa a tener ambos).
+            assert!(sistema.agregar_rol(Rol::Comprador).is_ok());
+            if let Some(user) = sistema.usuarios.get(&bob) {
+                assert!(user.rol == Rol::Ambos);
+            }
+        }
+
+        #[ink::test]
+        //Se testea que un vendedor no pueda pedir Ambos directamente y que deba agregar el rol de comprador.
+        fn test_agregar_roles_a_ambos() {
+            //Inicializa bob como vendedor.
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+
+            let mut sistema = Sistema::new();
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Vendedor);
+
+            //Pedir Ambos desde un rol simple se rechaza y el rol no cambia.
+            assert_eq!(sistema.agregar_rol(Rol::Ambos).unwrap_err(), ErrorSistema::MismoRolAmbosRedundante);
+            assert_eq!(sistema.usuarios.get(bob).unwrap().rol, Rol::Vendedor);
+
+            //Se agrega el rol de comprador (pasa a tener ambos).
+            assert!(sistema.agregar_rol(Rol::Comprador).is_ok());
+            assert_eq!(sistema.usuarios.get(bob).unwrap().rol, Rol::Ambos);
+        }
+
+        #[ink::test]
+        //Se testean las tres combinaciones que piden Ambos: Comprador + Ambos, Vendedor + Ambos y Ambos + Ambos.
+        fn test_agregar_rol_ambos_politica() {
+            let mut sistema = Sistema::new();
+            let comprador = AccountId::from([0x51; 32]);
+            let vendedor = AccountId::from([0x52; 32]);
+            let ambos = AccountId::from([0x53; 32]);
+            sistema._registrar_usuario("comprador".to_string(), "zz".to_string(), "comprador@gmail.com".to_string(), Rol::Comprador, comprador).unwrap();
+            sistema._registrar_usuario("vendedor".to_string(), "zz".to_string(), "vendedor@gmail.com".to_string(), Rol::Vendedor, vendedor).unwrap();
+            sistema._registrar_usuario("ambos".to_string(), "zz".to_string(), "ambos@gmail.com".to_string(), Rol::Ambos, ambos).unwrap();
+
+            assert_eq!(sistema._agregar_rol(Rol::Ambos, comprador).unwrap_err(), ErrorSistema::MismoRolAmbosRedundante);
+            assert_eq!(sistema.usuarios.get(comprador).unwrap().rol, Rol::Comprador);
+            assert_eq!(sistema._agregar_rol(Rol::Ambos, vendedor).unwrap_err(), ErrorSistema::MismoRolAmbosRedundante);
+            assert_eq!(sistema.usuarios.get(vendedor).unwrap().rol, Rol::Vendedor);
+            assert_eq!(sistema._agregar_rol(Rol::Ambos, ambos).unwrap_err(), ErrorSistema::RolYaEnUso);
+            assert_eq!(sistema.usuarios.get(ambos).unwrap().rol, Rol::Ambos);
+        }
+        
+        #[ink::test]
+        //Se testea que no se pueda agregar un rol que ya tiene el usuario.
+        fn test_agregar_roles_no_okay() {
+            //Inicializa charlie como vendedor.
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+
+            let mut sistema = Sistema::new();
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor);
+
+            //Ya tiene el rol de vendedor. Por lo que no se puede agregar el rol de vendedor otra vez.
+            let error = sistema.agregar_rol(Rol::Vendedor).unwrap_err();
+            assert_eq!(error, ErrorSistema::RolYaEnUso);
+        }
+
+        #[ink::test]
+        //Se testea que no se pueda agregar un rol a un usuario que no existe en el sistema.
+        fn test_agregar_roles_usuario_inexistente() {
+            let mut sistema = Sistema::new();
+            let eve = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().eve;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(eve);
+
+            //Pruebo con un usuario (eve) que no esté en el sistema.
+            let error = sistema.agregar_rol(Rol::Vendedor).unwrap_err();
+            assert_eq!(error, ErrorSistema::UsuarioNoExiste);
+        }
+
+        #[ink::test]
+        /*TEST PARA EL FIX DE ESTA CORRECCIÓN: 
+        "Existe una falla en la lógica que hace posible eliminarse roles al usar la función agregarRol() 
+        teniendo ya el rol de Ambos. Permitiendo, por ejemplo, cambiar del rol Comprador a Ambos,
+         para posteriormente pasar a tener únicamente el rol Vendedor."  */
+        fn agregar_rol_desde_ambos() {
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+
+            let mut sistema = Sistema::new();
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
+
+            // Agrego el rol de Comprador, debería seguir siendo Ambos.
+            assert!(sistema.agregar_rol(Rol::Comprador).is_err());
+            let error = sistema.agregar_rol(Rol::Comprador).unwrap_err();
+            assert_eq!(error, ErrorSistema::RolYaEnUso);
+
+            if let Some(user) = sistema.usuarios.get(&charlie) {
+                assert!(user.rol == Rol::Ambos);
+            }
+        }
+
+        #[ink::test]
+        //Se testean todas las combinaciones (rol actual, rol agregado), incluyendo las que piden Ambos.
+        fn test_agregar_rol_todas_las_combinaciones() {
+            let roles = [Rol::Comprador, Rol::Vendedor, Rol::Ambos];
+            let esperado = |actual: &Rol, nuevo: &Rol| -> Result<Rol, ErrorSistema> {
+                match (actual, nuevo) {
+                    (Rol::Ambos, _) => Err(ErrorSistema::RolYaEnUso),
+                    (a, n) if a == n => Err(ErrorSistema::RolYaEnUso),
+                    (_, Rol::Ambos) => Err(ErrorSistema::MismoRolAmbosRedundante),
+                    _ => Ok(Rol::Ambos),
+                }
+            };
+
+            let mut sistema = Sistema::new();
+            let mut semilla = 0u8;
+            for actual in roles.iter() {
+                for nuevo in roles.iter() {
+                    //Un usuario distinto por combinación.
+                    semilla += 1;
+                    let usuario = AccountId::from([semilla; 32]);
+                    sistema._registrar_usuario("usuario".to_string(), "zz".to_string(), "usuario@gmail.com".to_string(), actual.clone(), usuario).unwrap();
+
+                    let resultado = sistema._agregar_rol(nuevo.clone(), usuario);
+                    let rol_final = sistema.usuarios.get(usuario).unwrap().rol;
+                    match esperado(actual, nuevo) {
+                        Ok(rol) => {
+                            assert!(resultado.is_ok(), "{:?} + {:?}", actual, nuevo);
+                            assert_eq!(rol_final, rol);
+                        }
+                        Err(e) => {
+                            assert_eq!(resultado.unwrap_err(), e, "{:?} + {:?}", actual, nuevo);
+                            assert_eq!(&rol_final, actual);
+                        }
+                    }
+                }
+            }
+        }
+
+        #[ink::test]
+        //Se testea que un usuario con rol Ambos pueda quitarse un rol y que no se pueda quitar el único rol.
+        fn test_quitar_rol() {
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+
+            let mut sistema = Sistema::new();
+            assert_eq!(sistema.quitar_rol(Rol::Vendedor).unwrap_err(), ErrorSistema::UsuarioNoExiste);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos).unwrap();
+
+            assert_eq!(sistema.quitar_rol(Rol::Ambos).unwrap_err(), ErrorSistema::NoSePuedeQuitarRol);
+            assert!(sistema.quitar_rol(Rol::Comprador).is_ok());
+            assert_eq!(sistema.usuarios.get(charlie).unwrap().rol, Rol::Vendedor);
+
+            //Ya no puede quitarse el único rol que le queda, ni uno que no tiene.
+            assert_eq!(sistema.quitar_rol(Rol::Vendedor).unwrap_err(), ErrorSistema::NoSePuedeQuitarRol);
+            assert_eq!(sistema.quitar_rol(Rol::Comprador).unwrap_err(), ErrorSistema::NoSePuedeQuitarRol);
+            assert_eq!(sistema.usuarios.get(charlie).unwrap().rol, Rol::Vendedor);
+        }
+
+        #[ink::test]
+        //Se testea que no se pueda quitar el rol Vendedor con publicaciones activas ni Comprador con órdenes en curso.
+        fn test_quitar_rol_con_actividad() {
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            let mut sistema = Sistema::new();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 10, 2).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Ambos).unwrap();
+            sistema.generar_orden_compra(vec![(0, 1)], 100, Some(String::from("Calle 1 123"))).unwrap();
+
+            //Bob tiene una orden pendiente, no puede dejar de ser comprador.
+            assert_eq!(sistema.quitar_rol(Rol::Comprador).unwrap_err(), ErrorSistema::CompradorConOrdenesEnCurso);
+
+            //Charlie tiene una publicación activa, no puede dejar de ser vendedor.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            assert_eq!(sistema.quitar_rol(Rol::Vendedor).unwrap_err(), ErrorSistema::VendedorConPublicacionesActivas);
+            assert_eq!(sistema.usuarios.get(charlie).unwrap().rol, Rol::Ambos);
+
+            //Una vez cancelada la orden, Bob puede dejar de ser comprador.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.cancelar_orden(0).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.cancelar_orden(0).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            assert!(sistema.quitar_rol(Rol::Comprador).is_ok());
+            assert_eq!(sistema.usuarios.get(bob).unwrap().rol, Rol::Vendedor);
+
+            //Con la publicación desactivada, aunque le quede stock, Charlie puede dejar de ser vendedor.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.publicaciones[0].activa = false;
+            assert!(sistema.publicaciones[0].stock > 0);
+            assert!(sistema.quitar_rol(Rol::Vendedor).is_ok());
+            assert_eq!(sistema.usuarios.get(charlie).unwrap().rol, Rol::Comprador);
+        }
+
+        #[ink::test]
+        //Se testea que puede_publicar y puede_comprar respondan según el rol y devuelvan false para usuarios no registrados.
+        fn test_puede_publicar_y_comprar() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let eve = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().eve;
+            let mut sistema = Sistema::new();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Comprador).unwrap();
+            assert!(sistema.puede_comprar());
+            assert!(!sistema.puede_publicar());
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Vendedor).unwrap();
+            assert!(!sistema.puede_comprar());
+            assert!(sistema.puede_publicar());
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos).unwrap();
+            assert!(sistema.puede_comprar());
+            assert!(sistema.puede_publicar());
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(eve);
+            assert!(!sistema.puede_comprar());
+            assert!(!sistema.puede_publicar());
+        }
+
+        //-------------------------------------------------------------------------------------
+        //TESTS ORDEN DE COMPRA:
+
+        #[ink::test]
+        //Test para verificar que no se puede generar una orden de compra sin items.
+        fn generar_orden_compra_sin_items() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+
+            let mut sistema = Sistema::new();
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Comprador);
+
+            //Pruebo generar una orden de compra sin items.
+            let error = sistema.generar_orden_compra(Vec::<(u128, u32)>::new(), 100, Some(String::from("Calle 1 123"))).unwrap_err();
+            assert_eq!(error, ErrorSistema::CompraSinItems);
+        }
+
+        #[ink::test]
+        //Test para verificar que no se puede generar una orden de compra de una publicacion que no existe.
+        fn test_generar_orden_compra_error() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
+
+            //Quiero forzar el error de publicacionNoValida
+            //No existe la publicación con id 0.
+            let error_publicacion_invalida = sistema.generar_orden_compra(vec![(0, 1)],1, Some(String::from("Calle 1 123"))).unwrap_err();
+            assert_eq!(error_publicacion_invalida, ErrorSistema::PublicacionNoValida); //Ok
+
+            //Verifico que no se haya agregado ninguna orden de compra. (Estado posterior del sistema).
+            assert!(sistema.id_ordenes.is_empty());
+        }
+
+        #[ink::test]
+        //Test para verificar que no se puede generar una orden de compra de una publicación propia.
+        fn test_generar_orden_compra_propia() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
+
+            //Quiero forzar el error de NoPuedeComprarPublicacionPropia
+            //Charlie crea una publicación y luego intenta comprarla.
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None);
+            sistema.crear_publicacion(0, 1000, 4);
+
+            let error_no_puede_comprar_publicacion_propia = sistema.generar_orden_compra(vec![(0, 1)],4000, Some(String::from("Calle 1 123"))).unwrap_err();
+            assert_eq!(error_no_puede_comprar_publicacion_propia, ErrorSistema::NoPuedeComprarPublicacionPropia); //Ok.
+
+            //Verifico que no se haya agregado ninguna orden de compra. (Estado posterior del sistema).
+            assert!(sistema.id_ordenes.is_empty());
+        }
+
+        #[ink::test]
+        //Test para verificar que no se puede generar una orden de compra con dinero insuficiente.
+        fn test_generar_orden_compra_dinero_insuficiente() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
+
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None);
+            sistema.crear_publicacion(0, 1000, 4); //La publicación la crea Charlie.
+
+            //Preparo al otro usuario para que compre de esa publicación. (Ya que no se puede generar una orden de compra a partir de una publicación propia).
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Ambos);
+            //Alice intenta comprar 1 termo, pero no tiene suficiente dinero (solo tiene 500).
+
+            //Quiero forzar el error de FondosInsuficientes
+            let error_dinero_insuficiente = sistema.generar_orden_compra(vec![(0, 1)], 500, Some(String::from("Calle 1 123"))).unwrap_err();
+            assert_eq!(error_dinero_insuficiente, ErrorSistema::FondosInsuficientes { requerido: 1000, disponible: 500 }); //Ok.
+
+            //Verifico que no se haya agregado ninguna orden de compra. (Estado posterior del sistema).
+            assert!(sistema.id_ordenes.is_empty());
+        }
+
+        #[ink::test]
+        //Test para verificar que un usuario no pueda generar una orden de compra si no es comprador.
+        fn test_orden_compra_no_comprador() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor);
+
+            //Quiero forzar el error de UsuarioNoEsComprador
+            let error_usuario_no_comprador = sistema.generar_orden_compra(vec![(0, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap_err();
+            assert_eq!(error_usuario_no_comprador, ErrorSistema::UsuarioNoEsComprador); //Ok.
+
+            //Verifico que no se haya agregado ninguna orden de compra. (Estado posterior del sistema).
+            assert!(sistema.id_ordenes.is_empty());
+        }
+
+        #[ink::test]
+        //Test para verificar que un usuario no pueda generar una orden de compra si no existe en el sistema.
+        fn test_orden_compra_usuario_inexistente() {
+            let mut sistema = Sistema::new();
+            let eve = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().eve;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(eve);
+
+            //Quiero forzar el error de UsuarioNoExiste
+            let error_usuario_no_existe = sistema.generar_orden_compra(vec![(0, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap_err();
+            assert_eq!(error_usuario_no_existe, ErrorSistema::UsuarioNoExiste); //Ok.
+
+            //Verifico que no se haya agregado ninguna orden de compra. (Estado posterior del sistema).
+            assert!(sistema.id_ordenes.is_empty());
+        }
+
+
+        #[ink::test]
+        //Test para verificar que se pueda generar una orden de compra correctamente.
+        fn test_ver_mis_ordenes() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
+
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None);
+            sistema.crear_publicacion(0, 1000, 4); //La publicación la crea Charlie.
+
+            //Preparo al otro usuario para que compre de esa publicación. (Ya que no se puede generar una orden de compra a partir de una publicación propia).
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Ambos);
+            let lista_compra = vec![(0, 1)];
+            assert!(sistema.generar_orden_compra(lista_compra,4000, Some(String::from("Calle 1 123"))).is_ok());
+
+            //Verifico que la orden de compra se haya agregado a las órdenes del usuario Alice.
+            let mis_ordenes = sistema.ver_mis_ordenes();
+            assert_eq!(mis_ordenes.len(), 1);
+        }
+
+        #[ink::test]
+        //Test que verifica que categorias_compradas devuelva las categorías sin repetir de las órdenes recibidas.
+        fn test_categorias_compradas() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap(); //ID 0
+            sistema.nuevo_producto("Remera".to_string(), "Remera".to_string(), Categoria::Ropa, None).unwrap(); //ID 1
+            sistema.nuevo_producto("Pantalon".to_string(), "Pantalon".to_string(), Categoria::Ropa, None).unwrap(); //ID 2
+            sistema.crear_publicacion(0, 10, 10).unwrap();
+            sistema.crear_publicacion(1, 10, 10).unwrap();
+            sistema.crear_publicacion(2, 10, 10).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Comprador).unwrap();
+            sistema.generar_orden_compra(vec![(0, 1), (1, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap(); //Orden 0
+            sistema.generar_orden_compra(vec![(2, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap(); //Orden 1
+
+            //Sin órdenes recibidas no hay categorías.
+            assert!(sistema.categorias_compradas(alice).unwrap().is_empty());
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.marcar_orden_como_enviada(0).unwrap();
+            sistema.marcar_orden_como_enviada(1).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.marcar_orden_como_recibida(0).unwrap();
+            sistema.marcar_orden_como_recibida(1).unwrap();
+
+            //Ropa aparece en dos órdenes pero se devuelve una sola vez.
+            assert_eq!(sistema.categorias_compradas(alice).unwrap(), vec![Categoria::Limpieza, Categoria::Ropa]);
+
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            assert_eq!(sistema.categorias_compradas(bob).unwrap_err(), ErrorSistema::UsuarioNoExiste);
+        }
+
+        #[ink::test]
+        //Test que verifica que mi_orden_mayor devuelva la orden de mayor monto (y la más reciente ante empates).
+        fn test_mi_orden_mayor() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None).unwrap();
+            sistema.crear_publicacion(0, 100, 20).unwrap();
+
+            //Sin órdenes no hay nada para devolver.
+            assert!(sistema.mi_orden_mayor().is_none());
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Comprador).unwrap();
+            sistema.generar_orden_compra(vec![(0, 2)], 10000, Some(String::from("Calle 1 123"))).unwrap(); //Orden 0, monto 200
+            sistema.generar_orden_compra(vec![(0, 5)], 10000, Some(String::from("Calle 1 123"))).unwrap(); //Orden 1, monto 500
+            sistema.generar_orden_compra(vec![(0, 1)], 10000, Some(String::from("Calle 1 123"))).unwrap(); //Orden 2, monto 100
+
+            let mayor = sistema.mi_orden_mayor().unwrap();
+            assert_eq!(mayor.id_orden_compra, 1);
+            assert_eq!(mayor.monto, 500);
+
+            //Ante un empate se devuelve la más reciente.
+            sistema.generar_orden_compra(vec![(0, 5)], 10000, Some(String::from("Calle 1 123"))).unwrap(); //Orden 3, monto 500
+            assert_eq!(sistema.mi_orden_mayor().unwrap().id_orden_compra, 3);
+
+            //El vendedor también ve su orden de mayor monto.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            assert_eq!(sistema.mi_orden_mayor().unwrap().id_orden_compra, 3);
+        }
+
+        #[ink::test]
+        fn test_agregar_orden_usuario() {
+            let mut sistema = Sistema::new();
+
+            let error_usuario_no_existe = sistema.agregar_orden_usuario(AccountId::from([0x1; 32]), 1).unwrap_err();
+            assert_eq!(error_usuario_no_existe, ErrorSistema::UsuarioNoExiste); //No existe el usuario que llama a la función.
+        }
+
+        #[ink::test]
+        //Test para verificar que no se puede agregar una orden de compra con publicaciones repetidas (no pasa la validación).
+        fn test_validar_orden_publicacion_repetida() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
+
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None);
+            sistema.crear_publicacion(0, 1000, 4); //La publicación la crea Charlie.
+
+            //Quiero forzar el error de PublicacionRepetida.
+            let error_publicacion_repetida = sistema.validar_orden(vec![(0, 1), (0, 2)], charlie, bob, &[]).unwrap_err(); 
+            assert_eq!(error_publicacion_repetida, ErrorSistema::PublicacionRepetida); 
+        }
+
+        #[ink::test]
+        //Test para verificar que no se puede generar una orden de compra con cantidad cero (no pasa la validación).
+        fn test_validar_orden_comprar_cero() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
+
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None);
+            sistema.crear_publicacion(0, 1000, 4); //La publicación la crea Charlie.
+
+            //Quiero forzar el error de NoPuedeComprarCero.
+            let error_no_puede_comprar_cero = sistema.validar_orden(vec![(0, 0)], charlie, bob, &[]).unwrap_err(); 
+            assert_eq!(error_no_puede_comprar_cero, ErrorSistema::NoPuedeComprarCero); //Ok.
+        }
+
+        #[ink::test]
+        //Test para verificar que no se puede generar una orden de compra con productos de distintos usuarios vendedores (no pasa la validación).
+        fn test_validar_orden_vendedor_distinto() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
+
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None);
+            sistema.crear_publicacion(0, 1000, 4); //La publicación la crea Charlie.
+
+            //Quiero forzar el error de VendedorDistinto.
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Ambos);
+
+            let error_vendedor_distinto = sistema.validar_orden(vec![(0, 1)], alice, alice, &[]).unwrap_err();
+            assert_eq!(error_vendedor_distinto, ErrorSistema::VendedorDistinto); //Ok.
+        }
+
+        #[ink::test]
+        //Test para verificar que no se puede generar una orden de compra con stock insuficiente (no pasa la validación).
+        fn test_validar_orden_compra_stock_insuficiente() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
+
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None);
+            sistema.crear_publicacion(0, 1000, 4); //La publicación la crea Charlie.
+
+            //Quiero forzar el error de StockInsuficienteDetalle.
+            let error_stock_insuficiente = sistema.validar_orden(vec![(0, 5)], charlie, bob, &[]).unwrap_err(); //El stock es 4, y estoy tratando de comprar 5.
+            assert_eq!(error_stock_insuficiente, ErrorSistema::StockInsuficienteDetalle { id_publicacion: 0, solicitado: 5, disponible: 4 }); //Ok.
+        }
+
+        #[ink::test]
+        //Test para verificar que no se puede agregar una orden de compra con publicaciones no válidas (no pasa la validación).
+        fn test_validar_orden_publicacion_no_valida() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
+
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None);
+            sistema.crear_publicacion(0, 1000, 4); //La publicación la crea Charlie.
+
+            //Quiero forzar el error de PublicacionNoValida.
+            let error_publicacion_invalida = sistema.validar_orden(vec![(1, 1)], charlie, bob, &[]).unwrap_err();
+            assert_eq! (error_publicacion_invalida, ErrorSistema::PublicacionNoValida); //Ok.
+        }
+
+        //-------------------------------------------------------------------------------------
+        //TEST ESTADOS DE ORDEN
+  
+        #[ink::test]
+        //Este test es para ver si salta el error (operación no válida) al tratar de cancelar una orden ya recibida (ya que es algo que no se puede hacer).
+        fn test_cancelar_orden_ya_recibida() {
+            //Genero una orden de compra
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor);
+
+            //Creo el producto.
+            if let Ok(id) = sistema.nuevo_producto("banana".to_string(), "una banana".to_string(), Categoria::Limpieza, None){
+                assert_eq!(id, 0);
+            }
+
+            //Creo la publicación.
+            sistema.crear_publicacion(0, 10, 19);
+
+            let mut lista_compra = Vec::new();
+            lista_compra.push((0,2));
+
+            //Preparo al otro usuario para que compre de esa publicación. (Ya que no se puede generar una orden de compra a partir de una publicación propia).
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Ambos);
+            
+            assert!(sistema.generar_orden_compra(lista_compra, 200, Some(String::from("Calle 1 123"))).is_ok());
+
+            //Marco como enviado (desde Charlie).
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+
+            assert!(sistema.marcar_orden_como_enviada(0).is_ok());
+            if let Some(orden) = sistema.ordenes.get(0){
+                assert_eq!(orden.estado, EstadoOrdenCompra::Enviado);
+            }
+
+            //Marco como recibido (desde Alice).
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            assert!(sistema.marcar_orden_como_recibida(0).is_ok());
+
+            //Trato de cancelar la orden (desde Alice) (esto debería fallar).
+            let error = sistema.cancelar_orden(0).unwrap_err();
+            assert_eq!(error, ErrorSistema::OperacionNoValida);
+
+
+            //Trato de cancelar la orden también desde Charlie.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            let error = sistema.cancelar_orden(0).unwrap_err();
+            assert_eq!(error, ErrorSistema::OperacionNoValida);
+
+
+            //Chequeo estado del sistema posteriormente (ver si no se modificó el estado de la orden).
+            if let Some(orden) = sistema.ordenes.get(0){
+                assert_eq!(orden.estado, EstadoOrdenCompra::Recibido);
+            }
+        }
+
+        #[ink::test]
+        //Test que verifica que no se puede marcar la orden como enviada si el id de la misma es inválido.
+        fn test_marcar_orden_como_enviada_id_invalido() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
+
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None);
+            sistema.crear_publicacion(0, 1000, 4); //La publicación la crea Charlie.
+
+            //Quiero forzar el error de IdDeOrdenNoValida.
+            let error_id_invalido = sistema.marcar_orden_como_enviada(0).unwrap_err();
+            assert_eq!(error_id_invalido, ErrorSistema::IdDeOrdenNoValida); //No existe la orden con id 0.
+
+            //Chequeo estado del sistema posteriormente (ver si no se modificó el estado de la orden).
+            if let Some(orden) = sistema.ordenes.get(0){
+                assert_eq!(orden.estado, EstadoOrdenCompra::Pendiente);
+            }
+        }
+
+        #[ink::test]
+        ///Test que verifica que no se puede marcar una orden como enviada si el caller no es el vendedor de la orden.
+        fn test_marcar_orden_enviada_caller_invalido() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
+
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None);
+            sistema.crear_publicacion(0, 1000, 4); //La publicación la crea Charlie.
+
+            //Creo una orden de compra para que exista una orden con id 0.
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Ambos);
+
+            let lista_compra = vec![(0, 1)];
+            assert!(sistema.generar_orden_compra(lista_compra,4000, Some(String::from("Calle 1 123"))).is_ok());
+
+            //Quiero forzar el error de UsuarioNoAutorizado.
+            let error_no_autorizado = sistema.marcar_orden_como_enviada(0).unwrap_err(); //La estoy tratando de marcar como enviada desde Alice, pero la orden la creó Charlie.
+            assert_eq!(error_no_autorizado, ErrorSistema::UsuarioNoAutorizado); //El caller no es el vendedor de la orden.
+
+            //Chequeo estado del sistema posteriormente (ver si no se modificó el estado de la orden).
+            if let Some(orden) = sistema.ordenes.get(0){
+                assert_eq!(orden.estado, EstadoOrdenCompra::Pendiente);
+            }
+        }
+
+        #[ink::test]
+        //Test que verifica que no se puede marcar una orden como enviada si la orden ya fue enviada.
+        fn test_marcar_orden_como_enviada_doble() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
+
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None);
+            sistema.crear_publicacion(0, 1000, 4); //La publicación la crea Charlie.
+
+            //Creo una orden de compra para que exista una orden con id 0.
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Ambos);
+
+            let lista_compra = vec![(0, 1)];
+            assert!(sistema.generar_orden_compra(lista_compra,4000, Some(String::from("Calle 1 123"))).is_ok());
+
+            //Quiero forzar el error de OrdenYaEnviada porque la orden ya fue enviada.
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+
+            assert!(sistema.marcar_orden_como_enviada(0).is_ok()); //Primero lo marco como enviada.
+
+            //Trato de enviarlo otra vez.
+            let error_orden_ya_enviada = sistema.marcar_orden_como_enviada(0).unwrap_err();
+            assert_eq!(error_orden_ya_enviada, ErrorSistema::OrdenYaEnviada); //La orden ya fue enviada.
+
+            //Chequeo estado del sistema posteriormente (ver si el estado de la orden quedó como enviado).
+            if let Some(orden) = sistema.ordenes.get(0){
+                assert_eq!(orden.estado, EstadoOrdenCompra::Enviado);
+            }
+        }
+
+        #[ink::test]
+        //Test para verificar que se puede marcar una orden como enviada correctamente.
+        fn test_marcar_orden_enviada_okay() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
+
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None);
+            sistema.crear_publicacion(0, 1000, 4); //La publicación la crea Charlie.
+
+            //Creo una orden de compra para que exista una orden con id 0.
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Ambos);
+
+            //Genero la orden de compra.
+            let lista_compra = vec![(0, 1)];
+            assert!(sistema.generar_orden_compra(lista_compra,4000, Some(String::from("Calle 1 123"))).is_ok());
+
+
+            //Quiero marcar la orden como recibida.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            assert!(sistema.marcar_orden_como_enviada(0).is_ok()); //Lo marco como enviada.
+
+            //Chequeo el estado de la orden. (Estado posterior del sistema).
+            if let Some(orden) = sistema.ordenes.get(0){
+                assert_eq!(orden.estado, EstadoOrdenCompra::Enviado);
+            } else {
+                panic!("La orden no fue encontrada después de marcarla como enviada.");
+            }
+
+        }
+
+        #[ink::test]
+        //Test que verifica que se puede marcar una orden como recibida correctamente.
+        fn test_marcar_orden_como_recibida() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
+
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None);
+            sistema.crear_publicacion(0, 1000, 4); //La publicación la crea Charlie.
+
+            //Creo una orden de compra para que exista una orden con id 0.
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Ambos);
+
+            //Genero la orden de compra.
+            let lista_compra = vec![(0, 1)];
+            assert!(sistema.generar_orden_compra(lista_compra,4000, Some(String::from("Calle 1 123"))).is_ok());
+
+
+            //Quiero marcar la orden como recibida.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.marcar_orden_como_enviada(0); //primero lo marco como enviada
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            assert_eq!(sistema.marcar_orden_como_recibida(0), Ok(()));
+
+            //Chequeo el estado de la orden. (Estado posterior del sistema).
+            if let Some(orden) = sistema.ordenes.get(0){
+                assert_eq!(orden.estado, EstadoOrdenCompra::Recibido);
+            } else {
+                panic!("La orden no fue encontrada después de marcarla como recibida.");
+            }
+        }
+
+        #[ink::test]
+        //Test que verifica que no se puede marcar una orden como recibida si quien lo hace es el usuario que la creó.
+        fn test_marcar_orden_recibida_mismo_caller() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
+
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None);
+            sistema.crear_publicacion(0, 1000, 4); //La publicación la crea Charlie.
+
+            //Creo una orden de compra para que exista una orden con id 0.
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Ambos);
+
+            //Genero la orden de compra.
+            let lista_compra = vec![(0, 1)];
+            assert!(sistema.generar_orden_compra(lista_compra,4000, Some(String::from("Calle 1 123"))).is_ok());
+
+
+            //Quiero marcar la orden como recibida.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.marcar_orden_como_enviada(0); //primero lo marco como enviada
+
+            //Chequeo que el usuario que marcó como enviada no pueda marcar como recibida. (No cambié el caller).
+            if let Err(e) = sistema.marcar_orden_como_recibida(0) {
+                assert_eq!(e, ErrorSistema::UsuarioNoAutorizado);
+            }
+
+            //Chequeo el estado de la orden. (Estado posterior del sistema).
+            //La orden no debería haber cambiado su estado.
+            if let Some(orden) = sistema.ordenes.get(0){
+                assert_eq!(orden.estado, EstadoOrdenCompra::Enviado);
+            }
+        }
+
+        #[ink::test]
+        //Test que verifica que no se puede marcar una orden como recibida si el id de la misma es inválido.
+        fn test_marcar_orden_enviada_orden_invalida() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
+
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None);
+            sistema.crear_publicacion(0, 1000, 4); //La publicación la crea Charlie.
+
+            //Quiero forzar el error de IdDeOrdenNoValida.
+            let error_id_invalido = sistema.marcar_orden_como_enviada(0).unwrap_err();
+            assert_eq!(error_id_invalido, ErrorSistema::IdDeOrdenNoValida); //No existe la orden con id 0.
+        }
+
+        #[ink::test]
+        //Test que verifica que no se puede marcar una orden como enviada, que ya fue (previamente) recibida.
+        fn test_marcar_orden_enviada_orden_recibida() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
+
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None);
+            sistema.crear_publicacion(0, 1000, 4); //La publicación la crea Charlie.
+
+            //Creo una orden de compra para que exista una orden con id 0.
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Ambos);
+
+            //Genero la orden de compra.
+            let lista_compra = vec![(0, 1)];
+            assert!(sistema.generar_orden_compra(lista_compra,4000, Some(String::from("Calle 1 123"))).is_ok());
+
+            //Quiero marcar la orden como enviada.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            assert!(sistema.marcar_orden_como_enviada(0).is_ok()); //Lo marco como enviada.
+
+            //Ahora quiero marcarla como recibida.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            assert!(sistema.marcar_orden_como_recibida(0).is_ok()); //Lo marco como recibida.
+
+            //Quiero forzar el error de OperacionNoValida porque la orden ya fue recibida (desde el vendedor).
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            let error_operacion_no_valida = sistema.marcar_orden_como_enviada(0).unwrap_err();
+            assert_eq!(error_operacion_no_valida, ErrorSistema::OperacionNoValida); //La orden ya fue recibida.
+
+            //Chequeo el estado de la orden. (Estado posterior del sistema).
+            if let Some(orden) = sistema.ordenes.get(0){
+                assert_eq!(orden.estado, EstadoOrdenCompra::Recibido);
+            } else {
+                panic!("La orden no fue encontrada después de marcarla como recibida.");
+            }
+        }
+
+        #[ink::test]
+        //Test que verifica que no se puede marcar una orden como recibida si la orden no fue marcada como enviada previamente.
+        fn test_marcar_orden_recibida_sin_envio() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
+
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None);
+            sistema.crear_publicacion(0, 1000, 4); //La publicación la crea Charlie.
+
+            //Creo una orden de compra para que exista una orden con id 0.
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Ambos);
+
+            let lista_compra = vec![(0, 1)];
+            assert!(sistema.generar_orden_compra(lista_compra,4000, Some(String::from("Calle 1 123"))).is_ok());
+
+
+            //Quiero forzar el error de OperacionNoValida.
+            let error_operacion_no_valida = sistema.marcar_orden_como_recibida(0).unwrap_err();
+            assert_eq!(error_operacion_no_valida, ErrorSistema::OperacionNoValida); //El caller trata de marcar la orden como recibida sin que esta fuera marcada como enviada previamente.
+        }
+
+        #[ink::test]
+        //Test que verifica que no se puede marcar una orden como recibida si el caller no es el comprador de la orden.
+        fn test_marcar_orden_recibida_caller_invalido() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
+
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None);
+            sistema.crear_publicacion(0, 1000, 4); //La publicación la crea Charlie.
+
+            //Creo una orden de compra para que exista una orden con id 0.
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Ambos);
+
+            let lista_compra = vec![(0, 1)];
+            assert!(sistema.generar_orden_compra(lista_compra,4000, Some(String::from("Calle 1 123"))).is_ok());
+
+            //Primero la marco como enviada desde quien creo la publicación (Charlie).
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.marcar_orden_como_enviada(0); //Primero lo marco como enviada.
+
+            //Quiero forzar el error de UsuarioNoAutorizado.
+            let error_caller_invalido = sistema.marcar_orden_como_recibida(0).unwrap_err();
+            assert_eq!(error_caller_invalido, ErrorSistema::UsuarioNoAutorizado); //El caller no es el comprador de la orden.
+
+            //Verifico el error de marcar como recibida una orden que no existe
+            if let Err(e) = sistema.marcar_orden_como_recibida(5){
+                assert_eq!(e, ErrorSistema::IdDeOrdenNoValida)
+            }
+        }        
+
+
+        #[ink::test]
+        //Test que verifica que no se puede cancelar una orden cuando el id de la misma es inválido.
+        fn test_cancelar_orden_invalida() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
+
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None);
+            sistema.crear_publicacion(0, 1000, 4); //La publicación la crea Charlie.
+
+            //Quiero forzar el error de IdDeOrdenNoValida.
+            let error_id_invalido = sistema.cancelar_orden(0).unwrap_err();
+            assert_eq!(error_id_invalido, ErrorSistema::IdDeOrdenNoValida); //No existe la orden con id 0.     
+        }
+
+        #[ink::test]
+        //Test que verifica que se puede cancelar una orden correctamente.
+        fn test_cancelar_orden() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
+
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None);
+            sistema.crear_publicacion(0, 1000, 4); //La publicación la crea Charlie.
+
+            //Creo una orden de compra para que exista una orden con id 0.
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Ambos);
+
+            let lista_compra = vec![(0, 1)];
+            assert!(sistema.generar_orden_compra(lista_compra,4000, Some(String::from("Calle 1 123"))).is_ok());
+
+            //Quiero cancelar la orden.
+            //Primero cancelo desde quien lo compró (alice).
+            assert!(sistema.cancelar_orden(0).is_ok());
+
+            //Chequeo  que el estado de la orden no se modificó todavía (porque falta la segunda parte de la cancelación).
+            if let Some(orden) = sistema.ordenes.get(0){
+                assert_eq!(orden.estado, EstadoOrdenCompra::Pendiente);
+            } else {
+                panic!("La orden no fue encontrada después de cancelarla.");
+            }
+
+            //Chequeo que una vez solicitada, no puede solicitar su cancelacion de nuevo
+            if let Err(e) = sistema.cancelar_orden(0) {
+                assert_eq!(e, ErrorSistema::CancelacionYaSolicitada)
+            }
+
+            //Ahora cancelo desde quien la creó (Charlie).
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            assert!(sistema.cancelar_orden(0).is_ok());
+
+            //Chequeo que el estado de la orden cambió a cancelado.
+            if let Some(orden) = sistema.ordenes.get(0){
+                assert_eq!(orden.estado, EstadoOrdenCompra::Cancelado);
+            } else {
+                panic!("La orden no fue encontrada después de cancelarla.");
+            }
+
+            //Chequeo que una vez cancelado, no pueda solicitar su cancelacion de nuevo
+            if let Err(e) = sistema.cancelar_orden(0) {
+                assert_eq!(e, ErrorSistema::OrdenCancelada)
+            }
+        }
+
+        #[ink::test]
+        //Test que verifica que enviar una orden cancelada devuelva OrdenCancelada y no OperacionNoValida.
+        fn test_enviar_orden_cancelada() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            let mut sistema = Sistema::new();
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 100, 10).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            sistema.generar_orden_compra(vec![(0, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+            sistema.generar_orden_compra(vec![(0, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+            sistema.cancelar_orden(0).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.cancelar_orden(0).unwrap();
+            assert_eq!(sistema.ordenes.get(0).unwrap().estado, EstadoOrdenCompra::Cancelado);
+            assert_eq!(sistema.marcar_orden_como_enviada(0).unwrap_err(), ErrorSistema::OrdenCancelada);
+
+            //Una orden ya enviada devuelve OrdenYaEnviada, y una recibida sigue devolviendo OperacionNoValida.
+            sistema.marcar_orden_como_enviada(1).unwrap();
+            assert_eq!(sistema.marcar_orden_como_enviada(1).unwrap_err(), ErrorSistema::OrdenYaEnviada);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.marcar_orden_como_recibida(1).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            assert_eq!(sistema.marcar_orden_como_enviada(1).unwrap_err(), ErrorSistema::OperacionNoValida);
+        }
+
+        #[ink::test]
+        // test que verifica que un usuario que no esta involucrado en una orden no pueda cancelarla
+        fn test_tercero_no_puede_cancelar_orden(){
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+
+            sistema.guardar_orden(&
+                OrdenCompra {
+                    lista_productos: vec!((1,1)),
+                    lista_publicaciones: Vec::new(),
+                    id_orden_compra: 0,
+                    estado: EstadoOrdenCompra::Pendiente,
+                    id_comprador:charlie,
+                    id_vendedor:alice,
+                    solicitud_cancelacion: None,
+                    monto:23,
+                    puntuado_por_comprador:false,
+                    puntuado_por_vendedor:false,
+                    creada_en:0,
+                    actualizada_en:0,
+                    expira_en:0,
+                    historial: Vec::new(),
+                    envios: Vec::new(),
+                    comision: 0,
+                    direccion_envio: None,
+                    recepciones: Vec::new(),
+                }
+            );
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+
+            if let Err(e) = sistema.cancelar_orden(0){
+                assert_eq!(e, ErrorSistema::UsuarioNoAutorizado);
+            }
+        }
+
+        #[ink::test]
+        //Test que verifica que la orden registre cuándo se creó y cuándo cambió de estado por última vez.
+        fn test_timestamps_orden() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None).unwrap();
+            sistema.crear_publicacion(0, 1000, 4).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Comprador).unwrap();
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1000);
+            sistema.generar_orden_compra(vec![(0, 1)], 4000, Some(String::from("Calle 1 123"))).unwrap();
+
+            let orden = sistema.get_orden(0).unwrap();
+            assert_eq!(orden.creada_en, 1000);
+            assert_eq!(orden.actualizada_en, 1000);
+
+            //Avanzo el tiempo y el vendedor envía la orden.
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(5000);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.marcar_orden_como_enviada(0).unwrap();
+
+            let orden = sistema.get_orden(0).unwrap();
+            assert_eq!(orden.estado, EstadoOrdenCompra::Enviado);
+            assert_eq!(orden.creada_en, 1000);
+            assert_eq!(orden.actualizada_en, 5000);
+
+            //Al recibirla se vuelve a actualizar.
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(9000);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.marcar_orden_como_recibida(0).unwrap();
+            assert_eq!(sistema.get_orden(0).unwrap().actualizada_en, 9000);
+
+            //Un tercero no puede ver la orden.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            assert_eq!(sistema.get_orden(0).unwrap_err(), ErrorSistema::UsuarioNoAutorizado);
+            assert_eq!(sistema.get_orden(1).unwrap_err(), ErrorSistema::IdDeOrdenNoValida);
+        }
+
+        #[ink::test]
+        //Test que verifica que el comprador pueda expirar una orden pendiente una vez vencido el plazo.
+        fn test_expirar_orden_vencida() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None).unwrap();
+            sistema.crear_publicacion(0, 100, 4).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Comprador).unwrap();
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1000);
+            sistema.generar_orden_compra(vec![(0, 3)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+            assert_eq!(sistema.publicaciones[0].stock, 1);
+            assert_eq!(sistema.escrow_actual(), 300);
+
+            //El vendedor no puede expirar la orden.
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1001 + PLAZO_EXPIRACION_ORDEN);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            assert_eq!(sistema.expirar_orden(0).unwrap_err(), ErrorSistema::UsuarioNoAutorizado);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            assert!(sistema.expirar_orden(0).is_ok());
+
+            //La orden queda cancelada, se devuelve el stock y se libera el escrow.
+            assert_eq!(sistema.ordenes.get(0).unwrap().estado, EstadoOrdenCompra::Cancelado);
+            assert_eq!(sistema.publicaciones[0].stock, 4);
+            assert_eq!(sistema.escrow_actual(), 0);
+
+            //No se puede expirar dos veces.
+            assert_eq!(sistema.expirar_orden(0).unwrap_err(), ErrorSistema::OperacionNoValida);
+        }
+
+        #[ink::test]
+        //Test que verifica que no se pueda expirar una orden antes del plazo ni una orden ya enviada.
+        fn test_expirar_orden_antes_de_plazo() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None).unwrap();
+            sistema.crear_publicacion(0, 100, 4).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Comprador).unwrap();
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1000);
+            sistema.generar_orden_compra(vec![(0, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+            sistema.generar_orden_compra(vec![(0, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+
+            //Justo en el límite del plazo todavía no expira.
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1000 + PLAZO_EXPIRACION_ORDEN);
+            assert_eq!(sistema.expirar_orden(0).unwrap_err(), ErrorSistema::OperacionNoValida);
+            assert_eq!(sistema.ordenes.get(0).unwrap().estado, EstadoOrdenCompra::Pendiente);
+
+            //Una orden enviada no expira aunque haya pasado el plazo.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.marcar_orden_como_enviada(1).unwrap();
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(2000 + PLAZO_EXPIRACION_ORDEN);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            assert_eq!(sistema.expirar_orden(1).unwrap_err(), ErrorSistema::OperacionNoValida);
+            assert_eq!(sistema.publicaciones[0].stock, 2);
+        }
+
+        //-------------------------------------------------------------------------------------
+        //TESTS PUNTUACIÓN A USUARIOS
+
+        #[ink::test]
+        //Test puntuar funciona correctamente.
+        fn test_puntuar_ok(){
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+
+            sistema.productos.insert(1, &Producto{
+                nombre: "test".to_string(),
+                descripcion: "otro test".to_string(),
+                categoria:Categoria::Calzado,
+                subcategoria:None,
+                puntuaciones: Vec::<u8>::new(),
+                total_ventas:0,
+            });
+
+            sistema.guardar_orden(&
+                OrdenCompra {
+                    lista_productos: vec!((1,1)),
+                    lista_publicaciones: Vec::new(),
+                    id_orden_compra: 0,
+                    estado: EstadoOrdenCompra::Recibido,
+                    id_comprador:charlie,
+                    id_vendedor:alice,
+                    solicitud_cancelacion: None,
+                    monto:23,
+                    puntuado_por_comprador:false,
+                    puntuado_por_vendedor:false,
+                    creada_en:0,
+                    actualizada_en:0,
+                    expira_en:0,
+                    historial: Vec::new(),
+                    envios: Vec::new(),
+                    comision: 0,
+                    direccion_envio: None,
+                    recepciones: Vec::new(),
+                }
+            );
+            sistema._registrar_usuario("charlie".to_string(), "zz".to_string(), "char.zz@gmail.com".to_string(), Rol::Ambos, charlie);
+            sistema._registrar_usuario("alice".to_string(), "jhg".to_string(), "alialice@gmail.com".to_string(), Rol::Ambos, alice);
+
+            assert_eq!(sistema.usuarios.get(alice).unwrap().calificaciones_vendedor.len(),0);
+            assert_eq!(sistema.usuarios.get(charlie).unwrap().calificaciones_comprador.len(),0);
+
+            assert!(sistema._puntuar_usuario_por_orden(0, 3, charlie).is_ok());
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            assert!(sistema.puntuar_usuario_por_orden(0, 3).is_ok());
+
+            assert_eq!(sistema.usuarios.get(alice).unwrap().calificaciones_vendedor.len(),1);
+            assert_eq!(sistema.usuarios.get(alice).unwrap().momentos_calificaciones_vendedor.len(),1);
+            assert_eq!(sistema.usuarios.get(charlie).unwrap().calificaciones_comprador.len(),1);
+
+           
+        }
+
+        #[ink::test]
+        //Test para verificar que un usuario no pueda puntuar mas de una vez y comprobaciones previas.
+        fn test_errores_al_puntuar(){
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+
+            sistema.guardar_orden(&
+                OrdenCompra {
+                    lista_productos: vec!((1,1)),
+                    lista_publicaciones: Vec::new(),
+                    id_orden_compra: 0,
+                    estado: EstadoOrdenCompra::Recibido,
+                    id_comprador:charlie,
+                    id_vendedor:alice,
+                    solicitud_cancelacion: None,
+                    monto:23,
+                    puntuado_por_comprador:true,
+                    puntuado_por_vendedor:true,
+                    creada_en:0,
+                    actualizada_en:0,
+                    expira_en:0,
+                    historial: Vec::new(),
+                    envios: Vec::new(),
+                    comision: 0,
+                    direccion_envio: None,
+                    recepciones: Vec::new(),
+                }
+            );
+            sistema._registrar_usuario("charlie".to_string(), "zz".to_string(), "char.zz@gmail.com".to_string(), Rol::Ambos, charlie);
+            sistema._registrar_usuario("alice".to_string(), "jhg".to_string(), "alialice@gmail.com".to_string(), Rol::Ambos, alice);
+
+            assert_eq!(sistema._puntuar_usuario_por_orden(1, 3, charlie).unwrap_err(), ErrorSistema::IdDeOrdenNoValida);
+
+            assert_eq!(sistema._puntuar_usuario_por_orden(0, 6, alice).unwrap_err(), ErrorSistema::PuntuacionNoValida);
+
+            assert_eq!(sistema._puntuar_usuario_por_orden(0, 2, charlie).unwrap_err(), ErrorSistema::OrdenYaPuntuada);
+
+            assert_eq!(sistema._puntuar_usuario_por_orden(0, 2, alice).unwrap_err(), ErrorSistema::OrdenYaPuntuada);
+
+
+           
+        }
+
+         #[ink::test]
+        //Test para verificar que un usuario no pueda puntuar una orden no marcada como recibida ni por terceros.
+        fn test_puntuar_orden_recibida(){
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+
+            sistema.guardar_orden(&
+                OrdenCompra {
+                    lista_productos: vec!((1,1)),
+                    lista_publicaciones: Vec::new(),
+                    id_orden_compra: 0,
+                    estado: EstadoOrdenCompra::Enviado,
+                    id_comprador:charlie,
+                    id_vendedor:alice,
+                    solicitud_cancelacion: None,
+                    monto:23,
+                    puntuado_por_comprador:true,
+                    puntuado_por_vendedor:true,
+                    creada_en:0,
+                    actualizada_en:0,
+                    expira_en:0,
+                    historial: Vec::new(),
+                    envios: Vec::new(),
+                    comision: 0,
+                    direccion_envio: None,
+                    recepciones: Vec::new(),
+                }
+            );
+            sistema._registrar_usuario("charlie".to_string(), "zz".to_string(), "char.zz@gmail.com".to_string(), Rol::Ambos, charlie);
+            sistema._registrar_usuario("alice".to_string(), "jhg".to_string(), "alialice@gmail.com".to_string(), Rol::Ambos, alice);
+
+            assert_eq!(sistema._puntuar_usuario_por_orden(0, 1, charlie).unwrap_err(), ErrorSistema::NoPuedePuntuarOrdenSinRecibir);
+
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+
+            sistema.guardar_orden(&
+                OrdenCompra {
+                    lista_productos: vec!((1,1)),
+                    lista_publicaciones: Vec::new(),
+                    id_orden_compra: 1,
+                    estado: EstadoOrdenCompra::Recibido,
+                    id_comprador:charlie,
+                    id_vendedor:alice,
+                    solicitud_cancelacion: None,
+                    monto:23,
+                    puntuado_por_comprador:false,
+                    puntuado_por_vendedor:false,
+                    creada_en:0,
+                    actualizada_en:0,
+                    expira_en:0,
+                    historial: Vec::new(),
+                    envios: Vec::new(),
+                    comision: 0,
+                    direccion_envio: None,
+                    recepciones: Vec::new(),
+                }
+            );
+            assert_eq!(sistema._puntuar_usuario_por_orden(1, 5, bob).unwrap_err(), ErrorSistema::UsuarioNoAutorizado);
+
+           
+        }
+
+        #[ink::test]
+        //Test que verifica que un tercero no pueda puntuar una orden recibida ajena, dejándola sin puntuar.
+        fn test_puntuar_orden_por_tercero(){
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            let django = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().django;
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            let mut sistema = Sistema::new();
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 100, 10).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            sistema.generar_orden_compra(vec![(0, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.marcar_orden_como_enviada(0).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.marcar_orden_como_recibida(0).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(django);
+            sistema.registrar_usuario(String::from("Django"), String::from("Surname"), String::from("django.email"), Rol::Ambos).unwrap();
+            assert_eq!(sistema.puntuar_usuario_por_orden(0, 1).unwrap_err(), ErrorSistema::UsuarioNoAutorizado);
+
+            let orden = sistema.ordenes.get(0).unwrap();
+            assert!(!orden.puntuado_por_comprador);
+            assert!(!orden.puntuado_por_vendedor);
+            assert!(sistema.usuarios.get(alice).unwrap().calificaciones_vendedor.is_empty());
+            assert!(sistema.usuarios.get(bob).unwrap().calificaciones_comprador.is_empty());
+        }
+
+        #[ink::test]
+        //Test que verifica que un usuario Ambos no pueda puntuar una orden en la que es comprador y vendedor a la vez.
+        fn test_puntuar_orden_autoventa(){
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let mut sistema = Sistema::new();
+            sistema._registrar_usuario("charlie".to_string(), "zz".to_string(), "char.zz@gmail.com".to_string(), Rol::Ambos, charlie).unwrap();
+
+            sistema.guardar_orden(&
+                OrdenCompra {
+                    lista_productos: vec!((1,1)),
+                    lista_publicaciones: Vec::new(),
+                    id_orden_compra: 0,
+                    estado: EstadoOrdenCompra::Recibido,
+                    id_comprador:charlie,
+                    id_vendedor:charlie,
+                    solicitud_cancelacion: None,
+                    monto:23,
+                    puntuado_por_comprador:false,
+                    puntuado_por_vendedor:false,
+                    creada_en:0,
+                    actualizada_en:0,
+                    expira_en:0,
+                    historial: Vec::new(),
+                    envios: Vec::new(),
+                    comision: 0,
+                    direccion_envio: None,
+                    recepciones: Vec::new(),
+                }
+            );
+
+            assert_eq!(sistema._puntuar_usuario_por_orden(0, 5, charlie).unwrap_err(), ErrorSistema::OperacionNoValida);
+            let usuario = sistema.usuarios.get(charlie).unwrap();
+            assert!(usuario.calificaciones_vendedor.is_empty());
+            assert!(usuario.calificaciones_comprador.is_empty());
+        }
+
+        #[ink::test]
+        // tests para obtener los puntajes.
+        fn test_obtener_puntajes(){
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+
+            sistema.usuarios.insert(charlie, 
+                &Usuario{
+                    nombre:"carlos".to_string(),
+                    apellido:"cc".to_string(),
+                    email:"carlocc".to_string(),
+                    id:charlie,
+                    rol:Rol::Ambos,
+                    publicaciones:Vec::new(),
+                    ordenes:Vec::new(),
+                    productos:Vec::new(),
+                    calificaciones_comprador:vec!(2,3,4),
+                    calificaciones_vendedor:vec!(5,5,5),
+                    momentos_calificaciones_vendedor:Vec::new(),
+                    favoritos:Vec::new(),
+            });
+            assert_eq!(sistema.obtener_puntuacion_de_comprador(alice).unwrap_err(), ErrorSistema::UsuarioNoExiste);
+            assert_eq!(sistema.obtener_puntuacion_de_vendedor(alice).unwrap_err(), ErrorSistema::UsuarioNoExiste);
+            
+            sistema.usuarios.insert(alice, 
+                &Usuario{
+                    nombre:"carlos".to_string(),
+                    apellido:"cc".to_string(),
+                    email:"carlocc".to_string(),
+                    id:charlie,
+                    rol:Rol::Ambos,
+                    publicaciones:Vec::new(),
+                    ordenes:Vec::new(),
+                    productos:Vec::new(),
+                    calificaciones_comprador:Vec::new(),
+                    calificaciones_vendedor:Vec::new(),
+                    momentos_calificaciones_vendedor:Vec::new(),
+                    favoritos:Vec::new(),
+            });
+            assert_eq!(sistema.obtener_puntuacion_de_comprador(alice), Ok(0));
+            assert_eq!(sistema.obtener_puntuacion_de_vendedor(alice), Ok(0));
+            
+            assert_eq!(sistema.obtener_puntuacion_de_comprador(charlie), Ok(3));
+            assert_eq!(sistema.obtener_puntuacion_de_vendedor(charlie), Ok(5));
+        }
+
+
+        //-------------------------------------------------------------------------------------
+        //TESTS PUBLICACIONES Y STOCK:
+
+        #[ink::test]
+        //Test para verificar que se puede crear una publicación correctamente.
+        fn test_crear_publicacion() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor);
+
+            if let Ok(id) = sistema.nuevo_producto("banana".to_string(), "una banana".to_string(), Categoria::Limpieza, None){
+                assert_eq!(id, 0);
+            }
+
+
+            //Chequeo el estado posterior del sistema (que se haya creado la publicación).
+            sistema.crear_publicacion(0, 10, 19);
+            assert_eq!(sistema.get_publicaciones().len(), 1);
+        }
+
+        #[ink::test]
+        //Test para verificar que crear una publicación no pierda los demás datos guardados del usuario.
+        fn test_crear_publicacion_conserva_datos_usuario() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+
+            if let Some(mut user) = sistema.usuarios.get(charlie) {
+                user.calificaciones_vendedor = vec![5, 4];
+                user.calificaciones_comprador = vec![3];
+                user.ordenes = vec![7];
+                sistema.usuarios.insert(charlie, &user);
+            }
+
+            sistema.crear_publicacion(0, 10, 5).unwrap();
+
+            let user = sistema.usuarios.get(charlie).unwrap();
+            assert_eq!(user.publicaciones, vec![0]);
+            assert_eq!(user.productos, vec![0]);
+            assert_eq!(user.calificaciones_vendedor, vec![5, 4]);
+            assert_eq!(user.calificaciones_comprador, vec![3]);
+            assert_eq!(user.ordenes, vec![7]);
+        }
+
+        #[ink::test]
+        //Test para verificar que tiene_stock_suficiente funcione correctamente.
+        fn test_publicacion_tiene_stock_suficiente(){
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor);
+
+            if let Ok(id) = sistema.nuevo_producto("banana".to_string(), "una banana".to_string(), Categoria::Limpieza, None){
+                assert_eq!(id, 0);
+            }
+
+            sistema.crear_publicacion(0, 10, 19);
+            assert_eq!(sistema.get_publicaciones().len(), 1);
+
+            assert_eq!(sistema.publicaciones[0].tiene_stock_suficiente(5), true);
+            assert_eq!(sistema.publicaciones[0].tiene_stock_suficiente(10), true);
+        }
+
+        #[ink::test]
+        //Test que verifica que tiene_stock_suficiente funcione correctamente cuando el stock es insuficiente.
+        fn test_publicacion_tiene_stock_insuficiente() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor);
+
+            if let Ok(id) = sistema.nuevo_producto("banana".to_string(), "una banana".to_string(), Categoria::Limpieza, None){
+                assert_eq!(id, 0);
+            }
+
+            sistema.crear_publicacion(0, 10, 19); //Le doy 19 de stock.
+            assert_eq!(sistema.get_publicaciones().len(), 1);
+
+            assert_eq!(sistema.publicaciones[0].tiene_stock_suficiente(20), false);
+        }
+
+
+        #[ink::test]
+        //Test que verifica que no se puede crear una publicación con stock 0.
+        fn test_crear_publicacion_stock_cero() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor);
+
+            if let Ok(id) = sistema.nuevo_producto("banana".to_string(), "una banana".to_string(), Categoria::Limpieza, None){
+                assert_eq!(id, 0);
+            }
+
+            //Intento crear una publicación con stock 0.
+            let error_stock_cero = sistema.crear_publicacion(0, 10, 0).unwrap_err(); 
+            assert_eq!(error_stock_cero, ErrorSistema::StockInsuficiente); //No se puede crear una publicación con stock 0.
+
+            //Chequeo el estado posterior del sistema (que no se haya creado la publicación).
+            assert_eq!(sistema.get_publicaciones().len(), 0);
+        }
+
+        #[ink::test]
+        //Test para verificar que no se puede crear una publicación con precio 0.
+        fn test_crear_publicacion_precio_cero() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("banana".to_string(), "una banana".to_string(), Categoria::Limpieza, None).unwrap();
+
+            let error_precio_cero = sistema.crear_publicacion(0, 0, 10).unwrap_err();
+            assert_eq!(error_precio_cero, ErrorSistema::PrecioInvalido);
+
+            //Chequeo el estado posterior del sistema (que no se haya creado la publicación).
+            assert_eq!(sistema.get_publicaciones().len(), 0);
+            assert!(sistema.usuarios.get(charlie).unwrap().publicaciones.is_empty());
+        }
+
+        #[ink::test]
+        //Test para verificar que el vendedor pueda eliminar su publicación.
+        fn test_eliminar_publicacion_okay() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.nuevo_producto("Remera".to_string(), "Remera".to_string(), Categoria::Ropa, None).unwrap();
+            sistema.crear_publicacion(0, 10, 5).unwrap();
+            sistema.crear_publicacion(1, 20, 5).unwrap();
+
+            //Otro usuario no puede eliminar la publicación.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Comprador).unwrap();
+            assert_eq!(sistema.eliminar_publicacion(0).unwrap_err(), ErrorSistema::UsuarioNoAutorizado);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            assert!(sistema.eliminar_publicacion(0).is_ok());
+
+            let publicaciones = sistema.get_publicaciones();
+            assert_eq!(publicaciones.len(), 1);
+            assert_eq!(publicaciones[0].id_publicacion, 1);
+            assert_eq!(sistema.usuarios.get(charlie).unwrap().publicaciones, vec![1]);
+            assert_eq!(sistema.eliminar_publicacion(0).unwrap_err(), ErrorSistema::PublicacionNoValida);
+
+            //La publicación restante se sigue pudiendo comprar con su precio correcto.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            assert_eq!(sistema.generar_orden_compra(vec![(1, 2)], 100, Some(String::from("Calle 1 123"))).unwrap().monto, 40);
+        }
+
+        #[ink::test]
+        //Test para verificar que no se pueda eliminar una publicación con una orden pendiente.
+        fn test_eliminar_publicacion_con_orden_pendiente() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 10, 5).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Comprador).unwrap();
+            sistema.generar_orden_compra(vec![(0, 1)], 100, Some(String::from("Calle 1 123"))).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            assert_eq!(sistema.eliminar_publicacion(0).unwrap_err(), ErrorSistema::OperacionNoValida);
+            assert_eq!(sistema.get_publicaciones().len(), 1);
+            assert_eq!(sistema.usuarios.get(charlie).unwrap().publicaciones, vec![0]);
+
+            //Una vez recibida la orden, ya se puede eliminar.
+            sistema.marcar_orden_como_enviada(0).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.marcar_orden_como_recibida(0).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            assert!(sistema.eliminar_publicacion(0).is_ok());
+            assert!(sistema.get_publicaciones().is_empty());
+        }
+
+        #[ink::test]
+        //Test para verificar que no se puede comprar de una publicación con dinero insuficiente.
+        fn test_comprar_publicacion_dinero_insuficiente() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor);
+
+            if let Ok(id) = sistema.nuevo_producto("banana".to_string(), "una banana".to_string(), Categoria::Limpieza, None){
+                assert_eq!(id, 0);
+            }
+
+            sistema.crear_publicacion(0, 10, 19); //Le doy 19 de stock. Cada banana sale 10 pesos.
+            assert_eq!(sistema.get_publicaciones().len(), 1);
+
+            //Creo una orden de compra para que exista una orden con id 0.
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Ambos);
+
+            let lista_compra = vec![(0, 1)];
+
+            //Intento comprar una publicación con dinero insuficiente.
+            let error_dinero_insuficiente = sistema.generar_orden_compra(lista_compra, 0, Some(String::from("Calle 1 123"))).unwrap_err(); //Trato de comprar una banana con 0 dinero.
+            assert_eq!(error_dinero_insuficiente, ErrorSistema::FondosInsuficientes { requerido: 10, disponible: 0 }); //No se puede comprar la publicación porque el dinero es insuficiente.
+
+            //Chequeo el estado posterior del sistema (que no se haya modificado el stock).
+            assert_eq!(sistema.publicaciones[0].stock, 19);
+        }
+
+        #[ink::test]
+        //Test para verificar que un usuario que no es vendedor o ambos pueda crear una publicación.
+        fn test_crear_publicacion_user_no_vendedor() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Comprador);
+
+            let error_user_no_vendedor = sistema.crear_publicacion(0, 1000, 4).unwrap_err();
+            assert_eq!(error_user_no_vendedor, ErrorSistema::UsuarioNoEsVendedor); //Ok.
+
+            //Chequeo el estado posterior del sistema (no debe existir ninguna publicación).
+            assert_eq!(sistema.get_publicaciones().len(), 0);
+        }
+
+        #[ink::test]
+        //Test para verificar que un usuario no registrado no pueda crear una publicación de un producto existente.
+        fn test_crear_publicacion_user_no_registrado() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let eve = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().eve;
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(eve);
+            assert_eq!(sistema.crear_publicacion(0, 1000, 4).unwrap_err(), ErrorSistema::UsuarioNoExiste);
+
+            //Chequeo el estado posterior del sistema (no debe existir ninguna publicación).
+            assert_eq!(sistema.get_publicaciones().len(), 0);
+        }
+
+        #[ink::test]
+        //Test para verificar que no se puede crear una publicación de un producto inválido.
+        fn test_crear_publicacion_producto_invalido() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor);
+
+            let error_producto_invalido = sistema.crear_publicacion(0, 1000, 4).unwrap_err();
+            assert_eq!(error_producto_invalido, ErrorSistema::ProductoInvalido); //No existe el producto con id 0.
+
+            //Chequeo el estado posterior del sistema (no debe existir ninguna publicación).
+             assert_eq!(sistema.get_publicaciones().len(), 0);
+        }
+
+        #[ink::test]
+        //Test que verifica que get publicaciones_propias funcione (con un caso en el que sí funciona).
+        fn test_get_publicaciones_propias_okay() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor);
+
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None);
+            sistema.crear_publicacion(0, 1000, 4); //La publicación la crea Charlie.
+
+            //Verifico que el usuario pueda obtener sus publicaciones.
+            assert!(sistema.get_publicaciones_propias().is_ok());
+
+            //Chequeo el estado posterior del sistema.
+            assert_eq!(sistema.get_publicaciones().len(), 1);
+        }
+
+        #[ink::test]
+        //Test que verifica que get publicaciones_propias funcione (con un caso en el que el usuario es existe, es vendedor y no tiene publicaciones).
+        fn test_get_publicaciones_propias_vacio_okay() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor);
+
+            //Verifico que el usuario pueda obtener sus publicaciones (debe devolver un vector vacío).
+            let publicaciones_propias = sistema.get_publicaciones_propias().unwrap();
+            assert!(publicaciones_propias.is_empty());
+
+            //Chequeo el estado posterior del sistema.
+            assert_eq!(sistema.get_publicaciones().len(), 0);
+        }
+
+        #[ink::test]
+        //Test que verifica que get publicaciones_propias 'no funcione' (con un caso en el que el usuario no existe en el sistema).
+        fn test_get_publicaciones_propias_usuario_no_existe() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            //No registro a charlie en el sistema.
+
+            let error_usuario_no_existe = sistema.get_publicaciones_propias().unwrap_err();
+            assert_eq!(error_usuario_no_existe, ErrorSistema::UsuarioNoExiste); //El usuario no existe.
+        }
+
+        #[ink::test]
+        //Test que verifica que get publicaciones_propias 'no funcione' (con un caso en el que el usuario no es vendedor).
+        fn test_get_publicaciones_propias_usuario_no_vendedor() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Comprador);
+
+            let error_usuario_no_es_vendedor = sistema.get_publicaciones_propias().unwrap_err();
+            assert_eq!(error_usuario_no_es_vendedor, ErrorSistema::UsuarioNoEsVendedor); //El usuario no es vendedor.
+        }
+
+
+        #[ink::test]
+        //Test que verifica que publicacion_con_reputacion devuelva la publicación con el puntaje de su vendedor.
+        fn test_publicacion_con_reputacion() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 10, 5).unwrap();
+
+            if let Some(mut user) = sistema.usuarios.get(&charlie) {
+                user.calificaciones_vendedor = vec![5, 4, 3];
+                sistema.usuarios.insert(&charlie, &user);
+            }
+
+            let (publicacion, reputacion) = sistema.publicacion_con_reputacion(0).unwrap();
+            assert_eq!(publicacion.id_publicacion, 0);
+            assert_eq!(publicacion.id_publicador, charlie);
+            assert_eq!(reputacion, sistema.usuarios.get(&charlie).unwrap().calcular_puntaje_como_vendedor());
+            assert_eq!(reputacion, 4);
+
+            //Una publicación inexistente devuelve error.
+            assert_eq!(sistema.publicacion_con_reputacion(1).unwrap_err(), ErrorSistema::PublicacionNoValida);
+        }
+
+        #[ink::test]
+        //Test que verifica que stock_por_categoria agrupe el stock del vendedor por categoría.
+        fn test_stock_por_categoria() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Escoba".to_string(), "Escoba".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 10, 100).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            assert!(sistema.stock_por_categoria().is_empty());
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None).unwrap();
+            sistema.crear_publicacion(1, 10, 5).unwrap();
+            sistema.crear_publicacion(1, 12, 3).unwrap();
+            sistema.crear_publicacion(2, 20, 7).unwrap();
+
+            //Las publicaciones de otros vendedores no se cuentan.
+            assert_eq!(sistema.stock_por_categoria(), vec![(Categoria::Limpieza, 8), (Categoria::Otros, 7)]);
+
+            //Una publicación desactivada no suma aunque conserve stock.
+            sistema.publicaciones[2].activa = false;
+            assert_eq!(sistema.stock_por_categoria(), vec![(Categoria::Limpieza, 5), (Categoria::Otros, 7)]);
+            sistema.publicaciones[3].activa = false;
+            assert_eq!(sistema.stock_por_categoria(), vec![(Categoria::Limpieza, 5)]);
+        }
+
+        #[ink::test]
+        //Test que verifica que get_mis_productos devuelva los productos del vendedor, publicados o no, y rechace a quien no es vendedor.
+        fn test_get_mis_productos() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            let mut sistema = Sistema::new();
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor).unwrap();
+            assert!(sistema.get_mis_productos().unwrap().is_empty());
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None).unwrap();
+            sistema.crear_publicacion(0, 100, 3).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos).unwrap();
+            sistema.nuevo_producto("Pelota".to_string(), "Pelota de fútbol".to_string(), Categoria::Otros, None).unwrap();
+
+            //El producto 1 no está publicado y el 2 es de otro vendedor.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            let productos = sistema.get_mis_productos().unwrap();
+            assert_eq!(productos.iter().map(|(id, _)| *id).collect::<Vec<u128>>(), vec![0, 1]);
+            assert_eq!(productos[1].1.nombre, "Termo".to_string());
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            assert_eq!(sistema.get_mis_productos().err(), Some(ErrorSistema::UsuarioNoExiste));
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            assert_eq!(sistema.get_mis_productos().err(), Some(ErrorSistema::UsuarioNoEsVendedor));
+        }
+
+        #[ink::test]
+        //Test que verifica que mis_productos_tendencia sólo cuente las ventas posteriores al timestamp indicado.
+        fn test_mis_productos_tendencia() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap(); //ID 0
+            sistema.nuevo_producto("Remera".to_string(), "Remera".to_string(), Categoria::Ropa, None).unwrap(); //ID 1
+            sistema.crear_publicacion(0, 10, 50).unwrap();
+            sistema.crear_publicacion(1, 10, 50).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Comprador).unwrap();
+
+            //Venta anterior al corte: no debe contarse.
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(100);
+            sistema.generar_orden_compra(vec![(0, 20)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+
+            //Ventas posteriores al corte.
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(500);
+            sistema.generar_orden_compra(vec![(0, 2), (1, 3)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(600);
+            sistema.generar_orden_compra(vec![(1, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            assert_eq!(sistema.mis_productos_tendencia(200).unwrap(), vec![(1, 4), (0, 2)]);
+            assert_eq!(sistema.mis_productos_tendencia(0).unwrap(), vec![(0, 22), (1, 4)]);
+            assert!(sistema.mis_productos_tendencia(1000).unwrap().is_empty());
+
+            //Un comprador no puede consultar la tendencia.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            assert_eq!(sistema.mis_productos_tendencia(0).unwrap_err(), ErrorSistema::UsuarioNoEsVendedor);
+        }
+
+        #[ink::test]
+        //Test que verifica que las propuestas pendientes o rechazadas no cuenten en la tendencia ni en los más vendidos.
+        fn test_mis_productos_tendencia_sin_propuestas() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 10, 50).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Comprador).unwrap();
+            sistema.generar_orden_compra(vec![(0, 2)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+
+            //Una propuesta queda pendiente y otra es rechazada.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.crear_orden_para_comprador(alice, vec![(0, 5)]).unwrap();
+            let rechazada = sistema.crear_orden_para_comprador(alice, vec![(0, 7)]).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.rechazar_orden(rechazada).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            assert_eq!(sistema.mis_productos_tendencia(0).unwrap(), vec![(0, 2)]);
+            assert_eq!(sistema._ver_productos_mas_vendidos(Categoria::Limpieza), vec![(0, 2)]);
+        }
+
+        //-------------------------------------------------------------------------------------
+        //TESTS FAVORITOS:
+
+        #[ink::test]
+        //Test que verifica que se puedan agregar y quitar favoritos, y que no se repitan.
+        fn test_agregar_y_quitar_favoritos() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 10, 5).unwrap();
+            sistema.crear_publicacion(0, 15, 5).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Comprador).unwrap();
+
+            assert!(sistema.agregar_favorito(0).is_ok());
+            assert!(sistema.agregar_favorito(1).is_ok());
+            assert_eq!(sistema.agregar_favorito(0).unwrap_err(), ErrorSistema::FavoritoRepetido);
+            assert_eq!(sistema.agregar_favorito(9).unwrap_err(), ErrorSistema::PublicacionNoValida);
+
+            let favoritos = sistema.ver_favoritos();
+            assert_eq!(favoritos.len(), 2);
+            assert_eq!(favoritos[0].id_publicacion, 0);
+            assert_eq!(favoritos[1].id_publicacion, 1);
+
+            assert!(sistema.quitar_favorito(0).is_ok());
+            assert_eq!(sistema.quitar_favorito(0).unwrap_err(), ErrorSistema::PublicacionNoValida);
+
+            let favoritos = sistema.ver_favoritos();
+            assert_eq!(favoritos.len(), 1);
+            assert_eq!(favoritos[0].id_publicacion, 1);
+        }
+
+        #[ink::test]
+        //Test que verifica que ver_favoritos omita las publicaciones que fueron eliminadas.
+        fn test_ver_favoritos_omite_eliminadas() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 10, 5).unwrap();
+            sistema.crear_publicacion(0, 15, 5).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Comprador).unwrap();
+            sistema.agregar_favorito(0).unwrap();
+            sistema.agregar_favorito(1).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.eliminar_publicacion(0).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            let favoritos = sistema.ver_favoritos();
+            assert_eq!(favoritos.len(), 1);
+            assert_eq!(favoritos[0].id_publicacion, 1);
+        }
+
+        //-------------------------------------------------------------------------------------
+        //TESTS PRECIO Y CHECKED SUMS:
+
+        #[ink::test]
+        fn test_calculo_precio(){
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor);
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None);
+            sistema.nuevo_producto("Remera".to_string(), "Remera".to_string(), Categoria::Ropa, None);
+            sistema.crear_publicacion(0, 10, 19);
+            sistema.crear_publicacion(1, 20, 5);
+
+            sistema.nuevo_producto("Precioalto".to_string(), "Precioalto".to_string(), Categoria::Ropa, None);
+            let precio_alto = u32::MAX;
+            sistema.crear_publicacion(2, precio_alto, 5);
+
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Ambos);
+
+            let mut lista_compra = Vec::new();
+            lista_compra.push((0,2));
+            lista_compra.push((1,3));
+
+
+            if let Err(e) = sistema.generar_orden_compra(lista_compra.clone(), 70, Some(String::from("Calle 1 123"))){
+                assert_eq!(e, ErrorSistema::FondosInsuficientes { requerido: 80, disponible: 70 });
+            }
+
+            if let Ok(ord) = sistema.generar_orden_compra(lista_compra.clone(), 200, Some(String::from("Calle 1 123"))){
+                assert_eq!(ord.monto, 80);
+            }
+
+            if let Err(e) = sistema.generar_orden_compra(vec![(1,1), (2,1)], 200, Some(String::from("Calle 1 123"))) {
+                assert_eq!(e, ErrorSistema::Overflow);
+            }
+
+            if let Err(e) = sistema.generar_orden_compra(vec![(2,3)], 200, Some(String::from("Calle 1 123"))) {
+                assert_eq!(e, ErrorSistema::Overflow);
+            }
+
+            lista_compra.push((999,1));
+
+            if let Err(e) = sistema.validar_precio(lista_compra.clone(), 200){
+                assert_eq!(e, ErrorSistema::PublicacionNoValida);
+            }
+
+        }
+
+        #[ink::test]
+        fn test_checked_sums(){
+            let mut sistema = Sistema::new();
+            sistema.proximo_id_producto = u128::MAX;
+            let result = sistema.generar_id_producto();
+            assert_eq!(result.unwrap_err(), ErrorSistema::ProductosLleno);
+
+            sistema.proximo_id_publicacion = u128::MAX;
+            if let Err(e) = sistema.generar_id_publicacion() {
+                assert_eq!(e, ErrorSistema::PublicacionesLleno);
+            }
+
+            sistema.proximo_id_orden = u128::MAX;
+            if let Err(e) = sistema.generar_id_orden() {
+                assert_eq!(e, ErrorSistema::OrdenesLleno);
+            }
+
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None);
+
+            sistema.crear_publicacion(0, 10, 19);
+
+            if let Some(p) = sistema.publicaciones.get_mut(0) {
+                assert_eq!(p.actualizar_stock(u32::MAX), Err(ErrorSistema::StockOverflow))
+            }
+
+        }
+
+        #[ink::test]
+        //Test que verifica que si el stock cambia entre la validación y la actualización se propague el error sin dejar stock descontado.
+        fn test_actualizar_stock_de_orden_propaga_error(){
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None).unwrap();
+            sistema.crear_publicacion(0, 10, 5).unwrap();
+            sistema.crear_publicacion(1, 10, 5).unwrap();
+
+            let lista_compra = vec![(0, 3), (1, 3)];
+            assert!(sistema.validar_orden(lista_compra.clone(), charlie, bob, &[]).is_ok());
+
+            //Simulo que otra compra se llevó el stock de la segunda publicación.
+            sistema.publicaciones[1].stock = 1;
+
+            assert_eq!(sistema.actualizar_stock_de_orden(lista_compra).unwrap_err(), ErrorSistema::StockOverflow);
+            assert_eq!(sistema.publicaciones[0].stock, 5);
+            assert_eq!(sistema.publicaciones[1].stock, 1);
+        }
+
+        #[ink::test]
+        //Test que verifica que si el vendedor ya no existe no quede ningún cambio parcial de la orden.
+        fn test_generar_orden_atomica(){
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 10, 5).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+
+            //Simulo que la cuenta del vendedor fue eliminada.
+            sistema.usuarios.remove(charlie);
+
+            assert_eq!(sistema.generar_orden_compra(vec![(0, 2)], 100, Some(String::from("Calle 1 123"))).unwrap_err(), ErrorSistema::UsuarioNoExiste);
+            assert_eq!(sistema.publicaciones[0].stock, 5);
+            assert!(sistema.id_ordenes.is_empty());
+            assert_eq!(sistema.proximo_id_orden, 0);
+            assert!(sistema.usuarios.get(bob).unwrap().ordenes.is_empty());
+        }
+
+        #[ink::test]
+        //Test que verifica que se rechace una orden con más líneas que el máximo sin tocar el stock.
+        fn test_orden_demasiados_items(){
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            for _ in 0..=MAX_ITEMS_POR_ORDEN {
+                sistema.crear_publicacion(0, 1, 5).unwrap();
+            }
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+
+            let lista_compra: Vec<(u128, u32)> = (0..=MAX_ITEMS_POR_ORDEN as u128).map(|id| (id, 1)).collect();
+            assert_eq!(sistema.generar_orden_compra(lista_compra.clone(), 1000, Some(String::from("Calle 1 123"))).unwrap_err(), ErrorSistema::DemasiadosItems);
+            assert!(sistema.publicaciones.iter().all(|p| p.stock == 5));
+            assert!(sistema.id_ordenes.is_empty());
+
+            //Con exactamente el máximo la orden se acepta.
+            assert!(sistema.generar_orden_compra(lista_compra[..MAX_ITEMS_POR_ORDEN].to_vec(), 1000, Some(String::from("Calle 1 123"))).is_ok());
+        }
+
+        #[ink::test]
+        //Test que verifica el límite de unidades por línea: el máximo se acepta y uno más se rechaza antes de chequear stock.
+        fn test_cantidad_por_linea_limite(){
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 1, MAX_CANTIDAD_POR_LINEA * 2).unwrap();
+            sistema.crear_publicacion(0, 1, 1).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+
+            assert_eq!(sistema.generar_orden_compra(vec![(0, MAX_CANTIDAD_POR_LINEA + 1)], 10000, Some(String::from("Calle 1 123"))).unwrap_err(), ErrorSistema::CantidadExcedeLimite);
+            //Aunque no haya stock suficiente, el límite se informa primero.
+            assert_eq!(sistema.generar_orden_compra(vec![(1, MAX_CANTIDAD_POR_LINEA + 1)], 10000, Some(String::from("Calle 1 123"))).unwrap_err(), ErrorSistema::CantidadExcedeLimite);
+            assert_eq!(sistema.publicaciones[0].stock, MAX_CANTIDAD_POR_LINEA * 2);
+
+            assert!(sistema.generar_orden_compra(vec![(0, MAX_CANTIDAD_POR_LINEA)], 10000, Some(String::from("Calle 1 123"))).is_ok());
+            assert_eq!(sistema.publicaciones[0].stock, MAX_CANTIDAD_POR_LINEA);
+        }
+
+        #[ink::test]
+        //Test que verifica que al comprar todo el stock se emita SinStock y la publicación quede inactiva.
+        fn test_evento_sin_stock(){
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 10, 5).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+
+            //Una compra parcial no emite el evento.
+            sistema.generar_orden_compra(vec![(0, 2)], 100, Some(String::from("Calle 1 123"))).unwrap();
+            assert_eq!(ink::env::test::recorded_events().count(), 0);
+            assert!(sistema.publicaciones[0].activa);
+
+            sistema.generar_orden_compra(vec![(0, 3)], 100, Some(String::from("Calle 1 123"))).unwrap();
+            let eventos: Vec<_> = ink::env::test::recorded_events().collect();
+            assert_eq!(eventos.len(), 1);
+            let evento = <SinStock as ink::scale::Decode>::decode(&mut &eventos[0].data[..]).unwrap();
+            assert_eq!(evento.id_publicacion, 0);
+            assert_eq!(evento.publicador, charlie);
+            assert_eq!(sistema.publicaciones[0].stock, 0);
+            assert!(!sistema.publicaciones[0].activa);
+
+            //Si la orden expira, la publicación recupera el stock y vuelve a estar activa.
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(PLAZO_EXPIRACION_ORDEN + 1);
+            sistema.expirar_orden(1).unwrap();
+            assert_eq!(sistema.publicaciones[0].stock, 3);
+            assert!(sistema.publicaciones[0].activa);
+        }
+
+        #[ink::test]
+        //Test que verifica que se chequee en cada línea que el comprador no sea el publicador, no sólo en la primera.
+        fn test_compra_propia_en_linea_posterior(){
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 10, 5).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Ambos).unwrap();
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None).unwrap();
+            sistema.crear_publicacion(1, 20, 5).unwrap();
+
+            //La primera línea es de Charlie, la segunda es de Bob.
+            assert_eq!(sistema.generar_orden_compra(vec![(0, 1), (1, 1)], 100, Some(String::from("Calle 1 123"))).unwrap_err(), ErrorSistema::NoPuedeComprarPublicacionPropia);
+            assert_eq!(sistema.publicaciones[0].stock, 5);
+            assert_eq!(sistema.publicaciones[1].stock, 5);
+            assert!(sistema.id_ordenes.is_empty());
+        }
+
+        #[ink::test]
+        //Test que verifica que no se pueda comprar a un vendedor que perdió su rol.
+        fn test_comprar_a_vendedor_sin_rol(){
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 10, 5).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            assert!(sistema.generar_orden_compra(vec![(0, 1)], 100, Some(String::from("Calle 1 123"))).is_ok());
+
+            //Simulo que el vendedor quedó sólo como comprador.
+            let mut vendedor = sistema.usuarios.get(charlie).unwrap();
+            vendedor.rol = Rol::Comprador;
+            sistema.usuarios.insert(charlie, &vendedor);
+
+            assert_eq!(sistema.generar_orden_compra(vec![(0, 1)], 100, Some(String::from("Calle 1 123"))).unwrap_err(), ErrorSistema::VendedorNoDisponible);
+            assert_eq!(sistema.publicaciones[0].stock, 4);
+            assert_eq!(sistema.id_ordenes.len(), 1);
+        }
+
+        #[ink::test]
+        //Test que verifica que las publicaciones se busquen por id y no por posición.
+        fn test_buscar_publicacion(){
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 10, 5).unwrap();
+            sistema.crear_publicacion(0, 20, 5).unwrap();
+
+            //Al eliminar la primera, la de id 1 queda en la posición 0.
+            sistema.eliminar_publicacion(0).unwrap();
+            assert!(sistema.buscar_publicacion(0).is_none());
+            assert_eq!(sistema.buscar_publicacion(1).unwrap().precio, 20);
+
+            sistema.buscar_publicacion_mut(1).unwrap().stock = 2;
+            assert_eq!(sistema.publicaciones[0].stock, 2);
+            assert!(sistema.buscar_publicacion_mut(7).is_none());
+        }
+
+        //-------------------------------------------------------------------------------------
+        //TEST ID USUARIOS
+
+        #[ink::test]
+        //Test que verifica que los IDs de los usuarios se agregan correctamente al vector de IDs.
+        fn test_agregar_id_okay() {
+            let mut sistema = Sistema::new();
+
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+
+            assert!(sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos).is_ok());
+            assert_eq!(sistema.id_usuarios.len(), 1); //Debe tener un ID.
+            assert_eq!(sistema.id_usuarios[0], charlie); //El ID debe ser el de Charlie.
+        }
+
+        #[ink::test]
+        //Test que verifica que el vector de ids de usuarios está vecío cuando no hay nada.
+        fn test_ids_vacio_okay() {
+            let sistema = Sistema::new();
+            assert!(sistema.id_usuarios.is_empty()); //El vector de IDs debe estar vacío.
+        }
+
+
+
+        //TESTS PUNTUACIÓN A USUARIOS
+        #[ink::test]
+        //Test que verifica que la funcionalidad top_5_vendedores funcione correctamente. (Caso en el que hay más de 5 vendedores).
+        fn test_top_5_vendedores_okay() {
+            // Alice como owner de los contratos
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+
+            let mut sistema = Sistema::new();
+
+            // Pongo a la misma Alice simulando ser ReportesView para que pueda
+            // enviar los mensajes relativos a este
+            sistema.set_reportes_view(alice);
+
+            //Preparo a los vendedores.
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            let django = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().django;
+            let eve = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().eve;
+            let frank = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().frank;
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Vendedor);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(django);
+            sistema.registrar_usuario(String::from("Django"), String::from("Surname"), String::from("django.email"), Rol::Ambos);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(eve);
+            sistema.registrar_usuario(String::from("Eve"), String::from("Surname"), String::from("eve.email"), Rol::Ambos);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(frank);
+            sistema.registrar_usuario(String::from("Frank"), String::from("Surname"), String::from("frank.email"), Rol::Ambos);
+
+            //Les doy las calificaciones.
+
+            if let Some(mut user) = sistema.usuarios.get(&charlie) {
+                user.calificaciones_vendedor = vec![5, 5, 5];
+                sistema.usuarios.insert(&charlie, &user); 
+            }
+
+            if let Some(mut user) = sistema.usuarios.get(&alice) {
+                user.calificaciones_vendedor = vec![5, 5, 1]; //3.6
+                sistema.usuarios.insert(&alice, &user); 
+            }
+
+            if let Some(mut user) = sistema.usuarios.get(&bob) {
+                user.calificaciones_vendedor = vec![1, 1, 1]; 
+                sistema.usuarios.insert(&bob, &user); 
+            }
+             
+            if let Some(mut user) = sistema.usuarios.get(&django) {
+                user.calificaciones_vendedor = vec![2, 3, 4]; //3
+                sistema.usuarios.insert(&django, &user); 
+            }
+
+            if let Some(mut user) = sistema.usuarios.get(&eve) {
+                user.calificaciones_vendedor = vec![5, 5, 5];
+                sistema.usuarios.insert(&eve, &user); 
+            }
+
+            if let Some(mut user) = sistema.usuarios.get(&frank) {
+                user.calificaciones_vendedor = vec![0, 0, 0];
+                sistema.usuarios.insert(&frank, &user); 
+            }
+    
+            //Pido el top 5 de vendedores.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            let top_5 = sistema.consultar_top_5_vendedores().unwrap();
+            assert_eq!(top_5.len(), 5); //Debe devolver 5 vendedores.
+                
+
+            //Chequeo que estén ordenados correctamente (de mayor a menor puntuación) y que sean los correctos. 
+            assert_eq!(top_5[0].id, charlie);
+            assert_eq!(top_5[1].id, eve); 
+            assert_eq!(top_5[2].id, alice); 
+            assert_eq!(top_5[3].id, django); 
+            assert_eq!(top_5[4].id, bob);      
+        }
+
+        #[ink::test]
+        //Este test chequea que la funcionalidad top_5_vendedores funcione correctamente (Caso en el que no hay vendedores).
+        fn test_top_5_vendedores_nulo() {
+            // Alice como owner de los contratos
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+
+            let mut sistema = Sistema::new();
+
+            // Acá voy a usar a Bob como ReportesVew para comprobar que no hace
+            // falta que sea el owner del MarketPlace
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            sistema.set_reportes_view(bob);
+            
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            let top_5 = sistema.consultar_top_5_vendedores().unwrap();
+            assert!(top_5.is_empty()); //Debe devolver un vector vacío.
+        }
+
+        #[ink::test]
+        //Este test chequea que la funcionalidad top_5_vendedores funcione correctamente (Caso en el que hay menos de 5 vendedores).
+        fn test_top_5_vendedores_menor_a_5() {
+            // Alice como owner de los contratos
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+
+            let mut sistema = Sistema::new();
+
+            // Pongo a la misma Alice simulando ser ReportesView para que pueda
+            // enviar los mensajes relativos a este
+            sistema.set_reportes_view(alice);
+
+            //Preparo a los vendedores.
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Vendedor);
+
+            //Les doy las calificaciones.
+
+            if let Some(mut user) = sistema.usuarios.get(&charlie) {
+                user.calificaciones_vendedor = vec![5, 5, 5];
+                sistema.usuarios.insert(&charlie, &user); 
+            }
+
+            if let Some(mut user) = sistema.usuarios.get(&alice) {
+                user.calificaciones_vendedor = vec![5, 5, 1]; //3.6
+                sistema.usuarios.insert(&alice, &user); 
+            }
+
+            if let Some(mut user) = sistema.usuarios.get(&bob) {
+                user.calificaciones_vendedor = vec![1, 1, 1]; 
+                sistema.usuarios.insert(&bob, &user); 
+            }
+    
+            //Pido el top 5 de vendedores.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            let top_5 = sistema.consultar_top_5_vendedores().unwrap();
+            assert_eq!(top_5.len(), 3); //Debe devolver 3 vendedores (los únicos que hay).
+                
+
+            //Chequeo que estén ordenados correctamente (de mayor a menor puntuación) y que sean los correctos. 
+            assert_eq!(top_5[0].id, charlie);
+        }
+
+        //-------------------------------------------------------------------------------------
+        //TESTS VER PRODUCTOS MÁS VENDIDOS.
+
+         #[ink::test]
+        fn test_productos_mas_vendidos() {
+            // Alice como owner de los contratos
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+
+            let mut sistema = Sistema::new();
+
+            // Pongo a la misma Alice simulando ser ReportesView para que pueda
+            // enviar los mensajes relativos a este
+            sistema.set_reportes_view(alice);
+
+            //Preparo a los vendedores.
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Vendedor);
+
+            //Creo productos y publicaciones.
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None); //ID 0
+            sistema.nuevo_producto("Remera".to_string(), "Remera".to_string(), Categoria::Ropa, None); //ID 1
+            sistema.nuevo_producto("Pantalon".to_string(), "Pantalon".to_string(), Categoria::Ropa, None); //ID 2
+            sistema.nuevo_producto("Arroz".to_string(), "Arroz".to_string(), Categoria::Otros, None); //ID 3
+
+            sistema.crear_publicacion(0, 100, 10); //Cif
+            sistema.crear_publicacion(1, 500, 20); //Remera
+            sistema.crear_publicacion(2, 200, 25); //Pantalon
+            sistema.crear_publicacion(3, 400, 30); //Arroz
+
+            //Creo órdenes de compra para que haya ventas.
+            let django = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().django;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(django);
+            sistema.registrar_usuario(String::from("Django"), String::from("Surname"), String::from("django.email"), Rol::Ambos);
+            //Id publicación, cantidad.
+            sistema.generar_orden_compra(vec![(0, 2), (1, 1)], 10000, Some(String::from("Calle 1 123"))).unwrap(); //Compra 2 Cif y 1 Remera 
+            sistema.generar_orden_compra(vec![(1, 3), (2, 1)], 10000, Some(String::from("Calle 1 123"))).unwrap(); //Compra 2 Remera y 1 Pantalon 
+            sistema.generar_orden_compra(vec![(3, 3), (0, 1), (2, 2)], 10000, Some(String::from("Calle 1 123"))).unwrap(); //Compra 3 Arroz, 1 Cif y 2 Pantalon 
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice); // Alice es ReportesView
+            let productos_mas_vendidos = sistema.ver_productos_mas_vendidos(Categoria::Ropa.codigo(), VERSION_API).unwrap();
+
+            assert_eq!(productos_mas_vendidos.len(), 2); //Debe devolver 2 productos.
+            assert_eq!(productos_mas_vendidos[0].0, 1); //El producto más vendido es la Remera (ID 1).
+            assert_eq!(productos_mas_vendidos[0].1, 4); //Se vendieron 4 Remeras.
+            
+            assert_eq!(productos_mas_vendidos[1].0, 2); //El segundo producto más vendido es el Pantalon (ID 2). 
+            assert_eq!(productos_mas_vendidos[1].1, 3); //Se vendieron 3 Pantalones.
+        }
+
+
+        #[ink::test]
+        fn test_productos_mas_vendidos_vacio() {
+            // Alice como owner de los contratos
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+
+            let mut sistema = Sistema::new();
+
+            // Pongo a la misma Alice simulando ser ReportesView para que pueda
+            // enviar los mensajes relativos a este
+            sistema.set_reportes_view(alice);
+
+            //Preparo a los vendedores.
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Vendedor);
+
+            //Creo productos y publicaciones.
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None); //ID 0
+            sistema.nuevo_producto("Remera".to_string(), "Remera".to_string(), Categoria::Ropa, None); //ID 1
+            sistema.nuevo_producto("Pantalon".to_string(), "Pantalon".to_string(), Categoria::Ropa, None); //ID 2
+            sistema.nuevo_producto("Arroz".to_string(), "Arroz".to_string(), Categoria::Otros, None); //ID 3
+
+            sistema.crear_publicacion(0, 100, 10); //Cif
+            sistema.crear_publicacion(1, 500, 20); //Remera
+            sistema.crear_publicacion(2, 200, 25); //Pantalon
+            sistema.crear_publicacion(3, 400, 30); //Arroz
+
+            //Creo órdenes de compra para que haya ventas.
+            let django = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().django;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(django);
+            sistema.registrar_usuario(String::from("Django"), String::from("Surname"), String::from("django.email"), Rol::Ambos);
+            //Id publicación, cantidad.
+            sistema.generar_orden_compra(vec![(0, 2), (1, 1)], 10000, Some(String::from("Calle 1 123"))).unwrap(); //Compra 2 Cif y 1 Remera 
+            sistema.generar_orden_compra(vec![(1, 3), (2, 1)], 10000, Some(String::from("Calle 1 123"))).unwrap(); //Compra 2 Remera y 1 Pantalon 
+            sistema.generar_orden_compra(vec![(3, 3), (0, 1), (2, 2)], 10000, Some(String::from("Calle 1 123"))).unwrap(); //Compra 3 Arroz, 1 Cif y 2 Pantalon 
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice); // Alice es ReportesView
+            let productos_mas_vendidos = sistema.ver_productos_mas_vendidos(Categoria::Musica.codigo(), VERSION_API).unwrap();
+            assert_eq!(productos_mas_vendidos.len(), 0); //Debe devolver 2 productos.
+        }
+
+        #[ink::test]
+        //Test que verifica el camino de productos más vendidos con todas las categorías, incluida Otros,
+        //y que se rechacen códigos desconocidos o una versión distinta.
+        fn test_productos_mas_vendidos_todas_las_categorias() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            let django = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().django;
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            let mut sistema = Sistema::new();
+            sistema.set_reportes_view(alice).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Vendedor).unwrap();
+            let categorias = sistema.get_categorias();
+            //Un producto por categoría, con id igual al código de la categoría.
+            for categoria in categorias.iter() {
+                sistema.nuevo_producto("Producto".to_string(), "Producto".to_string(), categoria.clone(), None).unwrap();
+                sistema.crear_publicacion(categoria.codigo() as u128, 10, 50).unwrap();
+            }
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(django);
+            sistema.registrar_usuario(String::from("Django"), String::from("Surname"), String::from("django.email"), Rol::Comprador).unwrap();
+            for categoria in categorias.iter() {
+                let id = categoria.codigo() as u128;
+                sistema.generar_orden_compra(vec![(id, id as u32 + 1)], 10000, Some(String::from("Calle 1 123"))).unwrap();
+            }
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            for categoria in categorias.iter() {
+                assert_eq!(Categoria::desde_codigo(categoria.codigo()).unwrap(), *categoria);
+                let codigo = categoria.codigo();
+                assert_eq!(sistema.ver_productos_mas_vendidos(codigo, VERSION_API).unwrap(), vec![(codigo as u128, codigo + 1)]);
+            }
+            assert_eq!(Categoria::Otros.codigo(), 5);
+
+            assert_eq!(sistema.ver_productos_mas_vendidos(6, VERSION_API).unwrap_err(), ErrorSistema::CategoriaInvalida);
+        }
+
+        #[ink::test]
+        //Test que verifica la versión del contrato y el error cuando ReportesView usa otra versión.
+        fn test_version_incompatible() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            let mut sistema = Sistema::new();
+            sistema.set_reportes_view(alice).unwrap();
+
+            assert_eq!(VERSION_API, 1);
+            assert_eq!(sistema.version(), VERSION_API);
+            assert!(sistema.ver_productos_mas_vendidos(Categoria::Otros.codigo(), VERSION_API).is_ok());
+            assert_eq!(sistema.ver_productos_mas_vendidos(Categoria::Otros.codigo(), VERSION_API + 1).unwrap_err(), ErrorSistema::VersionIncompatible);
+        }
+
+        //-------------------------------------------------------------------------------------
+        //TESTS PARA ESTADISTICAS POR CATEGORIA
+
+        #[ink::test]
+        fn test_estadisticas_por_categorias() {
+            // Alice como owner de los contratos
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+
+            let mut sistema = Sistema::new();
+
+            // Pongo a la misma Alice simulando ser ReportesView para que pueda
+            // enviar los mensajes relativos a este
+            sistema.set_reportes_view(alice);
+
+            sistema.productos.insert(0, &Producto{
+                nombre:"remera".to_string(),
+                descripcion:"negra".to_string(),
+                categoria:Categoria::Ropa,
+                subcategoria:None,
+                puntuaciones:vec![1,2,3],
+                total_ventas:23,
+            });
+
+            sistema.productos.insert(1, &Producto{
+                nombre:"pantalon".to_string(),
+                descripcion:"azul".to_string(),
+                categoria:Categoria::Ropa,
+                subcategoria:None,
+                puntuaciones:vec![4,4],
+                total_ventas:12,
+            });
+
+            sistema.productos.insert(2, &Producto{
+                nombre:"Auriculares".to_string(),
+                descripcion:"XYZ".to_string(),
+                categoria:Categoria::Tecnologia,
+                subcategoria:None,
+                puntuaciones:vec![3,2],
+                total_ventas:100,
+            });
+
+            sistema.proximo_id_producto = 3;
+
+            assert_eq!(sistema.estadisticas_por_categoria().unwrap(), vec![(Categoria::Tecnologia, 100, 2), (Categoria::Ropa, 35, 2)]);
+        }
+
+        #[ink::test]
+        //Test que verifica las estadísticas detalladas por categoría: unidades, órdenes recibidas, ingreso y calificación.
+        fn test_estadisticas_por_categoria_detallado() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            let mut sistema = Sistema::new();
+            sistema.set_reportes_view(alice).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Remera".to_string(), "Negra".to_string(), Categoria::Ropa, None).unwrap();
+            sistema.nuevo_producto("Auriculares".to_string(), "XYZ".to_string(), Categoria::Tecnologia, None).unwrap();
+            sistema.crear_publicacion(0, 10, 10).unwrap();
+            sistema.crear_publicacion(1, 100, 10).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            //Orden 0 con las dos categorías y orden 1 sólo con ropa, ambas recibidas.
+            sistema.generar_orden_compra(vec![(0, 2), (1, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+            sistema.generar_orden_compra(vec![(0, 3)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+            //Orden 2 pendiente: no suma órdenes ni ingreso.
+            sistema.generar_orden_compra(vec![(1, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+
+            for id in 0..2 {
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+                sistema.marcar_orden_como_enviada(id).unwrap();
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+                sistema.marcar_orden_como_recibida(id).unwrap();
+            }
+
+            let mut remera = sistema.productos.get(0).unwrap();
+            remera.puntuaciones = vec![4, 5];
+            sistema.productos.insert(0, &remera);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            let estadisticas = sistema.estadisticas_por_categoria_detallado().unwrap();
+            let monto_0 = sistema.ordenes.get(0).unwrap().monto as u128;
+            let monto_1 = sistema.ordenes.get(1).unwrap().monto as u128;
+            let ropa = EstadisticaCategoria {
+                categoria: Categoria::Ropa,
+                unidades_vendidas: sistema.productos.get(0).unwrap().total_ventas,
+                ordenes: 2,
+                ingreso_total: monto_0 + monto_1,
+                prom_calificacion: Some(5),
+            };
+            let tecnologia = EstadisticaCategoria {
+                categoria: Categoria::Tecnologia,
+                unidades_vendidas: sistema.productos.get(1).unwrap().total_ventas,
+                ordenes: 1,
+                ingreso_total: monto_0,
+                prom_calificacion: None,
+            };
+            assert_eq!(estadisticas.len(), 2);
+            assert!(estadisticas.contains(&ropa));
+            assert!(estadisticas.contains(&tecnologia));
+
+            //Sin ser ReportesView no se puede consultar.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            assert!(sistema.estadisticas_por_categoria_detallado().is_err());
+        }
+
+        //-------------------------------------------------------------------------------------
+        //TESTS PARA TOP 5 COMPRADORES
+
+        #[ink::test]
+        fn test_top_5_compradores() {
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;            
+
+            // Alice como owner de los contratos
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+
+            let mut sistema = Sistema::new();
+
+            // Pongo a la misma Alice simulando ser ReportesView para que pueda
+            // enviar los mensajes relativos a este
+            sistema.set_reportes_view(alice);
+
+            sistema.usuarios.insert(charlie, &Usuario{
+                nombre:"charlie".to_string(),
+                apellido:"char".to_string(),
+                email:"ch@".to_string(),
+                id:charlie,
+                rol:Rol::Comprador,
+                publicaciones: Vec::new(),
+                ordenes: Vec::new(),
+                productos: Vec::new(),
+                calificaciones_comprador: vec![1,2,3],
+                calificaciones_vendedor: Vec::new(),
+                momentos_calificaciones_vendedor: Vec::new(),
+                favoritos: Vec::new(),
+            });
+
+            sistema.usuarios.insert(alice, &Usuario{
+                nombre:"alice".to_string(),
+                apellido:"alice".to_string(),
+                email:"alice@".to_string(),
+                id:alice,
+                rol:Rol::Ambos,
+                publicaciones: Vec::new(),
+                ordenes: Vec::new(),
+                productos: Vec::new(),
+                calificaciones_comprador: vec![5,4,3],
+                calificaciones_vendedor: Vec::new(),
+                momentos_calificaciones_vendedor: Vec::new(),
+                favoritos: Vec::new(),
+            });
+
+            sistema.usuarios.insert(bob, &Usuario{
+                nombre:"bob".to_string(),
+                apellido:"bob".to_string(),
+                email:"bob@".to_string(),
+                id:bob,
+                rol:Rol::Ambos,
+                publicaciones: Vec::new(),
+                ordenes: Vec::new(),
+                productos: Vec::new(),
+                calificaciones_comprador: vec![5,5],
+                calificaciones_vendedor: Vec::new(),
+                momentos_calificaciones_vendedor: Vec::new(),
+                favoritos: Vec::new(),
+            });
+
+            sistema.id_usuarios.push(charlie);
+            sistema.id_usuarios.push(alice);
+            sistema.id_usuarios.push(bob);
+
+            let top_5 = sistema.consultar_top_5_compradores().unwrap();
 
-        fn tiene_stock_suficiente(&self, cant:u32)->bool{
-            self.stock >= cant
-        }
-    } 
+            assert_eq!(top_5[0].id, bob);
 
-    #[cfg(test)]
-    mod tests {
+            assert_eq!(top_5[1].id, alice);
 
-        use super::*;
+            assert_eq!(top_5[2].id, charlie);
+            
+        }
+
+        //-------------------------------------------------------------------------------------
+        //TESTS PARA CANTIDAD DE ORDENES DE COMPRA
 
-        //TESTS REGISTRAR USUARIO:
         #[ink::test]
-        fn test_registrar_usuario_comprador_okay() {
+        fn test_cantidad_ordenes_cero() {
             let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
 
             let mut sistema = Sistema::new();
+            sistema.set_reportes_view(alice);
+            
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Ambos);
 
-            assert!(sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Comprador).is_ok());
+            let cantidades_ordenes = sistema.cantidad_ordenes_por_usuario().unwrap();
 
-            //Chequeamos que el usuario se haya registrado correctamente.
-            let usuario = sistema.usuarios.get(&alice);
-            assert!(usuario.is_some());
+            let ordenes_alice = cantidades_ordenes.clone()
+            .into_iter()
+            .find(|(id, _)| *id == alice)
+            .map(|(_, ordenes)| ordenes)
+            .unwrap();
+
+            assert_eq!(cantidades_ordenes.len(), 1); // Hay solo un usuario registrado, habrá una única tupla en el vector
+            assert_eq!(ordenes_alice, 0); // Alice tiene 0 ordenes
         }
 
         #[ink::test]
-        fn test_registrar_usuario_vendedor_okay() {
+        fn test_cantidad_ordenes_una() {
             let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
 
             let mut sistema = Sistema::new();
+            sistema.set_reportes_view(alice);
+            
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Ambos);
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None);
+            sistema.crear_publicacion(0, 1000, 4);
 
-            assert!(sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor).is_ok());
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Comprador);
+            sistema.generar_orden_compra(vec![(0, 1)],4000, Some(String::from("Calle 1 123"))).unwrap();
 
-            //Chequeamos que el usuario se haya registrado correctamente.
-            let usuario = sistema.usuarios.get(&alice);
-            assert!(usuario.is_some());
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            let cantidades_ordenes = sistema.cantidad_ordenes_por_usuario().unwrap();
+
+            let ordenes_alice = cantidades_ordenes.clone()
+            .into_iter()
+            .find(|(id, _)| *id == alice)
+            .map(|(_, ordenes)| ordenes)
+            .unwrap();
+
+            let ordenes_bob = cantidades_ordenes.clone()
+            .into_iter()
+            .find(|(id, _)| *id == bob)
+            .map(|(_, ordenes)| ordenes)
+            .unwrap();
+
+            assert_eq!(cantidades_ordenes.len(), 2); // Hay dos usuarios registrados, habrá dos tuplas en el vector
+            assert_eq!(ordenes_alice, 1); // Alice tiene 1 ordene, como vendedor
+            assert_eq!(ordenes_bob, 1); // Bob tiene 1 orden, como comprador
         }
 
+        //-------------------------------------------------------------------------------------
+        //TESTS ESCROW
+
         #[ink::test]
-        fn test_registrar_usuario_ambos_okay() {
+        //Test que verifica que se acepten órdenes hasta el escrow máximo y se rechacen las que lo superan.
+        fn test_escrow_maximo() {
             let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
 
+            // Alice como owner del contrato.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
             let mut sistema = Sistema::new();
+            assert!(sistema.set_escrow_maximo(Some(100)).is_ok());
 
-            assert!(sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Ambos).is_ok());
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 10, 50).unwrap();
 
-            //Chequeamos que el usuario se haya registrado correctamente.
-            let usuario = sistema.usuarios.get(&alice);
-            assert!(usuario.is_some());
+            //Sólo el owner puede configurar el escrow máximo.
+            assert_eq!(sistema.set_escrow_maximo(None).unwrap_err(), ErrorSistema::AccesoDenegado);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+
+            //Llego exactamente al máximo.
+            sistema.generar_orden_compra(vec![(0, 6)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+            sistema.generar_orden_compra(vec![(0, 4)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+            assert_eq!(sistema.escrow_actual(), 100);
+
+            //Cualquier orden adicional lo supera.
+            assert_eq!(sistema.generar_orden_compra(vec![(0, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap_err(), ErrorSistema::EscrowLleno);
+            assert_eq!(sistema.id_ordenes.len(), 2);
+            assert_eq!(sistema.publicaciones[0].stock, 40);
+
+            //Al cancelarse una orden se libera su monto del escrow.
+            sistema.cancelar_orden(1).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.cancelar_orden(1).unwrap();
+            assert_eq!(sistema.escrow_actual(), 60);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            assert!(sistema.generar_orden_compra(vec![(0, 4)], 1000, Some(String::from("Calle 1 123"))).is_ok());
         }
 
-         /// No se puede registrar un usuario que ya está registrado
-         #[ink::test]
-         fn test_registrar_usuario_not_okay() {
+        //-------------------------------------------------------------------------------------
+        //TESTS TIEMPO PROMEDIO DE VENTA
+
+        #[ink::test]
+        //Test que verifica el promedio de bloques entre la creación de las publicaciones y su primera venta.
+        fn test_tiempo_promedio_venta() {
             let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+
+            // Alice como owner del contrato.
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
- 
             let mut sistema = Sistema::new();
-            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Comprador);
- 
-            assert!(sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Comprador).is_err());
+            assert_eq!(sistema.tiempo_promedio_venta().unwrap_err(), ErrorSistema::SinDatos);
 
-            //Chequeamos que el usuario no se haya registrado nuevamente.
-            assert!(sistema.usuarios.get(&alice).is_some());
-         }
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.nuevo_producto("Remera".to_string(), "Remera".to_string(), Categoria::Ropa, None).unwrap();
+            sistema.nuevo_producto("Arroz".to_string(), "Arroz".to_string(), Categoria::Otros, None).unwrap();
+
+            ink::env::test::set_block_number::<ink::env::DefaultEnvironment>(10);
+            sistema.crear_publicacion(0, 10, 10).unwrap(); //Publicación 0, creada en el bloque 10
+            ink::env::test::set_block_number::<ink::env::DefaultEnvironment>(20);
+            sistema.crear_publicacion(1, 10, 10).unwrap(); //Publicación 1, creada en el bloque 20
+            sistema.crear_publicacion(2, 10, 10).unwrap(); //Publicación 2, nunca se vende
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            sistema.generar_orden_compra(vec![(0, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap(); //Orden 0
+            sistema.generar_orden_compra(vec![(1, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap(); //Orden 1
+            sistema.generar_orden_compra(vec![(0, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap(); //Orden 2
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.marcar_orden_como_enviada(0).unwrap();
+            sistema.marcar_orden_como_enviada(1).unwrap();
+            sistema.marcar_orden_como_enviada(2).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            ink::env::test::set_block_number::<ink::env::DefaultEnvironment>(30);
+            sistema.marcar_orden_como_recibida(0).unwrap(); //Publicación 0 tarda 20 bloques
+            ink::env::test::set_block_number::<ink::env::DefaultEnvironment>(60);
+            sistema.marcar_orden_como_recibida(1).unwrap(); //Publicación 1 tarda 40 bloques
+            sistema.marcar_orden_como_recibida(2).unwrap(); //No es la primera venta de la publicación 0
+
+            //Sólo el owner puede consultarlo.
+            assert_eq!(sistema.tiempo_promedio_venta().unwrap_err(), ErrorSistema::AccesoDenegado);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            assert_eq!(sistema.tiempo_promedio_venta().unwrap(), 30);
+        }
 
         //-------------------------------------------------------------------------------------
-        //TESTS PRODUCTOS:
+        //TESTS CARRITO
 
         #[ink::test]
-        fn test_nuevo_producto_usuario_inexistente() {
-            //Se testea que un usuario que no existe en la plataforma no pueda crear un producto.
+        //Test que verifica que el carrito acumule publicaciones y sume las cantidades repetidas.
+        fn test_carrito_acumula_y_fusiona() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
             let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 10, 10).unwrap();
+            sistema.crear_publicacion(0, 20, 10).unwrap();
+
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
-            let mut sistema = Sistema::new();
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Comprador).unwrap();
+            assert!(sistema.ver_carrito().is_empty());
 
-            assert!(sistema.nuevo_producto(String::from("Laptop"), String::from("Laptop gamer"), Categoria::Tecnologia).is_err());
-            // El usuario no existe, por lo tanto no puede crear un producto.
+            assert!(sistema.agregar_al_carrito(0, 1).is_ok());
+            assert!(sistema.agregar_al_carrito(1, 2).is_ok());
+            assert!(sistema.agregar_al_carrito(0, 3).is_ok());
+            assert_eq!(sistema.ver_carrito(), vec![(0, 4), (1, 2)]);
 
-            //Chequear el estado posterior del sistema (no debería haber ningún producto).
-            assert!(sistema.productos.get(0).is_none());
+            assert_eq!(sistema.agregar_al_carrito(9, 1).unwrap_err(), ErrorSistema::PublicacionNoValida);
+            assert_eq!(sistema.agregar_al_carrito(0, 0).unwrap_err(), ErrorSistema::NoPuedeComprarCero);
+            assert_eq!(sistema.ver_carrito(), vec![(0, 4), (1, 2)]);
+
+            sistema.vaciar_carrito();
+            assert!(sistema.ver_carrito().is_empty());
         }
 
         #[ink::test]
-        fn test_nuevo_producto_error() {
+        //Test que verifica que comprar el carrito genere la orden y lo vacíe.
+        fn test_comprar_carrito() {
             let mut sistema = Sistema::new();
             let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Comprador);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 10, 10).unwrap();
+            sistema.crear_publicacion(0, 20, 10).unwrap();
 
-            let error = sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros).unwrap_err();
-            assert_eq!(error, ErrorSistema::UsuarioNoEsVendedor);//Chequear el estado posterior del sistema (no debería haber ningún producto).
-            assert!(sistema.productos.get(0).is_none());
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Comprador).unwrap();
+            sistema.agregar_al_carrito(0, 2).unwrap();
+            sistema.agregar_al_carrito(1, 1).unwrap();
 
-            //Chequear el estado posterior del sistema (no debería haber ningún producto).
-            assert!(sistema.productos.get(0).is_none());
+            //Si la compra falla, el carrito se conserva.
+            assert_eq!(sistema.comprar_carrito(10, Some(String::from("Calle 1 123"))).unwrap_err(), ErrorSistema::FondosInsuficientes { requerido: 40, disponible: 10 });
+            assert_eq!(sistema.ver_carrito(), vec![(0, 2), (1, 1)]);
+
+            let id_orden = sistema.comprar_carrito(100, Some(String::from("Calle 1 123"))).unwrap();
+            assert_eq!(id_orden, 0);
+            assert!(sistema.ver_carrito().is_empty());
+
+            let orden = sistema.ordenes.get(0).unwrap();
+            assert_eq!(orden.id_comprador, alice);
+            assert_eq!(orden.monto, 40);
+            assert_eq!(sistema.publicaciones[0].stock, 8);
+            assert_eq!(sistema.publicaciones[1].stock, 9);
+
+            //Un carrito vacío no genera orden.
+            assert_eq!(sistema.comprar_carrito(100, Some(String::from("Calle 1 123"))).unwrap_err(), ErrorSistema::CompraSinItems);
         }
 
         #[ink::test]
-        //Test en el que se registra un producto correctamente desde un usuario que es vendedor.
-        fn test_nuevo_producto_usuario_vendedor() {
+        //Test que verifica que un carrito con todas sus cantidades en cero se rechace en lugar de generar una orden vacía.
+        fn test_comprar_carrito_con_cantidades_cero() {
             let mut sistema = Sistema::new();
             let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 10, 10).unwrap();
+            sistema.crear_publicacion(0, 20, 10).unwrap();
 
-            let id_producto = sistema.nuevo_producto(String::from("Laptop"), String::from("Laptop gamer"), Categoria::Tecnologia).unwrap();
-            // Verifico que el producto se haya registrado correctamente.
-            let producto = sistema.productos.get(&id_producto);
-            assert!(producto.is_some());
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Comprador).unwrap();
+            //agregar_al_carrito no acepta cantidades cero, así que las escribo directamente.
+            sistema.carritos.insert(alice, &vec![(0, 0), (1, 0)]);
+
+            assert_eq!(sistema.comprar_carrito(100, Some(String::from("Calle 1 123"))).unwrap_err(), ErrorSistema::CompraSinItems);
+            assert!(sistema.id_ordenes.is_empty());
+
+            //Las líneas en cero se descartan y el resto se compra normalmente.
+            sistema.carritos.insert(alice, &vec![(0, 0), (1, 2)]);
+            sistema.comprar_carrito(100, Some(String::from("Calle 1 123"))).unwrap();
+            let orden = sistema.ordenes.get(0).unwrap();
+            assert_eq!(orden.lista_publicaciones, vec![(1, 2)]);
+            assert_eq!(orden.monto, 40);
         }
 
+        //-------------------------------------------------------------------------------------
+        //TESTS RESEÑAS DE PRODUCTOS
+
         #[ink::test]
-        //Test en el que se registra un producto correctamente desde un usuario con ambos roles.
-        fn test_nuevo_producto_usuario_ambos() {
+        //Test que verifica que el comprador de una orden recibida pueda reseñar sus productos una sola vez.
+        fn test_dejar_resena_okay() {
             let mut sistema = Sistema::new();
             let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 10, 10).unwrap();
 
-            let id_producto = sistema.nuevo_producto(String::from("Laptop"), String::from("Laptop gamer"), Categoria::Tecnologia).unwrap();
-            // Verifico que el producto se haya registrado correctamente.
-            let producto = sistema.productos.get(&id_producto);
-            assert!(producto.is_some());
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Comprador).unwrap();
+            sistema.generar_orden_compra(vec![(0, 1)], 100, Some(String::from("Calle 1 123"))).unwrap();
+
+            //No se puede reseñar antes de recibir la orden.
+            assert_eq!(sistema.dejar_resena(0, 0, String::from("Muy bueno"), 5).unwrap_err(), ErrorSistema::NoPuedePuntuarOrdenSinRecibir);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.marcar_orden_como_enviada(0).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.marcar_orden_como_recibida(0).unwrap();
+
+            assert_eq!(sistema.dejar_resena(0, 0, String::from("Muy bueno"), 6).unwrap_err(), ErrorSistema::PuntuacionNoValida);
+            assert_eq!(sistema.dejar_resena(0, 0, "a".repeat(MAX_LARGO_RESENA + 1), 5).unwrap_err(), ErrorSistema::ResenaDemasiadoLarga);
+
+            assert!(sistema.dejar_resena(0, 0, String::from("Muy bueno"), 5).is_ok());
+            assert_eq!(sistema.dejar_resena(0, 0, String::from("Otra vez"), 4).unwrap_err(), ErrorSistema::ResenaYaRealizada);
+
+            let resenas = sistema.ver_resenas(0);
+            assert_eq!(resenas.len(), 1);
+            assert_eq!(resenas[0].autor, alice);
+            assert_eq!(resenas[0].texto, "Muy bueno");
+            assert_eq!(resenas[0].puntuacion, 5);
         }
 
         #[ink::test]
-        // Test para corroborar que al crear un producto, se agregue tambien al vector del usuario
-        fn test_vector_de_productos_de_usuario(){
+        //Test que verifica que no se pueda reseñar un producto que no se compró.
+        fn test_dejar_resena_producto_no_comprado() {
             let mut sistema = Sistema::new();
             let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
-            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.nuevo_producto("Remera".to_string(), "Remera".to_string(), Categoria::Ropa, None).unwrap();
+            sistema.crear_publicacion(0, 10, 10).unwrap();
+            sistema.crear_publicacion(1, 10, 10).unwrap();
 
-            let user = sistema.usuarios.get(&charlie).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Comprador).unwrap();
+            sistema.generar_orden_compra(vec![(0, 1)], 100, Some(String::from("Calle 1 123"))).unwrap();
 
-            assert_eq!(user.productos.len(),1);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.marcar_orden_como_enviada(0).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.marcar_orden_como_recibida(0).unwrap();
+
+            //El producto 1 no está en la orden.
+            assert_eq!(sistema.dejar_resena(0, 1, String::from("Buena remera"), 4).unwrap_err(), ErrorSistema::ProductoInvalido);
+
+            //Un tercero no puede reseñar a partir de una orden ajena.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            assert_eq!(sistema.dejar_resena(0, 0, String::from("Malo"), 1).unwrap_err(), ErrorSistema::UsuarioNoAutorizado);
 
+            assert!(sistema.ver_resenas(0).is_empty());
+            assert!(sistema.ver_resenas(1).is_empty());
         }
 
+
+        //-------------------------------------------------------------------------------------
+        //TESTS DISPUTAS
+
         #[ink::test]
-        // test para no permitir que un usuario cree una publicacion con un producto que no haya creado este
-        fn test_publicacion_con_usuario_sin_producto_seleccionado(){
-            let mut sistema = Sistema::new();
+        //Test que verifica que sólo el comprador pueda abrir una disputa sobre una orden enviada y que esta bloquee la recepción y la cancelación.
+        fn test_abrir_disputa() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
             let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            let mut sistema = Sistema::new();
+
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
-            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None).unwrap();
+            sistema.crear_publicacion(0, 100, 4).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            sistema.generar_orden_compra(vec![(0, 2)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+
+            //No se puede disputar una orden pendiente.
+            assert_eq!(sistema.abrir_disputa(0).unwrap_err(), ErrorSistema::OperacionNoValida);
 
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.marcar_orden_como_enviada(0).unwrap();
+
+            //El vendedor no puede abrir la disputa.
+            assert_eq!(sistema.abrir_disputa(0).unwrap_err(), ErrorSistema::UsuarioNoAutorizado);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            assert_eq!(sistema.abrir_disputa(5).unwrap_err(), ErrorSistema::IdDeOrdenNoValida);
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(500);
+            assert!(sistema.abrir_disputa(0).is_ok());
+            assert_eq!(sistema.ordenes.get(0).unwrap().estado, EstadoOrdenCompra::EnDisputa);
+            assert_eq!(sistema.ordenes.get(0).unwrap().actualizada_en, 500);
+
+            //El monto sigue retenido y la orden no puede recibirse ni cancelarse.
+            assert_eq!(sistema.escrow_actual(), 200);
+            assert_eq!(sistema.marcar_orden_como_recibida(0).unwrap_err(), ErrorSistema::OrdenEnDisputa);
+            assert_eq!(sistema.cancelar_orden(0).unwrap_err(), ErrorSistema::OrdenEnDisputa);
+            assert_eq!(sistema.abrir_disputa(0).unwrap_err(), ErrorSistema::OperacionNoValida);
+        }
+
+        #[ink::test]
+        //Test que verifica la resolución de disputas a favor del comprador y del vendedor.
+        fn test_resolver_disputa() {
             let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
-            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Ambos);
+            let mut sistema = Sistema::new();
 
-            if let Err(e) = sistema.crear_publicacion(0, 20, 1) {
-                assert_eq!(e, ErrorSistema::UsuarioNoTieneProducto);
-            }
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None).unwrap();
+            sistema.crear_publicacion(0, 100, 10).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            sistema.generar_orden_compra(vec![(0, 2)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+            sistema.generar_orden_compra(vec![(0, 3)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.marcar_orden_como_enviada(0).unwrap();
+            sistema.marcar_orden_como_enviada(1).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.abrir_disputa(0).unwrap();
+            sistema.abrir_disputa(1).unwrap();
+
+            //Sólo el owner resuelve disputas.
+            assert_eq!(sistema.resolver_disputa(0, true).unwrap_err(), ErrorSistema::AccesoDenegado);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+
+            //A favor del comprador: la orden se cancela y se libera su monto.
+            assert!(sistema.resolver_disputa(0, true).is_ok());
+            assert_eq!(sistema.ordenes.get(0).unwrap().estado, EstadoOrdenCompra::Cancelado);
+            assert_eq!(sistema.escrow_actual(), 300);
+
+            //A favor del vendedor: la orden se da por recibida y se registra la venta.
+            assert!(sistema.resolver_disputa(1, false).is_ok());
+            assert_eq!(sistema.ordenes.get(1).unwrap().estado, EstadoOrdenCompra::Recibido);
+            assert_eq!(sistema.productos.get(0).unwrap().total_ventas, 3);
+            assert_eq!(sistema.escrow_actual(), 0);
+
+            //Una orden que no está en disputa no puede resolverse.
+            assert_eq!(sistema.resolver_disputa(1, true).unwrap_err(), ErrorSistema::OperacionNoValida);
         }
 
-       //-------------------------------------------------------------------------------------
-       //TESTS FUNCIONES INTERNAS:
 
         #[ink::test]
-        fn test_existe_usuario() {
+        //Test que verifica que el historial registre cada transición de una orden con su timestamp.
+        fn test_get_historial_estados() {
             let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let eve = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().eve;
 
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
             let mut sistema = Sistema::new();
-            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Comprador);
 
-            assert!(sistema._existe_usuario(alice).is_ok());
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None).unwrap();
+            sistema.crear_publicacion(0, 100, 4).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(100);
+            sistema.generar_orden_compra(vec![(0, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(200);
+            sistema.marcar_orden_como_enviada(0).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(300);
+            sistema.abrir_disputa(0).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(400);
+            sistema.resolver_disputa(0, false).unwrap();
+
+            //Alguien ajeno a la orden no puede consultar el historial.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(eve);
+            assert_eq!(sistema.get_historial_estados(0).unwrap_err(), ErrorSistema::UsuarioNoAutorizado);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            assert_eq!(sistema.get_historial_estados(0).unwrap(), vec![
+                (EstadoOrdenCompra::Pendiente, 100),
+                (EstadoOrdenCompra::Enviado, 200),
+                (EstadoOrdenCompra::EnDisputa, 300),
+                (EstadoOrdenCompra::Recibido, 400),
+            ]);
+            assert_eq!(sistema.get_historial_estados(3).unwrap_err(), ErrorSistema::IdDeOrdenNoValida);
         }
 
+
+        //-------------------------------------------------------------------------------------
+        //TESTS ENVÍO PARCIAL
+
         #[ink::test]
-        fn test_no_existe_usuaro() {
+        //Test que verifica que una orden con varias líneas sólo pase a Enviado cuando se envían todas.
+        fn test_marcar_item_enviado() {
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
             let mut sistema = Sistema::new();
 
-            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None).unwrap();
+            sistema.crear_publicacion(0, 10, 5).unwrap();
+            sistema.crear_publicacion(1, 20, 5).unwrap();
+
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            sistema.generar_orden_compra(vec![(0, 1), (1, 2)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+            assert_eq!(sistema.get_progreso_envio(0), vec![(0, false), (1, false)]);
 
-            //Pruebo con un usuario (bob) que no esté en el sistema.
-            assert!(sistema._existe_usuario(bob).is_err());
-        }
+            //Sólo el vendedor puede marcar líneas como enviadas.
+            assert_eq!(sistema.marcar_item_enviado(0, 0).unwrap_err(), ErrorSistema::UsuarioNoAutorizado);
 
-        #[ink::test]
-        //Registro un usuario en el sistema, que es vendedor y verifico que exista (con ese rol).
-        fn test_es_vendedor() {
-            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            assert_eq!(sistema.marcar_item_enviado(0, 9).unwrap_err(), ErrorSistema::ProductoInvalido);
 
-            let mut sistema = Sistema::new();
-            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor);
+            //Envío parcial: la orden sigue pendiente.
+            assert!(sistema.marcar_item_enviado(0, 1).is_ok());
+            assert_eq!(sistema.get_progreso_envio(0), vec![(0, false), (1, true)]);
+            assert_eq!(sistema.ordenes.get(0).unwrap().estado, EstadoOrdenCompra::Pendiente);
+            assert_eq!(sistema.marcar_item_enviado(0, 1).unwrap_err(), ErrorSistema::OperacionNoValida);
 
-            //Pruebo con un usuario (alice) que esté en el sistema y sea vendedor.
-            assert!(matches!(sistema.es_vendedor(), Ok(true)));
+            //Al enviar la última línea la orden pasa a Enviado.
+            assert!(sistema.marcar_item_enviado(0, 0).is_ok());
+            assert_eq!(sistema.get_progreso_envio(0), vec![(0, true), (1, true)]);
+            assert_eq!(sistema.ordenes.get(0).unwrap().estado, EstadoOrdenCompra::Enviado);
+            assert_eq!(sistema.marcar_item_enviado(0, 0).unwrap_err(), ErrorSistema::OperacionNoValida);
+            assert!(sistema.get_progreso_envio(5).is_empty());
         }
 
         #[ink::test]
-        //Registro un usuario en el sistema, que no es vendedor y verifico exista sin ese rol (que el modulo es_vendedor retorne falso).
-        fn test_no_es_vendedor() {
+        //Test que verifica que marcar la orden completa como enviada marque todas sus líneas.
+        fn test_marcar_orden_enviada_completa_envios() {
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
             let mut sistema = Sistema::new();
 
-            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Comprador);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None).unwrap();
+            sistema.crear_publicacion(0, 10, 5).unwrap();
+            sistema.crear_publicacion(1, 20, 5).unwrap();
 
-            //Pruebo con un usuario (charlie) que esté en el sistema pero no sea vendedor.
-            assert!(matches!(sistema.es_vendedor(), Ok(false)));
-        }
-
-        #[ink::test]
-        //No registro a un usuario en el sistema, y verifico que no exista (que el modulo es_vendedor retorne error).
-        fn test_es_vendedor_usuario_inexistente() {
-            let mut sistema = Sistema::new(); 
-            
-            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            sistema.generar_orden_compra(vec![(0, 1), (1, 2)], 1000, Some(String::from("Calle 1 123"))).unwrap();
 
-            //Pruebo con un usuario (bob) que no esté en el sistema.
-            assert!(sistema.es_vendedor().is_err());
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.marcar_item_enviado(0, 0).unwrap();
+            sistema.marcar_orden_como_enviada(0).unwrap();
+            assert_eq!(sistema.get_progreso_envio(0), vec![(0, true), (1, true)]);
+            assert_eq!(sistema.ordenes.get(0).unwrap().estado, EstadoOrdenCompra::Enviado);
         }
 
 
+        //-------------------------------------------------------------------------------------
+        //TESTS TOTAL VENDIDO
+
         #[ink::test]
-        //Registro un usuario en el sistema, que es comprador y verifico que exista (con ese rol).
-        fn test_es_comprador() {
+        //Test que verifica que el total vendido sólo considere las órdenes recibidas del vendedor.
+        fn test_total_vendido() {
             let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let mut sistema = Sistema::new();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 10, 50).unwrap();
+
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None).unwrap();
+            sistema.crear_publicacion(1, 7, 50).unwrap();
 
-            let mut sistema = Sistema::new();
-            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Comprador);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            assert_eq!(sistema.total_vendido(charlie), 0);
 
-            //Pruebo con un usuario (alice) que esté en el sistema y sea comprador.
-            assert!(matches!(sistema.es_comprador(), Ok(true)));
-        }
+            sistema.generar_orden_compra(vec![(0, 2)], 1000, Some(String::from("Calle 1 123"))).unwrap(); // 20, recibida
+            sistema.generar_orden_compra(vec![(0, 3)], 1000, Some(String::from("Calle 1 123"))).unwrap(); // 30, recibida
+            sistema.generar_orden_compra(vec![(0, 4)], 1000, Some(String::from("Calle 1 123"))).unwrap(); // 40, enviada
+            sistema.generar_orden_compra(vec![(0, 5)], 1000, Some(String::from("Calle 1 123"))).unwrap(); // 50, pendiente
+            sistema.generar_orden_compra(vec![(1, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap(); // 7, recibida de otro vendedor
 
-        #[ink::test]
-        //No registro a un usuario en el sistema, y verifico que no exista (que el modulo es_comprador retorne error).
-        fn test_es_comprador_usuario_inexistente() {
-            let mut sistema = Sistema::new(); 
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.marcar_orden_como_enviada(0).unwrap();
+            sistema.marcar_orden_como_enviada(1).unwrap();
+            sistema.marcar_orden_como_enviada(2).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.marcar_orden_como_enviada(4).unwrap();
 
-            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.marcar_orden_como_recibida(0).unwrap();
+            sistema.marcar_orden_como_recibida(1).unwrap();
+            sistema.marcar_orden_como_recibida(4).unwrap();
 
-            //Pruebo con un usuario (bob) que no esté en el sistema.
-            assert!(sistema.es_comprador().is_err());
+            assert_eq!(sistema.total_vendido(charlie), 50);
+            assert_eq!(sistema.total_vendido(alice), 7);
+            assert_eq!(sistema.total_vendido(bob), 0);
         }
 
+
+        //-------------------------------------------------------------------------------------
+        //TESTS MÉTRICAS GENERALES
+
         #[ink::test]
-        //Registro un usuario en el sistema, que no es comprador y verifico exista sin ese rol (que el modulo es_comprador retorne falso).
-        fn test_no_es_comprador() {
+        //Test que verifica las métricas generales del marketplace luego de una carga conocida.
+        fn test_metricas_generales() {
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
             let mut sistema = Sistema::new();
+            assert_eq!(sistema.metricas_generales(), (0, 0, 0, 0));
 
-            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 10, 5).unwrap();
+            sistema.crear_publicacion(0, 20, 2).unwrap();
 
-            //Pruebo con un usuario (charlie) que esté en el sistema pero no sea vendedor.
-            assert!(matches!(sistema.es_comprador(), Ok(false)));
-        }
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            sistema.generar_orden_compra(vec![(0, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+            //Esta orden deja sin stock a la segunda publicación.
+            sistema.generar_orden_compra(vec![(1, 2)], 1000, Some(String::from("Calle 1 123"))).unwrap();
 
-        //-------------------------------------------------------------------------------------
-        //TESTS AGREGAR_ROL:
-        #[ink::test]
-        //Se testea que se pueda agregar el rol de vendedor a un usuario que es comprador.
-        fn test_agregar_roles_a_vendedor() {
-            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.marcar_orden_como_enviada(0).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.marcar_orden_como_recibida(0).unwrap();
 
-            let mut sistema = Sistema::new();
-            //Inicializa alice como comprador.
-            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Comprador);
+            assert_eq!(sistema.metricas_generales(), (2, 1, 2, 1));
 
-            //Se agrega el rol de vendedor (pasa a tener ambos).
-            assert!(sistema.agregar_rol(Rol::Vendedor).is_ok());
-            if let Some(user) = sistema.usuarios.get(&alice) {
-                assert!(user.rol == Rol::Ambos);
-            }
+            //Una publicación con todo su stock reservado sigue activa.
+            sistema.reservar_stock(0, 4).unwrap();
+            assert_eq!(sistema.publicaciones[0].stock, 0);
+            assert_eq!(sistema.metricas_generales(), (2, 1, 2, 1));
         }
+
+
+        //-------------------------------------------------------------------------------------
+        //TESTS ÓRDENES POR ID
+
         #[ink::test]
-        //Se testea que se pueda agregar el rol de comprador a un usuario que es vendedor.
-        fn test_agregar_roles_a_comprador() {
-            //Inicializa bob como vendedor.
+        //Test que verifica que, si una orden se saca del índice, las demás se sigan encontrando por su id real.
+        fn test_ordenes_por_id_luego_de_quitar_una() {
             let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let mut sistema = Sistema::new();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 10, 50).unwrap();
+
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            sistema.generar_orden_compra(vec![(0, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+            sistema.generar_orden_compra(vec![(0, 2)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+            sistema.generar_orden_compra(vec![(0, 3)], 1000, Some(String::from("Calle 1 123"))).unwrap();
 
-            let mut sistema = Sistema::new();
-            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Vendedor);
+            //Archivo la primera orden quitándola del mapping y del índice.
+            sistema.ordenes.remove(0);
+            sistema.id_ordenes.retain(|id| *id != 0);
 
-            //Se agrega el rol de vendedor (pasa a tener ambos).
-            assert!(sistema.agregar_rol(Rol::Comprador).is_ok());
-            if let Some(user) = sistema.usuarios.get(&bob) {
-                assert!(user.rol == Rol::Ambos);
-            }
+            //Las órdenes restantes se resuelven por su id y no por su posición.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            assert_eq!(sistema.marcar_orden_como_enviada(0).unwrap_err(), ErrorSistema::IdDeOrdenNoValida);
+            assert!(sistema.marcar_orden_como_enviada(2).is_ok());
+            assert_eq!(sistema.ordenes.get(2).unwrap().estado, EstadoOrdenCompra::Enviado);
+            assert_eq!(sistema.ordenes.get(1).unwrap().estado, EstadoOrdenCompra::Pendiente);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            assert!(sistema.marcar_orden_como_recibida(2).is_ok());
+            assert!(sistema.cancelar_orden(1).is_ok());
+            assert_eq!(sistema.ordenes.get(1).unwrap().solicitud_cancelacion, Some(bob));
+
+            let mis_ordenes = sistema.ver_mis_ordenes();
+            assert_eq!(mis_ordenes.len(), 2);
+            assert_eq!(mis_ordenes[0].id_orden_compra, 1);
+            assert_eq!(mis_ordenes[1].id_orden_compra, 2);
+            assert_eq!(mis_ordenes[1].monto, 30);
         }
 
+
         #[ink::test]
-        //Se testea que se pueda agregar el rol de ambos a un usuario que es vendedor.
-        fn test_agregar_roles_a_ambos() {
-            //Inicializa bob como vendedor.
+        //Test que verifica que un usuario Ambos vea por separado las órdenes en las que vende y en las que compra.
+        fn test_ver_ordenes_como_vendedor_y_comprador() {
             let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let django = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().django;
+            let mut sistema = Sistema::new();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 10, 50).unwrap();
+
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Ambos).unwrap();
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None).unwrap();
+            sistema.crear_publicacion(1, 20, 50).unwrap();
+            //Bob compra a Charlie.
+            sistema.generar_orden_compra(vec![(0, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap();
 
-            let mut sistema = Sistema::new();
-            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Vendedor);
+            //Django le compra dos veces a Bob.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(django);
+            sistema.registrar_usuario(String::from("Django"), String::from("Surname"), String::from("django.email"), Rol::Comprador).unwrap();
+            sistema.generar_orden_compra(vec![(1, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+            sistema.generar_orden_compra(vec![(1, 2)], 1000, Some(String::from("Calle 1 123"))).unwrap();
 
-            //Se agrega el rol de vendedor (pasa a tener ambos).
-            assert!(sistema.agregar_rol(Rol::Ambos).is_ok());
-            if let Some(user) = sistema.usuarios.get(&bob) {
-                assert!(user.rol == Rol::Ambos);
-            }
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            let como_comprador = sistema.ver_ordenes_como_comprador();
+            let como_vendedor = sistema.ver_ordenes_como_vendedor();
+            assert_eq!(como_comprador.len(), 1);
+            assert_eq!(como_comprador[0].id_orden_compra, 0);
+            assert_eq!(como_vendedor.len(), 2);
+            assert!(como_vendedor.iter().all(|o| o.id_vendedor == bob && o.id_comprador == django));
+            assert_eq!(sistema.ver_mis_ordenes().len(), como_comprador.len() + como_vendedor.len());
+
+            //Un usuario que sólo compra no tiene órdenes como vendedor.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(django);
+            assert!(sistema.ver_ordenes_como_vendedor().is_empty());
+            assert_eq!(sistema.ver_ordenes_como_comprador().len(), 2);
         }
-        
+
         #[ink::test]
-        //Se testea que no se pueda agregar un rol que ya tiene el usuario.
-        fn test_agregar_roles_no_okay() {
-            //Inicializa charlie como vendedor.
+        //Test que verifica que el resumen de órdenes coincida con las órdenes completas, para comprador y vendedor.
+        fn test_ver_mis_ordenes_resumen() {
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
             let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let mut sistema = Sistema::new();
+
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None).unwrap();
+            sistema.crear_publicacion(0, 10, 50).unwrap();
+            sistema.crear_publicacion(1, 20, 50).unwrap();
 
-            let mut sistema = Sistema::new();
-            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            sistema.generar_orden_compra(vec![(0, 1), (1, 2)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+            sistema.generar_orden_compra(vec![(1, 3)], 1000, Some(String::from("Calle 1 123"))).unwrap();
 
-            //Ya tiene el rol de vendedor. Por lo que no se puede agregar el rol de vendedor otra vez.
-            let error = sistema.agregar_rol(Rol::Vendedor).unwrap_err();
-            assert_eq!(error, ErrorSistema::RolYaEnUso);
-        }
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.marcar_orden_como_enviada(1).unwrap();
 
-        #[ink::test]
-        //Se testea que no se pueda agregar un rol a un usuario que no existe en el sistema.
-        fn test_agregar_roles_usuario_inexistente() {
-            let mut sistema = Sistema::new();
-            let eve = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().eve;
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(eve);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            let resumen = sistema.ver_mis_ordenes_resumen();
+            assert_eq!(resumen, vec![
+                ResumenOrden { id_orden: 0, estado: EstadoOrdenCompra::Pendiente, monto: 50, contraparte: charlie, cantidad_items: 2 },
+                ResumenOrden { id_orden: 1, estado: EstadoOrdenCompra::Enviado, monto: 60, contraparte: charlie, cantidad_items: 1 },
+            ]);
 
-            //Pruebo con un usuario (eve) que no esté en el sistema.
-            let error = sistema.agregar_rol(Rol::Vendedor).unwrap_err();
-            assert_eq!(error, ErrorSistema::UsuarioNoExiste);
+            //Para el vendedor la contraparte es el comprador.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            let resumen = sistema.ver_mis_ordenes_resumen();
+            assert_eq!(resumen.len(), 2);
+            assert!(resumen.iter().all(|r| r.contraparte == bob));
+            assert_eq!(resumen[1].monto, sistema.ordenes.get(1).unwrap().monto);
         }
 
         #[ink::test]
-        /*TEST PARA EL FIX DE ESTA CORRECCIÓN: 
-        "Existe una falla en la lógica que hace posible eliminarse roles al usar la función agregarRol() 
-        teniendo ya el rol de Ambos. Permitiendo, por ejemplo, cambiar del rol Comprador a Ambos,
-         para posteriormente pasar a tener únicamente el rol Vendedor."  */
-        fn agregar_rol_desde_ambos() {
+        //Test que verifica que una orden archivada salga de los reportes pero siga disponible por id.
+        fn test_archivar_orden() {
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
             let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-
+            let eve = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().eve;
             let mut sistema = Sistema::new();
-            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
 
-            // Agrego el rol de Comprador, debería seguir siendo Ambos.
-            assert!(sistema.agregar_rol(Rol::Comprador).is_err());
-            let error = sistema.agregar_rol(Rol::Comprador).unwrap_err();
-            assert_eq!(error, ErrorSistema::RolYaEnUso);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 10, 50).unwrap();
 
-            if let Some(user) = sistema.usuarios.get(&charlie) {
-                assert!(user.rol == Rol::Ambos);
-            }
-        }
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            sistema.generar_orden_compra(vec![(0, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+            sistema.generar_orden_compra(vec![(0, 2)], 1000, Some(String::from("Calle 1 123"))).unwrap();
 
-        //-------------------------------------------------------------------------------------
-        //TESTS ORDEN DE COMPRA:
+            //Una orden pendiente no se puede archivar.
+            assert_eq!(sistema.archivar_orden(0).unwrap_err(), ErrorSistema::OperacionNoValida);
 
-        #[ink::test]
-        //Test para verificar que no se puede generar una orden de compra sin items.
-        fn generar_orden_compra_sin_items() {
-            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.marcar_orden_como_enviada(0).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.marcar_orden_como_recibida(0).unwrap();
+            assert_eq!(sistema.total_vendido(charlie), 10);
 
-            let mut sistema = Sistema::new();
-            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Comprador);
+            //Sólo el comprador o el vendedor pueden archivarla.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(eve);
+            assert_eq!(sistema.archivar_orden(0).unwrap_err(), ErrorSistema::UsuarioNoAutorizado);
 
-            //Pruebo generar una orden de compra sin items.
-            let error = sistema.generar_orden_compra(Vec::<(u128, u32)>::new(), 100).unwrap_err();
-            assert_eq!(error, ErrorSistema::CompraSinItems);
-        }
+            //Recibida pero sin puntuar por ambas partes todavía no se puede archivar.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            assert_eq!(sistema.archivar_orden(0).unwrap_err(), ErrorSistema::OperacionNoValida);
+            sistema.puntuar_usuario_por_orden(0, 4).unwrap();
+            assert_eq!(sistema.archivar_orden(0).unwrap_err(), ErrorSistema::OperacionNoValida);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.puntuar_usuario_por_orden(0, 5).unwrap();
 
-        #[ink::test]
-        //Test para verificar que no se puede generar una orden de compra de una publicacion que no existe.
-        fn test_generar_orden_compra_error() {
-            let mut sistema = Sistema::new();
-            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
+            assert!(sistema.archivar_orden(0).is_ok());
+            assert_eq!(sistema.archivar_orden(0).unwrap_err(), ErrorSistema::IdDeOrdenNoValida);
 
-            //Quiero forzar el error de publicacionNoValida
-            //No existe la publicación con id 0.
-            let error_publicacion_invalida = sistema.generar_orden_compra(vec![(0, 1)],1).unwrap_err();
-            assert_eq!(error_publicacion_invalida, ErrorSistema::PublicacionNoValida); //Ok
+            //Desaparece de los reportes activos.
+            assert_eq!(sistema.total_vendido(charlie), 0);
+            assert_eq!(sistema.metricas_generales(), (2, 1, 1, 0));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            let activas = sistema.ver_mis_ordenes();
+            assert_eq!(activas.len(), 1);
+            assert_eq!(activas[0].id_orden_compra, 1);
 
-            //Verifico que no se haya agregado ninguna orden de compra. (Estado posterior del sistema).
-            assert!(sistema.ordenes.is_empty());
+            //Pero sigue disponible por id y entre las archivadas.
+            assert_eq!(sistema.get_orden(0).unwrap().estado, EstadoOrdenCompra::Recibido);
+            assert_eq!(sistema.get_historial_estados(0).unwrap().len(), 3);
+            let archivadas = sistema.ver_mis_ordenes_archivadas();
+            assert_eq!(archivadas.len(), 1);
+            assert_eq!(archivadas[0].id_orden_compra, 0);
         }
 
         #[ink::test]
-        //Test para verificar que no se puede generar una orden de compra de una publicación propia.
-        fn test_generar_orden_compra_propia() {
-            let mut sistema = Sistema::new();
+        //Test que verifica qué reportes dejan de contar una venta archivada y cuáles la siguen contando.
+        fn test_reportes_sin_ordenes_archivadas() {
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
             let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let mut sistema = Sistema::new();
+
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 10, 50).unwrap();
 
-            //Quiero forzar el error de NoPuedeComprarPublicacionPropia
-            //Charlie crea una publicación y luego intenta comprarla.
-            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros);
-            sistema.crear_publicacion(0, 1000, 4);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            sistema.generar_orden_compra(vec![(0, 3)], 1000, Some(String::from("Calle 1 123"))).unwrap();
 
-            let error_no_puede_comprar_publicacion_propia = sistema.generar_orden_compra(vec![(0, 1)],4000).unwrap_err();
-            assert_eq!(error_no_puede_comprar_publicacion_propia, ErrorSistema::NoPuedeComprarPublicacionPropia); //Ok.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.marcar_orden_como_enviada(0).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.marcar_orden_como_recibida(0).unwrap();
+            sistema.puntuar_usuario_por_orden(0, 5).unwrap();
+            assert_eq!(sistema.categorias_compradas(bob).unwrap(), vec![Categoria::Limpieza]);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.puntuar_usuario_por_orden(0, 4).unwrap();
+            assert_eq!(sistema.mis_productos_tendencia(0).unwrap(), vec![(0, 3)]);
+            assert_eq!(sistema.ver_compras_de_producto(0).unwrap(), vec![(bob, 3)]);
+            assert_eq!(sistema._ver_productos_mas_vendidos(Categoria::Limpieza), vec![(0, 3)]);
 
-            //Verifico que no se haya agregado ninguna orden de compra. (Estado posterior del sistema).
-            assert!(sistema.ordenes.is_empty());
+            sistema.archivar_orden(0).unwrap();
+
+            //Los reportes que recorren las órdenes activas ya no la cuentan.
+            assert_eq!(sistema.total_vendido(charlie), 0);
+            assert_eq!(sistema.metricas_generales(), (2, 1, 0, 0));
+            assert!(sistema.mis_productos_tendencia(0).unwrap().is_empty());
+            assert!(sistema.ver_compras_de_producto(0).unwrap().is_empty());
+            assert!(sistema._ver_productos_mas_vendidos(Categoria::Limpieza).is_empty());
+            assert!(sistema.categorias_compradas(bob).unwrap().is_empty());
+            let estadisticas = sistema._estadisticas_por_categoria_detallado().unwrap();
+            assert_eq!(estadisticas[0].ordenes, 0);
+            assert_eq!(estadisticas[0].ingreso_total, 0);
+
+            //La reputación y el contador de ventas del producto la siguen contando.
+            assert_eq!(sistema.ver_reputacion(charlie).unwrap().n_ventas, 1);
+            assert_eq!(sistema.ver_reputacion(bob).unwrap().n_compras, 1);
+            assert_eq!(estadisticas[0].unidades_vendidas, 3);
         }
 
+
+        //-------------------------------------------------------------------------------------
+        //TESTS COMISIONES
+
         #[ink::test]
-        //Test para verificar que no se puede generar una orden de compra con dinero insuficiente.
-        fn test_generar_orden_compra_dinero_insuficiente() {
+        //Test que verifica que sólo el owner pueda configurar la comisión y que se rechacen valores mayores a 10000.
+        fn test_set_comision() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
             let mut sistema = Sistema::new();
-            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
 
-            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros);
-            sistema.crear_publicacion(0, 1000, 4); //La publicación la crea Charlie.
+            assert_eq!(sistema.set_comision(10001).unwrap_err(), ErrorSistema::ComisionInvalida);
+            assert!(sistema.set_comision(10000).is_ok());
+            assert!(sistema.set_comision(250).is_ok());
+            assert_eq!(sistema.comision_bps, 250);
+            //El valor rechazado no se recorta: la comisión anterior queda igual.
+            assert_eq!(sistema.set_comision(10001).unwrap_err(), ErrorSistema::ComisionInvalida);
+            assert_eq!(sistema.comision_bps, 250);
 
-            //Preparo al otro usuario para que compre de esa publicación. (Ya que no se puede generar una orden de compra a partir de una publicación propia).
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            assert_eq!(sistema.set_comision(0).unwrap_err(), ErrorSistema::AccesoDenegado);
+            assert_eq!(sistema.liquidar_comisiones().unwrap_err(), ErrorSistema::AccesoDenegado);
+            assert_eq!(sistema.comision_bps, 250);
+        }
+
+        #[ink::test]
+        //Test que verifica el límite de MAX_BPS (10000 se acepta, 10001 se rechaza) en comisiones por categoría y descuentos.
+        fn test_limite_bps_categoria_y_descuentos() {
             let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
-            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Ambos);
-            //Alice intenta comprar 1 termo, pero no tiene suficiente dinero (solo tiene 500).
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            let mut sistema = Sistema::new();
 
-            //Quiero forzar el error de DineroInsuficiente
-            let error_dinero_insuficiente = sistema.generar_orden_compra(vec![(0, 1)], 500).unwrap_err();
-            assert_eq!(error_dinero_insuficiente, ErrorSistema::DineroInsuficiente); //Ok.
+            assert!(sistema.set_comision_categoria(Categoria::Ropa, MAX_BPS).is_ok());
+            assert_eq!(sistema.comisiones_por_categoria.get(Categoria::Ropa), Some(10000));
+            assert_eq!(sistema.set_comision_categoria(Categoria::Ropa, MAX_BPS + 1).unwrap_err(), ErrorSistema::ComisionInvalida);
+            assert_eq!(sistema.comisiones_por_categoria.get(Categoria::Ropa), Some(10000));
 
-            //Verifico que no se haya agregado ninguna orden de compra. (Estado posterior del sistema).
-            assert!(sistema.ordenes.is_empty());
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Remera".to_string(), "Remera".to_string(), Categoria::Ropa, None).unwrap();
+            sistema.crear_publicacion(0, 100, 50).unwrap();
+            assert!(sistema.modificar_publicacion(0, None, None, Some(vec![(10, MAX_BPS)])).is_ok());
+            assert_eq!(sistema.modificar_publicacion(0, None, None, Some(vec![(10, MAX_BPS + 1)])).unwrap_err(), ErrorSistema::DescuentoInvalido);
+            assert_eq!(sistema.publicaciones[0].descuentos, vec![(10, 10000)]);
         }
 
         #[ink::test]
-        //Test para verificar que un usuario no pueda generar una orden de compra si no es comprador.
-        fn test_orden_compra_no_comprador() {
-            let mut sistema = Sistema::new();
+        //Test que verifica el reparto del monto entre vendedor y marketplace al completarse una orden y la liquidación de comisiones.
+        fn test_comision_al_recibir_orden() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
             let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            let mut sistema = Sistema::new();
+            //2,5% de comisión.
+            sistema.set_comision(250).unwrap();
+
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 125, 50).unwrap();
 
-            //Quiero forzar el error de UsuarioNoEsComprador
-            let error_usuario_no_comprador = sistema.generar_orden_compra(vec![(0, 1)], 1000).unwrap_err();
-            assert_eq!(error_usuario_no_comprador, ErrorSistema::UsuarioNoEsComprador); //Ok.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            sistema.generar_orden_compra(vec![(0, 2)], 1000, Some(String::from("Calle 1 123"))).unwrap(); // 250
+            sistema.generar_orden_compra(vec![(0, 4)], 1000, Some(String::from("Calle 1 123"))).unwrap(); // 500
 
-            //Verifico que no se haya agregado ninguna orden de compra. (Estado posterior del sistema).
-            assert!(sistema.ordenes.is_empty());
-        }
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.marcar_orden_como_enviada(0).unwrap();
+            sistema.marcar_orden_como_enviada(1).unwrap();
 
-        #[ink::test]
-        //Test para verificar que un usuario no pueda generar una orden de compra si no existe en el sistema.
-        fn test_orden_compra_usuario_inexistente() {
-            let mut sistema = Sistema::new();
-            let eve = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().eve;
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(eve);
+            //Mientras la orden no se recibe no hay comisión.
+            assert_eq!(sistema.comisiones_acumuladas, 0);
 
-            //Quiero forzar el error de UsuarioNoExiste
-            let error_usuario_no_existe = sistema.generar_orden_compra(vec![(0, 1)], 1000).unwrap_err();
-            assert_eq!(error_usuario_no_existe, ErrorSistema::UsuarioNoExiste); //Ok.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.marcar_orden_como_recibida(0).unwrap();
+            sistema.marcar_orden_como_recibida(1).unwrap();
 
-            //Verifico que no se haya agregado ninguna orden de compra. (Estado posterior del sistema).
-            assert!(sistema.ordenes.is_empty());
+            //250 * 2,5% = 6,25 -> 6 ; 500 * 2,5% = 12,5 -> 12
+            let orden = sistema.ordenes.get(0).unwrap();
+            assert_eq!(orden.comision, 6);
+            assert_eq!(orden.monto - orden.comision, 244);
+            assert_eq!(sistema.ordenes.get(1).unwrap().comision, 12);
+            assert_eq!(sistema.comisiones_acumuladas, 18);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            assert_eq!(sistema.liquidar_comisiones().unwrap(), 18);
+            assert_eq!(sistema.liquidar_comisiones().unwrap(), 0);
         }
 
 
-        #[ink::test]
-        //Test para verificar que se pueda generar una orden de compra correctamente.
-        fn test_ver_mis_ordenes() {
-            let mut sistema = Sistema::new();
-            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
 
-            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros);
-            sistema.crear_publicacion(0, 1000, 4); //La publicación la crea Charlie.
+        //-------------------------------------------------------------------------------------
+        //TESTS DIRECCION DE ENVIO
 
-            //Preparo al otro usuario para que compre de esa publicación. (Ya que no se puede generar una orden de compra a partir de una publicación propia).
+        #[ink::test]
+        //Test que verifica que la dirección indicada al comprar quede guardada en la orden y la vea el vendedor.
+        fn test_generar_orden_con_direccion() {
             let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
-            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Ambos);
-            let lista_compra = vec![(0, 1)];
-            assert!(sistema.generar_orden_compra(lista_compra,4000).is_ok());
+            let mut sistema = Sistema::new();
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 100, 10).unwrap();
 
-            //Verifico que la orden de compra se haya agregado a las órdenes del usuario Alice.
-            let mis_ordenes = sistema.ver_mis_ordenes();
-            assert_eq!(mis_ordenes.len(), 1);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            sistema.generar_orden_compra(vec![(0, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            let orden = sistema.get_orden(0).unwrap();
+            assert_eq!(orden.direccion_envio, Some(String::from("Calle 1 123")));
         }
 
         #[ink::test]
-        fn test_agregar_orden_usuario() {
+        //Test que verifica que se rechacen direcciones vacías o demasiado largas sin crear la orden.
+        fn test_generar_orden_direccion_invalida() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
             let mut sistema = Sistema::new();
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 100, 10).unwrap();
 
-            let error_usuario_no_existe = sistema.agregar_orden_usuario(AccountId::from([0x1; 32]), 1).unwrap_err();
-            assert_eq!(error_usuario_no_existe, ErrorSistema::UsuarioNoExiste); //No existe el usuario que llama a la función.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            assert_eq!(sistema.generar_orden_compra(vec![(0, 1)], 1000, Some(String::from("   "))).unwrap_err(), ErrorSistema::DireccionInvalida);
+            let larga = "a".repeat(MAX_LARGO_DIRECCION + 1);
+            assert_eq!(sistema.generar_orden_compra(vec![(0, 1)], 1000, Some(larga)).unwrap_err(), ErrorSistema::DireccionInvalida);
+
+            assert!(sistema.id_ordenes.is_empty());
+            assert_eq!(sistema.publicaciones[0].stock, 10);
         }
 
         #[ink::test]
-        //Test para verificar que no se puede agregar una orden de compra con publicaciones repetidas (no pasa la validación).
-        fn test_validar_orden_publicacion_repetida() {
+        //Test que verifica que por defecto se exija dirección de envío y que el owner pueda eximir una categoría.
+        fn test_direccion_requerida_por_categoria() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
             let mut sistema = Sistema::new();
-            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
+            sistema.set_direccion_requerida(Categoria::Musica, false).unwrap();
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.nuevo_producto("Disco".to_string(), "Vinilo".to_string(), Categoria::Musica, None).unwrap();
+            sistema.crear_publicacion(0, 100, 10).unwrap();
+            sistema.crear_publicacion(1, 100, 10).unwrap();
 
-            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros);
-            sistema.crear_publicacion(0, 1000, 4); //La publicación la crea Charlie.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            //Sólo el owner configura la exigencia.
+            assert_eq!(sistema.set_direccion_requerida(Categoria::Limpieza, false).unwrap_err(), ErrorSistema::AccesoDenegado);
 
-            //Quiero forzar el error de PublicacionRepetida.
-            let error_publicacion_repetida = sistema.validar_orden(vec![(0, 1), (0, 2)], charlie).unwrap_err(); 
-            assert_eq!(error_publicacion_repetida, ErrorSistema::PublicacionRepetida); 
+            //Sin dirección se rechaza si la orden tiene algún producto de una categoría no eximida.
+            assert_eq!(sistema.generar_orden_compra(vec![(0, 1)], 1000, None).unwrap_err(), ErrorSistema::DireccionRequerida);
+            assert_eq!(sistema.generar_orden_compra(vec![(1, 1), (0, 1)], 1000, None).unwrap_err(), ErrorSistema::DireccionRequerida);
+            assert!(sistema.id_ordenes.is_empty());
+            assert_eq!(sistema.publicaciones[0].stock, 10);
+
+            //Con dirección se acepta, y la categoría eximida no la necesita.
+            assert!(sistema.generar_orden_compra(vec![(0, 1)], 1000, Some(String::from("Calle 1 123"))).is_ok());
+            assert!(sistema.generar_orden_compra(vec![(1, 1)], 1000, None).is_ok());
+
+            //El owner puede volver a exigirla.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.set_direccion_requerida(Categoria::Musica, true).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            assert_eq!(sistema.generar_orden_compra(vec![(1, 1)], 1000, None).unwrap_err(), ErrorSistema::DireccionRequerida);
         }
 
         #[ink::test]
-        //Test para verificar que no se puede generar una orden de compra con cantidad cero (no pasa la validación).
-        fn test_validar_orden_comprar_cero() {
+        //Test que verifica que el comprador pueda actualizar la dirección sólo mientras la orden está pendiente.
+        fn test_actualizar_direccion() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
             let mut sistema = Sistema::new();
-            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 100, 10).unwrap();
 
-            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros);
-            sistema.crear_publicacion(0, 1000, 4); //La publicación la crea Charlie.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            sistema.generar_orden_compra(vec![(0, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap();
 
-            //Quiero forzar el error de NoPuedeComprarCero.
-            let error_no_puede_comprar_cero = sistema.validar_orden(vec![(0, 0)], charlie).unwrap_err(); 
-            assert_eq!(error_no_puede_comprar_cero, ErrorSistema::NoPuedeComprarCero); //Ok.
+            assert_eq!(sistema.actualizar_direccion(0, String::from("")).unwrap_err(), ErrorSistema::DireccionInvalida);
+            sistema.actualizar_direccion(0, String::from("Calle 2 456")).unwrap();
+            assert_eq!(sistema.ordenes.get(0).unwrap().direccion_envio, Some(String::from("Calle 2 456")));
+
+            //El vendedor no puede cambiarla.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            assert_eq!(sistema.actualizar_direccion(0, String::from("Otra")).unwrap_err(), ErrorSistema::UsuarioNoAutorizado);
+
+            //Una vez enviada, el comprador tampoco.
+            sistema.marcar_orden_como_enviada(0).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            assert_eq!(sistema.actualizar_direccion(0, String::from("Calle 3 789")).unwrap_err(), ErrorSistema::OperacionNoValida);
+            assert_eq!(sistema.ordenes.get(0).unwrap().direccion_envio, Some(String::from("Calle 2 456")));
         }
 
-        #[ink::test]
-        //Test para verificar que no se puede generar una orden de compra con productos de distintos usuarios vendedores (no pasa la validación).
-        fn test_validar_orden_vendedor_distinto() {
-            let mut sistema = Sistema::new();
-            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
 
-            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros);
-            sistema.crear_publicacion(0, 1000, 4); //La publicación la crea Charlie.
 
-            //Quiero forzar el error de VendedorDistinto.
+        //-------------------------------------------------------------------------------------
+        //TESTS SUBCATEGORIAS
+
+        #[ink::test]
+        //Test que verifica que la subcategoría se guarde normalizada y que se rechacen subcategorías vacías o demasiado largas.
+        fn test_nuevo_producto_con_subcategoria() {
             let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
-            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Ambos);
+            let mut sistema = Sistema::new();
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor).unwrap();
 
-            let error_vendedor_distinto = sistema.validar_orden(vec![(0, 1)], alice).unwrap_err();
-            assert_eq!(error_vendedor_distinto, ErrorSistema::VendedorDistinto); //Ok.
+            sistema.nuevo_producto("Laptop".to_string(), "Laptop gamer".to_string(), Categoria::Tecnologia, Some(" Notebooks ".to_string())).unwrap();
+            assert_eq!(sistema.productos.get(0).unwrap().subcategoria, Some(String::from("notebooks")));
+
+            assert_eq!(sistema.nuevo_producto("Laptop".to_string(), "Laptop".to_string(), Categoria::Tecnologia, Some("  ".to_string())).unwrap_err(), ErrorSistema::SubcategoriaInvalida);
+            let larga = "a".repeat(MAX_LARGO_SUBCATEGORIA + 1);
+            assert_eq!(sistema.nuevo_producto("Laptop".to_string(), "Laptop".to_string(), Categoria::Tecnologia, Some(larga)).unwrap_err(), ErrorSistema::SubcategoriaInvalida);
         }
 
         #[ink::test]
-        //Test para verificar que no se puede generar una orden de compra con stock insuficiente (no pasa la validación).
-        fn test_validar_orden_compra_stock_insuficiente() {
+        //Test que verifica el filtrado de publicaciones por subcategoría sin distinguir mayúsculas.
+        fn test_get_publicaciones_por_subcategoria() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
             let mut sistema = Sistema::new();
-            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Vinilo".to_string(), "Vinilo de jazz".to_string(), Categoria::Musica, Some("Vinilos".to_string())).unwrap();
+            sistema.nuevo_producto("Guitarra".to_string(), "Guitarra criolla".to_string(), Categoria::Musica, Some("Instrumentos".to_string())).unwrap();
+            sistema.nuevo_producto("Disco".to_string(), "Disco de rock".to_string(), Categoria::Musica, Some("vinilos".to_string())).unwrap();
+            sistema.nuevo_producto("Parlante".to_string(), "Parlante".to_string(), Categoria::Musica, None).unwrap();
+            sistema.crear_publicacion(0, 100, 10).unwrap();
+            sistema.crear_publicacion(1, 100, 10).unwrap();
+            sistema.crear_publicacion(2, 100, 10).unwrap();
+            sistema.crear_publicacion(3, 100, 10).unwrap();
 
-            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros);
-            sistema.crear_publicacion(0, 1000, 4); //La publicación la crea Charlie.
+            let vinilos = sistema.get_publicaciones_por_subcategoria("VINILOS".to_string());
+            assert_eq!(vinilos.len(), 2);
+            assert_eq!(vinilos[0].id_producto, 0);
+            assert_eq!(vinilos[1].id_producto, 2);
 
-            //Quiero forzar el error de StockInsuficiente.
-            let error_stock_insuficiente = sistema.validar_orden(vec![(0, 5)], charlie).unwrap_err(); //El stock es 4, y estoy tratando de comprar 5.
-            assert_eq!(error_stock_insuficiente, ErrorSistema::StockInsuficiente); //Ok.
+            assert_eq!(sistema.get_publicaciones_por_subcategoria("instrumentos".to_string()).len(), 1);
+            assert!(sistema.get_publicaciones_por_subcategoria("Libros".to_string()).is_empty());
+            assert!(sistema.get_publicaciones_por_subcategoria("".to_string()).is_empty());
         }
 
+
+
+        //-------------------------------------------------------------------------------------
+        //TESTS PUBLICACIONES ACTIVAS
+
         #[ink::test]
-        //Test para verificar que no se puede agregar una orden de compra con publicaciones no válidas (no pasa la validación).
-        fn test_validar_orden_publicacion_no_valida() {
-            let mut sistema = Sistema::new();
+        //Test que verifica que sólo se cuenten las publicaciones activas del vendedor indicado.
+        fn test_contar_publicaciones_activas() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
             let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            let mut sistema = Sistema::new();
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 100, 1).unwrap();
+            sistema.crear_publicacion(0, 100, 5).unwrap();
+            sistema.crear_publicacion(0, 100, 5).unwrap();
+
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Remera".to_string(), "Remera".to_string(), Categoria::Ropa, None).unwrap();
+            sistema.crear_publicacion(1, 100, 5).unwrap();
 
-            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros);
-            sistema.crear_publicacion(0, 1000, 4); //La publicación la crea Charlie.
+            assert_eq!(sistema.contar_publicaciones_activas(alice), 3);
 
-            //Quiero forzar el error de PublicacionNoValida.
-            let error_publicacion_invalida = sistema.validar_orden(vec![(1, 1)], charlie).unwrap_err();
-            assert_eq! (error_publicacion_invalida, ErrorSistema::PublicacionNoValida); //Ok.
+            //Bob agota la primera publicación de Alice, que queda desactivada.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            sistema.generar_orden_compra(vec![(0, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+
+            assert_eq!(sistema.contar_publicaciones_activas(alice), 2);
+            assert_eq!(sistema.contar_publicaciones_activas(charlie), 1);
+            assert_eq!(sistema.contar_publicaciones_activas(bob), 0);
         }
 
+
+
         //-------------------------------------------------------------------------------------
-        //TEST ESTADOS DE ORDEN
-  
+        //TESTS PROMEDIO DE CALIFICACIONES
+
         #[ink::test]
-        //Este test es para ver si salta el error (operación no válida) al tratar de cancelar una orden ya recibida (ya que es algo que no se puede hacer).
-        fn test_cancelar_orden_ya_recibida() {
-            //Genero una orden de compra
+        //Test que verifica el promedio de un vendedor sin calificaciones, con una y con varias (incluyendo redondeo).
+        fn test_promedio_calificacion_vendedor() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
             let mut sistema = Sistema::new();
-            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor);
+            sistema._registrar_usuario("alice".to_string(), "jhg".to_string(), "alialice@gmail.com".to_string(), Rol::Vendedor, alice).unwrap();
 
-            //Creo el producto.
-            if let Ok(id) = sistema.nuevo_producto("banana".to_string(), "una banana".to_string(), Categoria::Limpieza){
-                assert_eq!(id, 0);
-            }
+            assert_eq!(sistema.promedio_calificacion_vendedor(alice).unwrap(), None);
 
-            //Creo la publicación.
-            sistema.crear_publicacion(0, 10, 19);
+            let mut user = sistema.usuarios.get(alice).unwrap();
+            user.calificaciones_vendedor.push(4);
+            sistema.usuarios.insert(alice, &user);
+            assert_eq!(sistema.promedio_calificacion_vendedor(alice).unwrap(), Some(4));
 
-            let mut lista_compra = Vec::new();
-            lista_compra.push((0,2));
+            //(4 + 5) / 2 = 4,5 -> 5
+            user.calificaciones_vendedor.push(5);
+            sistema.usuarios.insert(alice, &user);
+            assert_eq!(sistema.promedio_calificacion_vendedor(alice).unwrap(), Some(5));
 
-            //Preparo al otro usuario para que compre de esa publicación. (Ya que no se puede generar una orden de compra a partir de una publicación propia).
+            //(4 + 5 + 1) / 3 = 3,33 -> 3
+            user.calificaciones_vendedor.push(1);
+            sistema.usuarios.insert(alice, &user);
+            assert_eq!(sistema.promedio_calificacion_vendedor(alice).unwrap(), Some(3));
+        }
+
+        #[ink::test]
+        //Test que verifica los errores al pedir el promedio de un usuario inexistente o que no es vendedor.
+        fn test_promedio_calificacion_vendedor_errores() {
             let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
-            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Ambos);
-            
-            assert!(sistema.generar_orden_compra(lista_compra, 200).is_ok());
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            let mut sistema = Sistema::new();
+            sistema._registrar_usuario("bob".to_string(), "zz".to_string(), "bob.zz@gmail.com".to_string(), Rol::Comprador, bob).unwrap();
 
-            //Marco como enviado (desde Charlie).
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            assert_eq!(sistema.promedio_calificacion_vendedor(alice).unwrap_err(), ErrorSistema::UsuarioNoExiste);
+            assert_eq!(sistema.promedio_calificacion_vendedor(bob).unwrap_err(), ErrorSistema::UsuarioNoEsVendedor);
+        }
 
-            assert!(sistema.marcar_orden_como_enviada(0).is_ok());
-            if let Some(orden) = sistema.ordenes.get(0){
-                assert_eq!(orden.estado, EstadoOrdenCompra::Enviado);
-            }
 
-            //Marco como recibido (desde Alice).
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
-            assert!(sistema.marcar_orden_como_recibida(0).is_ok());
+        #[ink::test]
+        //Test que verifica que el promedio ponderado favorece a las calificaciones recientes y difiere del promedio simple.
+        fn test_promedio_ponderado_vendedor() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            let mut sistema = Sistema::new();
+            sistema._registrar_usuario("alice".to_string(), "jhg".to_string(), "alialice@gmail.com".to_string(), Rol::Vendedor, alice).unwrap();
+            sistema._registrar_usuario("bob".to_string(), "zz".to_string(), "bob.zz@gmail.com".to_string(), Rol::Comprador, bob).unwrap();
 
-            //Trato de cancelar la orden (desde Alice) (esto debería fallar).
-            let error = sistema.cancelar_orden(0).unwrap_err();
-            assert_eq!(error, ErrorSistema::OperacionNoValida);
+            assert_eq!(sistema.promedio_ponderado_vendedor(alice).unwrap(), None);
+            assert_eq!(sistema.promedio_ponderado_vendedor(bob).unwrap_err(), ErrorSistema::UsuarioNoEsVendedor);
 
+            //Un 1 recibido al inicio y un 5 recibido justo al cumplirse la ventana.
+            let mut user = sistema.usuarios.get(alice).unwrap();
+            user.calificaciones_vendedor = vec![1, 5];
+            user.momentos_calificaciones_vendedor = vec![0, VENTANA_REPUTACION];
+            sistema.usuarios.insert(alice, &user);
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(VENTANA_REPUTACION);
 
-            //Trato de cancelar la orden también desde Charlie.
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            let error = sistema.cancelar_orden(0).unwrap_err();
-            assert_eq!(error, ErrorSistema::OperacionNoValida);
+            //Simple: (1 + 5) / 2 = 3. Ponderado: (1*1 + 5*10) / 11 = 4,6 -> 5
+            assert_eq!(sistema.promedio_calificacion_vendedor(alice).unwrap(), Some(3));
+            assert_eq!(sistema.promedio_ponderado_vendedor(alice).unwrap(), Some(5));
 
+            //Pasada otra ventana, las dos pesan 1 y el resultado coincide con el promedio simple.
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(2 * VENTANA_REPUTACION);
+            assert_eq!(sistema.promedio_ponderado_vendedor(alice).unwrap(), Some(3));
 
-            //Chequeo estado del sistema posteriormente (ver si no se modificó el estado de la orden).
-            if let Some(orden) = sistema.ordenes.get(0){
-                assert_eq!(orden.estado, EstadoOrdenCompra::Recibido);
-            }
+            //Una calificación sin momento registrado pesa como una antigua.
+            user.momentos_calificaciones_vendedor = Vec::new();
+            sistema.usuarios.insert(alice, &user);
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(0);
+            assert_eq!(sistema.promedio_ponderado_vendedor(alice).unwrap(), Some(3));
         }
 
         #[ink::test]
-        //Test que verifica que no se puede marcar la orden como enviada si el id de la misma es inválido.
-        fn test_marcar_orden_como_enviada_id_invalido() {
+        //Test que verifica el promedio de un comprador sin calificaciones, con una y con varias (incluyendo redondeo).
+        fn test_promedio_calificacion_comprador() {
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
             let mut sistema = Sistema::new();
-            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
+            sistema._registrar_usuario("bob".to_string(), "zz".to_string(), "bob.zz@gmail.com".to_string(), Rol::Comprador, bob).unwrap();
 
-            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros);
-            sistema.crear_publicacion(0, 1000, 4); //La publicación la crea Charlie.
+            assert_eq!(sistema.promedio_calificacion_comprador(bob).unwrap(), None);
 
-            //Quiero forzar el error de IdDeOrdenNoValida.
-            let error_id_invalido = sistema.marcar_orden_como_enviada(0).unwrap_err();
-            assert_eq!(error_id_invalido, ErrorSistema::IdDeOrdenNoValida); //No existe la orden con id 0.
+            let mut user = sistema.usuarios.get(bob).unwrap();
+            user.calificaciones_comprador.push(2);
+            sistema.usuarios.insert(bob, &user);
+            assert_eq!(sistema.promedio_calificacion_comprador(bob).unwrap(), Some(2));
 
-            //Chequeo estado del sistema posteriormente (ver si no se modificó el estado de la orden).
-            if let Some(orden) = sistema.ordenes.get(0){
-                assert_eq!(orden.estado, EstadoOrdenCompra::Pendiente);
-            }
+            //(2 + 3) / 2 = 2,5 -> 3
+            user.calificaciones_comprador.push(3);
+            sistema.usuarios.insert(bob, &user);
+            assert_eq!(sistema.promedio_calificacion_comprador(bob).unwrap(), Some(3));
+
+            //(2 + 3 + 5 + 5) / 4 = 3,75 -> 4
+            user.calificaciones_comprador.push(5);
+            user.calificaciones_comprador.push(5);
+            sistema.usuarios.insert(bob, &user);
+            assert_eq!(sistema.promedio_calificacion_comprador(bob).unwrap(), Some(4));
         }
 
         #[ink::test]
-        ///Test que verifica que no se puede marcar una orden como enviada si el caller no es el vendedor de la orden.
-        fn test_marcar_orden_enviada_caller_invalido() {
+        //Test que verifica los errores al pedir el promedio de un usuario inexistente o que no es comprador.
+        fn test_promedio_calificacion_comprador_errores() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
             let mut sistema = Sistema::new();
-            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
+            sistema._registrar_usuario("alice".to_string(), "jhg".to_string(), "alialice@gmail.com".to_string(), Rol::Vendedor, alice).unwrap();
 
-            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros);
-            sistema.crear_publicacion(0, 1000, 4); //La publicación la crea Charlie.
+            assert_eq!(sistema.promedio_calificacion_comprador(bob).unwrap_err(), ErrorSistema::UsuarioNoExiste);
+            assert_eq!(sistema.promedio_calificacion_comprador(alice).unwrap_err(), ErrorSistema::UsuarioNoEsComprador);
+        }
 
-            //Creo una orden de compra para que exista una orden con id 0.
+
+
+        //-------------------------------------------------------------------------------------
+        //TESTS EVENTO CALIFICACION
+
+        #[ink::test]
+        //Test que verifica que al puntuar se emita Calificacion con sus datos, y que no se emita si la puntuación falla.
+        fn test_evento_calificacion() {
             let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
-            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Ambos);
+            let mut sistema = Sistema::new();
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 100, 10).unwrap();
 
-            let lista_compra = vec![(0, 1)];
-            assert!(sistema.generar_orden_compra(lista_compra,4000).is_ok());
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            sistema.generar_orden_compra(vec![(0, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap();
 
-            //Quiero forzar el error de OperacionNoValida.
-            let error_operacion_no_valida = sistema.marcar_orden_como_enviada(0).unwrap_err(); //La estoy tratando de marcar como enviada desde Alice, pero la orden la creó Charlie.
-            assert_eq!(error_operacion_no_valida, ErrorSistema::OperacionNoValida); //El caller no es el vendedor de la orden.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.marcar_orden_como_enviada(0).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.marcar_orden_como_recibida(0).unwrap();
 
-            //Chequeo estado del sistema posteriormente (ver si no se modificó el estado de la orden).
-            if let Some(orden) = sistema.ordenes.get(0){
-                assert_eq!(orden.estado, EstadoOrdenCompra::Pendiente);
+            let previos = ink::env::test::recorded_events().count();
+
+            //Una puntuación inválida no emite eventos.
+            assert_eq!(sistema.puntuar_usuario_por_orden(0, 6).unwrap_err(), ErrorSistema::PuntuacionNoValida);
+            assert_eq!(ink::env::test::recorded_events().count(), previos);
+
+            sistema.puntuar_usuario_por_orden(0, 4).unwrap();
+            let eventos: Vec<_> = ink::env::test::recorded_events().collect();
+            assert_eq!(eventos.len(), previos + 1);
+            let evento = <Calificacion as ink::scale::Decode>::decode(&mut &eventos[previos].data[..]).unwrap();
+            assert_eq!(evento.calificado, alice);
+            assert_eq!(evento.calificador, bob);
+            assert_eq!(evento.id_orden, 0);
+            assert_eq!(evento.puntuacion, 4);
+            assert!(evento.es_vendedor);
+
+            //Puntuar dos veces tampoco emite un nuevo evento.
+            assert_eq!(sistema.puntuar_usuario_por_orden(0, 4).unwrap_err(), ErrorSistema::OrdenYaPuntuada);
+            assert_eq!(ink::env::test::recorded_events().count(), previos + 1);
+        }
+
+
+
+        //-------------------------------------------------------------------------------------
+        //TESTS CODIGOS DE ERROR
+
+        #[ink::test]
+        //Test que verifica que una muestra de errores agregados mantenga su código estable.
+        fn test_codigos_de_error() {
+            assert_eq!(ErrorSistema::NoSePuedeQuitarRol.codigo(), 30);
+            assert_eq!(ErrorSistema::StockOverflow.codigo(), 35);
+            assert_eq!(ErrorSistema::OrdenEnDisputa.codigo(), 46);
+            assert_eq!(ErrorSistema::FondosInsuficientes { requerido: 10, disponible: 5 }.codigo(), 47);
+            assert_eq!(ErrorSistema::PropuestaDesactualizada.codigo(), 64);
+        }
+
+        #[ink::test]
+        //Test que verifica que los errores de la versión inicial conserven su posición, y por lo tanto su codificación y su código.
+        fn test_codigos_de_error_iniciales() {
+            let iniciales = [
+                ErrorSistema::UsuarioYaRegistrado,
+                ErrorSistema::UsuarioNoExiste,
+                ErrorSistema::RolYaEnUso,
+                ErrorSistema::ProductosLleno,
+                ErrorSistema::UsuarioNoEsVendedor,
+                ErrorSistema::UsuarioNoEsComprador,
+                ErrorSistema::ProductoInvalido,
+                ErrorSistema::PublicacionesLleno,
+                ErrorSistema::CompraSinItems,
+                ErrorSistema::PublicacionNoValida,
+                ErrorSistema::StockInsuficiente,
+                ErrorSistema::VendedorDistinto,
+                ErrorSistema::IdDeOrdenNoValida,
+                ErrorSistema::PublicacionRepetida,
+                ErrorSistema::NoPuedeComprarCero,
+                ErrorSistema::NoPuedeComprarPublicacionPropia,
+                ErrorSistema::OperacionNoValida,
+                ErrorSistema::CancelacionYaSolicitada,
+                ErrorSistema::DineroInsuficiente,
+                ErrorSistema::FueraDeRango,
+                ErrorSistema::OrdenCancelada,
+                ErrorSistema::UsuarioNoTieneProducto,
+                ErrorSistema::UsuarioNoAutorizado,
+                ErrorSistema::PuntuacionNoValida,
+                ErrorSistema::OrdenYaPuntuada,
+                ErrorSistema::NoPuedePuntuarOrdenSinRecibir,
+                ErrorSistema::AccesoDenegado,
+                ErrorSistema::ReportesViewNoEstablecido,
+                ErrorSistema::ReportesViewYaEstablecido,
+            ];
+            for (i, error) in iniciales.iter().enumerate() {
+                assert_eq!(error.codigo(), i as u16 + 1);
+                assert_eq!(ink::scale::Encode::encode(error), vec![i as u8]);
             }
         }
 
         #[ink::test]
-        //Test que verifica que no se puede marcar una orden como enviada si la orden ya fue enviada.
-        fn test_marcar_orden_como_enviada_doble() {
-            let mut sistema = Sistema::new();
-            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
+        //Test que verifica que el índice con que scale codifica cada error coincida con su código estable.
+        fn test_codigos_de_error_coinciden_con_codificacion() {
+            let muestra = [
+                ErrorSistema::UsuarioYaRegistrado,
+                ErrorSistema::StockInsuficiente,
+                ErrorSistema::StockOverflow,
+                ErrorSistema::DineroInsuficiente,
+                ErrorSistema::FueraDeRango,
+                ErrorSistema::OrdenEnDisputa,
+                ErrorSistema::FondosInsuficientes { requerido: 10, disponible: 5 },
+                ErrorSistema::DescuentoInvalido,
+                ErrorSistema::StockInsuficienteDetalle { id_publicacion: 0, solicitado: 2, disponible: 1 },
+                ErrorSistema::ProductoEnUso,
+                ErrorSistema::SinStock,
+                ErrorSistema::CompradorConOrdenesEnCurso,
+            ];
+            for error in muestra.iter() {
+                let codificado = ink::scale::Encode::encode(error);
+                assert_eq!(codificado[0] as u16 + 1, error.codigo());
+            }
+        }
 
-            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros);
-            sistema.crear_publicacion(0, 1000, 4); //La publicación la crea Charlie.
+        #[ink::test]
+        //Test que verifica que una muestra de errores tenga mensajes no vacíos y distintos entre sí.
+        fn test_mensajes_de_error() {
+            let muestra = [
+                ErrorSistema::UsuarioNoExiste,
+                ErrorSistema::StockInsuficiente,
+                ErrorSistema::StockInsuficienteDetalle { id_publicacion: 0, solicitado: 2, disponible: 1 },
+                ErrorSistema::FondosInsuficientes { requerido: 10, disponible: 5 },
+                ErrorSistema::OperacionNoValida,
+                ErrorSistema::AccesoDenegado,
+                ErrorSistema::OrdenEnDisputa,
+            ];
+            for (i, error) in muestra.iter().enumerate() {
+                assert!(!error.mensaje().is_empty());
+                for otro in muestra[i + 1..].iter() {
+                    assert_ne!(error.mensaje(), otro.mensaje());
+                }
+            }
+            assert_eq!(ErrorSistema::UsuarioNoExiste.mensaje(), "El usuario no existe");
+        }
 
-            //Creo una orden de compra para que exista una orden con id 0.
+        #[ink::test]
+        //Test que verifica que FondosInsuficientes informe el total de la compra y el dinero disponible, y que se codifique con scale.
+        fn test_fondos_insuficientes_detalle() {
             let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
-            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Ambos);
+            let mut sistema = Sistema::new();
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.nuevo_producto("Remera".to_string(), "Remera".to_string(), Categoria::Ropa, None).unwrap();
+            sistema.crear_publicacion(0, 150, 10).unwrap();
+            sistema.crear_publicacion(1, 300, 10).unwrap();
 
-            let lista_compra = vec![(0, 1)];
-            assert!(sistema.generar_orden_compra(lista_compra,4000).is_ok());
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            let error = sistema.generar_orden_compra(vec![(0, 2), (1, 1)], 450, Some(String::from("Calle 1 123"))).unwrap_err();
+            assert_eq!(error, ErrorSistema::FondosInsuficientes { requerido: 600, disponible: 450 });
 
-            //Quiero forzar el error de OperacionNoValida porque la orden ya fue enviada.
+            let codificado = ink::scale::Encode::encode(&error);
+            let decodificado = <ErrorSistema as ink::scale::Decode>::decode(&mut &codificado[..]).unwrap();
+            assert_eq!(decodificado, error);
+        }
 
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+        #[ink::test]
+        //Test que verifica que StockInsuficienteDetalle informe la línea que falla con el stock actual de la publicación.
+        fn test_stock_insuficiente_detalle() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
 
-            assert!(sistema.marcar_orden_como_enviada(0).is_ok()); //Primero lo marco como enviada.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            let mut sistema = Sistema::new();
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 10, 10).unwrap();
+            sistema.crear_publicacion(0, 10, 5).unwrap();
 
-            //Trato de enviarlo otra vez.
-            let error_operacion_no_valida = sistema.marcar_orden_como_enviada(0).unwrap_err();
-            assert_eq!(error_operacion_no_valida, ErrorSistema::OperacionNoValida); //La orden ya fue enviada.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            //Una primera compra deja a la publicación 1 con 3 unidades.
+            sistema.generar_orden_compra(vec![(1, 2)], 1000, Some(String::from("Calle 1 123"))).unwrap();
 
-            //Chequeo estado del sistema posteriormente (ver si el estado de la orden quedó como enviado).
-            if let Some(orden) = sistema.ordenes.get(0){
-                assert_eq!(orden.estado, EstadoOrdenCompra::Enviado);
-            }
+            let error = sistema.generar_orden_compra(vec![(0, 1), (1, 4)], 1000, Some(String::from("Calle 1 123"))).unwrap_err();
+            assert_eq!(error, ErrorSistema::StockInsuficienteDetalle { id_publicacion: 1, solicitado: 4, disponible: sistema.publicaciones[1].stock });
+            assert_eq!(sistema.publicaciones[1].stock, 3);
+
+            let codificado = ink::scale::Encode::encode(&error);
+            assert_eq!(<ErrorSistema as ink::scale::Decode>::decode(&mut &codificado[..]).unwrap(), error);
         }
 
         #[ink::test]
-        //Test para verificar que se puede marcar una orden como enviada correctamente.
-        fn test_marcar_orden_enviada_okay() {
+        //Test que verifica que una publicación agotada devuelva SinStock y una con stock insuficiente StockInsuficienteDetalle.
+        fn test_sin_stock_vs_stock_insuficiente() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
             let mut sistema = Sistema::new();
-            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 10, 2).unwrap();
+            sistema.crear_publicacion(0, 10, 5).unwrap();
 
-            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros);
-            sistema.crear_publicacion(0, 1000, 4); //La publicación la crea Charlie.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            //Se compra todo el stock de la publicación 0.
+            sistema.generar_orden_compra(vec![(0, 2)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+            assert_eq!(sistema.publicaciones[0].stock, 0);
+
+            assert_eq!(sistema.generar_orden_compra(vec![(0, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap_err(), ErrorSistema::SinStock);
+            assert_eq!(
+                sistema.generar_orden_compra(vec![(1, 6)], 1000, Some(String::from("Calle 1 123"))).unwrap_err(),
+                ErrorSistema::StockInsuficienteDetalle { id_publicacion: 1, solicitado: 6, disponible: 5 }
+            );
+            assert_eq!(ErrorSistema::SinStock.codigo(), 63);
+            assert_ne!(ErrorSistema::SinStock.mensaje(), ErrorSistema::StockInsuficiente.mensaje());
+        }
 
-            //Creo una orden de compra para que exista una orden con id 0.
-            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
-            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Ambos);
 
-            //Genero la orden de compra.
-            let lista_compra = vec![(0, 1)];
-            assert!(sistema.generar_orden_compra(lista_compra,4000).is_ok());
 
+        //-------------------------------------------------------------------------------------
+        //TESTS REGISTRO EN LOTE
 
-            //Quiero marcar la orden como recibida.
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            assert!(sistema.marcar_orden_como_enviada(0).is_ok()); //Lo marco como enviada.
+        #[ink::test]
+        //Test que verifica un lote con un usuario repetido: los demás se registran y el repetido devuelve error.
+        fn test_registrar_usuarios_batch() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut sistema = Sistema::new();
 
-            //Chequeo el estado de la orden. (Estado posterior del sistema).
-            if let Some(orden) = sistema.ordenes.get(0){
-                assert_eq!(orden.estado, EstadoOrdenCompra::Enviado);
-            } else {
-                panic!("La orden no fue encontrada después de marcarla como enviada.");
-            }
+            let resultados = sistema.registrar_usuarios_batch(vec![
+                (accounts.bob, String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador),
+                (accounts.charlie, String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor),
+                (accounts.bob, String::from("Bob"), String::from("Otro"), String::from("bob2.email"), Rol::Ambos),
+            ]).unwrap();
 
+            assert_eq!(resultados, vec![Ok(()), Ok(()), Err(ErrorSistema::UsuarioYaRegistrado)]);
+            assert_eq!(sistema.id_usuarios.len(), 2);
+            assert_eq!(sistema.usuarios.get(accounts.bob).unwrap().rol, Rol::Comprador);
+            assert_eq!(sistema.usuarios.get(accounts.charlie).unwrap().rol, Rol::Vendedor);
         }
 
         #[ink::test]
-        //Test que verifica que se puede marcar una orden como recibida correctamente.
-        fn test_marcar_orden_como_recibida() {
+        //Test que verifica que sólo el owner pueda registrar usuarios en lote.
+        fn test_registrar_usuarios_batch_no_owner() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
             let mut sistema = Sistema::new();
-            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
 
-            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros);
-            sistema.crear_publicacion(0, 1000, 4); //La publicación la crea Charlie.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            let resultado = sistema.registrar_usuarios_batch(vec![
+                (accounts.bob, String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador),
+            ]);
+            assert_eq!(resultado.unwrap_err(), ErrorSistema::AccesoDenegado);
+            assert!(sistema.id_usuarios.is_empty());
+        }
 
-            //Creo una orden de compra para que exista una orden con id 0.
-            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
-            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Ambos);
 
-            //Genero la orden de compra.
-            let lista_compra = vec![(0, 1)];
-            assert!(sistema.generar_orden_compra(lista_compra,4000).is_ok());
 
+        //-------------------------------------------------------------------------------------
+        //TESTS SIMULAR ORDEN
 
-            //Quiero marcar la orden como recibida.
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            sistema.marcar_orden_como_enviada(0); //primero lo marco como enviada
+        #[ink::test]
+        //Test que verifica que la simulación devuelva el monto correcto y los mismos errores que la compra, sin tocar el stock.
+        fn test_simular_orden() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
 
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
-            assert_eq!(sistema.marcar_orden_como_recibida(0), Ok(()));
+            let mut sistema = Sistema::new();
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.nuevo_producto("Remera".to_string(), "Remera".to_string(), Categoria::Ropa, None).unwrap();
+            sistema.crear_publicacion(0, 100, 10).unwrap();
+            sistema.crear_publicacion(1, 250, 2).unwrap();
 
-            //Chequeo el estado de la orden. (Estado posterior del sistema).
-            if let Some(orden) = sistema.ordenes.get(0){
-                assert_eq!(orden.estado, EstadoOrdenCompra::Recibido);
-            } else {
-                panic!("La orden no fue encontrada después de marcarla como recibida.");
-            }
+            //Un vendedor no puede comprar.
+            assert_eq!(sistema.simular_orden(vec![(0, 1)], 1000).unwrap_err(), ErrorSistema::UsuarioNoEsComprador);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+
+            assert_eq!(sistema.simular_orden(vec![(0, 3), (1, 2)], 1000).unwrap(), 800);
+            assert_eq!(sistema.simular_orden(vec![], 1000).unwrap_err(), ErrorSistema::CompraSinItems);
+            assert_eq!(sistema.simular_orden(vec![(5, 1)], 1000).unwrap_err(), ErrorSistema::PublicacionNoValida);
+            assert_eq!(sistema.simular_orden(vec![(1, 3)], 1000).unwrap_err(), ErrorSistema::StockInsuficienteDetalle { id_publicacion: 1, solicitado: 3, disponible: 2 });
+            assert_eq!(sistema.simular_orden(vec![(0, 0)], 1000).unwrap_err(), ErrorSistema::NoPuedeComprarCero);
+            assert_eq!(sistema.simular_orden(vec![(0, 3), (1, 2)], 799).unwrap_err(), ErrorSistema::FondosInsuficientes { requerido: 800, disponible: 799 });
+
+            //Nada de lo anterior modifica el stock ni genera órdenes.
+            assert_eq!(sistema.publicaciones[0].stock, 10);
+            assert_eq!(sistema.publicaciones[1].stock, 2);
+            assert!(sistema.id_ordenes.is_empty());
         }
 
-        #[ink::test]
-        //Test que verifica que no se puede marcar una orden como recibida si quien lo hace es el usuario que la creó.
-        fn test_marcar_orden_recibida_mismo_caller() {
-            let mut sistema = Sistema::new();
-            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
 
-            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros);
-            sistema.crear_publicacion(0, 1000, 4); //La publicación la crea Charlie.
 
-            //Creo una orden de compra para que exista una orden con id 0.
+        //-------------------------------------------------------------------------------------
+        //TESTS DESCUENTOS POR CANTIDAD
+
+        //Función auxiliar: Alice publica Cif a 100 con 1000 de stock y Bob queda registrado como comprador.
+        fn sistema_con_publicacion_para_descuentos() -> Sistema {
             let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
-            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Ambos);
+            let mut sistema = Sistema::new();
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 100, 1000).unwrap();
 
-            //Genero la orden de compra.
-            let lista_compra = vec![(0, 1)];
-            assert!(sistema.generar_orden_compra(lista_compra,4000).is_ok());
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            sistema
+        }
 
+        #[ink::test]
+        //Test que verifica que sin descuentos se cobre el precio completo.
+        fn test_descuentos_sin_escalones() {
+            let sistema = sistema_con_publicacion_para_descuentos();
+            assert_eq!(sistema.simular_orden(vec![(0, 50)], 100000).unwrap(), 5000);
+        }
 
-            //Quiero marcar la orden como recibida.
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            sistema.marcar_orden_como_enviada(0); //primero lo marco como enviada
+        #[ink::test]
+        //Test que verifica que un único escalón se aplique sólo al alcanzar la cantidad mínima.
+        fn test_descuentos_un_escalon() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            let mut sistema = sistema_con_publicacion_para_descuentos();
 
-            //Chequeo que el usuario que marcó como enviada no pueda marcar como recibida. (No cambié el caller).
-            if let Err(e) = sistema.marcar_orden_como_recibida(0) {
-                assert_eq!(e, ErrorSistema::OperacionNoValida);
-            }
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.modificar_publicacion(0, None, None, Some(vec![(10, 1000)])).unwrap();
 
-            //Chequeo el estado de la orden. (Estado posterior del sistema).
-            //La orden no debería haber cambiado su estado.
-            if let Some(orden) = sistema.ordenes.get(0){
-                assert_eq!(orden.estado, EstadoOrdenCompra::Enviado);
-            }
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            assert_eq!(sistema.simular_orden(vec![(0, 9)], 100000).unwrap(), 900);
+            //10 * 100 = 1000, con 10% de descuento -> 900
+            let orden = sistema.generar_orden_compra(vec![(0, 10)], 900, Some(String::from("Calle 1 123"))).unwrap();
+            assert_eq!(orden.monto, 900);
         }
 
         #[ink::test]
-        //Test que verifica que no se puede marcar una orden como recibida si el id de la misma es inválido.
-        fn test_marcar_orden_enviada_orden_invalida() {
-            let mut sistema = Sistema::new();
-            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
+        //Test que verifica que con varios escalones se aplique el mejor alcanzado.
+        fn test_descuentos_mejor_escalon() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            let mut sistema = sistema_con_publicacion_para_descuentos();
 
-            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros);
-            sistema.crear_publicacion(0, 1000, 4); //La publicación la crea Charlie.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.modificar_publicacion(0, None, None, Some(vec![(10, 500), (50, 1000), (100, 750)])).unwrap();
 
-            //Quiero forzar el error de IdDeOrdenNoValida.
-            let error_id_invalido = sistema.marcar_orden_como_enviada(0).unwrap_err();
-            assert_eq!(error_id_invalido, ErrorSistema::IdDeOrdenNoValida); //No existe la orden con id 0.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            //20 unidades: sólo alcanza el 5%.
+            assert_eq!(sistema.simular_orden(vec![(0, 20)], 100000).unwrap(), 1900);
+            //60 unidades: alcanza 5% y 10%, se aplica el 10%.
+            assert_eq!(sistema.simular_orden(vec![(0, 60)], 100000).unwrap(), 5400);
+            //100 unidades: alcanza los tres escalones, el mejor sigue siendo el 10%.
+            assert_eq!(sistema.simular_orden(vec![(0, 100)], 100000).unwrap(), 9000);
         }
 
         #[ink::test]
-        //Test que verifica que no se puede marcar una orden como enviada, que ya fue (previamente) recibida.
-        fn test_marcar_orden_enviada_orden_recibida() {
-            let mut sistema = Sistema::new();
-            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
+        //Test que verifica las validaciones de modificar_publicacion.
+        fn test_modificar_publicacion_errores() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let mut sistema = sistema_con_publicacion_para_descuentos();
 
-            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros);
-            sistema.crear_publicacion(0, 1000, 4); //La publicación la crea Charlie.
+            //Bob no es el publicador.
+            assert_eq!(sistema.modificar_publicacion(0, Some(50), None, None).unwrap_err(), ErrorSistema::UsuarioNoAutorizado);
 
-            //Creo una orden de compra para que exista una orden con id 0.
-            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
-            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Ambos);
+            assert_eq!(sistema.modificar_publicacion(5, Some(50), None, None).unwrap_err(), ErrorSistema::PublicacionNoValida);
+            assert_eq!(sistema.modificar_publicacion(0, Some(0), None, None).unwrap_err(), ErrorSistema::PrecioInvalido);
+            //Escalones desordenados, repetidos, con mínimo cero o descuento mayor a 10000.
+            assert_eq!(sistema.modificar_publicacion(0, None, None, Some(vec![(50, 1000), (10, 500)])).unwrap_err(), ErrorSistema::DescuentoInvalido);
+            assert_eq!(sistema.modificar_publicacion(0, None, None, Some(vec![(10, 500), (10, 1000)])).unwrap_err(), ErrorSistema::DescuentoInvalido);
+            assert_eq!(sistema.modificar_publicacion(0, None, None, Some(vec![(0, 500)])).unwrap_err(), ErrorSistema::DescuentoInvalido);
+            assert_eq!(sistema.modificar_publicacion(0, None, None, Some(vec![(10, 10001)])).unwrap_err(), ErrorSistema::DescuentoInvalido);
 
-            //Genero la orden de compra.
-            let lista_compra = vec![(0, 1)];
-            assert!(sistema.generar_orden_compra(lista_compra,4000).is_ok());
+            //Un error no modifica nada, aunque vengan otros campos válidos.
+            assert_eq!(sistema.modificar_publicacion(0, Some(50), None, Some(vec![(10, 10001)])).unwrap_err(), ErrorSistema::DescuentoInvalido);
+            assert_eq!(sistema.publicaciones[0].precio, 100);
+            assert!(sistema.publicaciones[0].descuentos.is_empty());
 
-            //Quiero marcar la orden como enviada.
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            assert!(sistema.marcar_orden_como_enviada(0).is_ok()); //Lo marco como enviada.
+            sistema.modificar_publicacion(0, Some(50), Some(0), None).unwrap();
+            assert_eq!(sistema.publicaciones[0].precio, 50);
+            assert_eq!(sistema.publicaciones[0].stock, 0);
+            assert!(!sistema.publicaciones[0].activa);
+        }
 
-            //Ahora quiero marcarla como recibida.
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
-            assert!(sistema.marcar_orden_como_recibida(0).is_ok()); //Lo marco como recibida.
 
-            //Quiero forzar el error de OperacionNoValida porque la orden ya fue recibida.
-            let error_operacion_no_valida = sistema.marcar_orden_como_enviada(0).unwrap_err();
-            assert_eq!(error_operacion_no_valida, ErrorSistema::OperacionNoValida); //La orden ya fue recibida.
 
-            //Chequeo el estado de la orden. (Estado posterior del sistema).
-            if let Some(orden) = sistema.ordenes.get(0){
-                assert_eq!(orden.estado, EstadoOrdenCompra::Recibido);
-            } else {
-                panic!("La orden no fue encontrada después de marcarla como recibida.");
+        //-------------------------------------------------------------------------------------
+        //TESTS CATEGORIAS
+
+        #[ink::test]
+        //Test que verifica que get_categorias devuelva cada variante de Categoria una sola vez.
+        fn test_get_categorias() {
+            let sistema = Sistema::new();
+            let categorias = sistema.get_categorias();
+
+            //Este match deja de compilar si se agrega una variante, obligando a actualizar get_categorias y este test.
+            let cantidad_variantes = |c: &Categoria| match c {
+                Categoria::Limpieza | Categoria::Tecnologia | Categoria::Musica
+                | Categoria::Ropa | Categoria::Calzado | Categoria::Otros => 6,
+            };
+            assert_eq!(categorias.len(), cantidad_variantes(&categorias[0]));
+
+            for (i, categoria) in categorias.iter().enumerate() {
+                assert!(!categorias[i + 1..].contains(categoria));
             }
         }
 
-        #[ink::test]
-        //Test que verifica que no se puede marcar una orden como recibida si la orden no fue marcada como enviada previamente.
-        fn test_marcar_orden_recibida_sin_envio() {
-            let mut sistema = Sistema::new();
-            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
 
-            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros);
-            sistema.crear_publicacion(0, 1000, 4); //La publicación la crea Charlie.
 
-            //Creo una orden de compra para que exista una orden con id 0.
+        //-------------------------------------------------------------------------------------
+        //TESTS TRANSFERIR PUBLICACION
+
+        #[ink::test]
+        //Test que verifica que la transferencia cambie el publicador y mueva la publicación entre ambos usuarios.
+        fn test_transferir_publicacion() {
             let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
-            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Ambos);
+            let mut sistema = Sistema::new();
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 100, 10).unwrap();
 
-            let lista_compra = vec![(0, 1)];
-            assert!(sistema.generar_orden_compra(lista_compra,4000).is_ok());
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
 
+            //Sólo el publicador puede transferirla.
+            assert_eq!(sistema.transferir_publicacion(0, charlie).unwrap_err(), ErrorSistema::UsuarioNoAutorizado);
 
-            //Quiero forzar el error de OperacionNoValida.
-            let error_operacion_no_valida = sistema.marcar_orden_como_recibida(0).unwrap_err();
-            assert_eq!(error_operacion_no_valida, ErrorSistema::OperacionNoValida); //El caller trata de marcar la orden como recibida sin que esta fuera marcada como enviada previamente.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            assert_eq!(sistema.transferir_publicacion(0, bob).unwrap_err(), ErrorSistema::UsuarioNoEsVendedor);
+            assert_eq!(sistema.transferir_publicacion(0, alice).unwrap_err(), ErrorSistema::OperacionNoValida);
+            assert_eq!(sistema.transferir_publicacion(3, charlie).unwrap_err(), ErrorSistema::PublicacionNoValida);
+
+            sistema.transferir_publicacion(0, charlie).unwrap();
+            assert_eq!(sistema.publicaciones[0].id_publicador, charlie);
+            assert!(sistema.usuarios.get(alice).unwrap().publicaciones.is_empty());
+            assert_eq!(sistema.usuarios.get(charlie).unwrap().publicaciones, vec![0]);
         }
 
         #[ink::test]
-        //Test que verifica que no se puede marcar una orden como recibida si el caller no es el comprador de la orden.
-        fn test_marcar_orden_recibida_caller_invalido() {
-            let mut sistema = Sistema::new();
+        //Test que verifica que no se pueda transferir una publicación con una orden pendiente, y sí una vez recibida.
+        fn test_transferir_publicacion_con_orden_pendiente() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
             let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            let mut sistema = Sistema::new();
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 100, 10).unwrap();
+
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
 
-            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros);
-            sistema.crear_publicacion(0, 1000, 4); //La publicación la crea Charlie.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            sistema.generar_orden_compra(vec![(0, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap();
 
-            //Creo una orden de compra para que exista una orden con id 0.
-            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
-            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Ambos);
+            assert_eq!(sistema.transferir_publicacion(0, charlie).unwrap_err(), ErrorSistema::OperacionNoValida);
+            assert_eq!(sistema.publicaciones[0].id_publicador, alice);
 
-            let lista_compra = vec![(0, 1)];
-            assert!(sistema.generar_orden_compra(lista_compra,4000).is_ok());
+            sistema.marcar_orden_como_enviada(0).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.marcar_orden_como_recibida(0).unwrap();
 
-            //Primero la marco como enviada desde quien creo la publicación (Charlie).
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            sistema.marcar_orden_como_enviada(0); //Primero lo marco como enviada.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.transferir_publicacion(0, charlie).unwrap();
+            assert_eq!(sistema.publicaciones[0].id_publicador, charlie);
+        }
 
-            //Quiero forzar el error de OperacionNoValida.
-            let error_caller_invalido = sistema.marcar_orden_como_recibida(0).unwrap_err();
-            assert_eq!(error_caller_invalido, ErrorSistema::OperacionNoValida); //El caller no es el comprador de la orden.
 
-            //Verifico el error de marcar como recibida una orden que no existe
-            if let Err(e) = sistema.marcar_orden_como_recibida(5){
-                assert_eq!(e, ErrorSistema::IdDeOrdenNoValida)
-            }
-        }        
 
+        //-------------------------------------------------------------------------------------
+        //TESTS PUBLICACIONES DE UN VENDEDOR
 
         #[ink::test]
-        //Test que verifica que no se puede cancelar una orden cuando el id de la misma es inválido.
-        fn test_cancelar_orden_invalida() {
-            let mut sistema = Sistema::new();
+        //Test que verifica que un comprador vea sólo las publicaciones activas del vendedor consultado.
+        fn test_get_publicaciones_de() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
             let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
 
-            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros);
-            sistema.crear_publicacion(0, 1000, 4); //La publicación la crea Charlie.
-
-            //Quiero forzar el error de IdDeOrdenNoValida.
-            let error_id_invalido = sistema.cancelar_orden(0).unwrap_err();
-            assert_eq!(error_id_invalido, ErrorSistema::IdDeOrdenNoValida); //No existe la orden con id 0.     
-        }
-
-        #[ink::test]
-        //Test que verifica que se puede cancelar una orden correctamente.
-        fn test_cancelar_orden() {
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
             let mut sistema = Sistema::new();
-            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 100, 10).unwrap();
+            sistema.crear_publicacion(0, 100, 10).unwrap();
+            sistema.crear_publicacion(0, 100, 10).unwrap();
+            //La segunda publicación queda sin stock y por lo tanto inactiva.
+            sistema.modificar_publicacion(1, None, Some(0), None).unwrap();
+
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Remera".to_string(), "Remera".to_string(), Categoria::Ropa, None).unwrap();
+            sistema.crear_publicacion(1, 100, 10).unwrap();
+
+            //Bob es comprador y no necesita ser el vendedor para ver la tienda.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            let tienda: Vec<u128> = sistema.get_publicaciones_de(alice).iter().map(|p| p.id_publicacion).collect();
+            assert_eq!(tienda, vec![0, 2]);
+            assert_eq!(sistema.get_publicaciones_de(charlie).len(), 1);
+            assert!(sistema.get_publicaciones_de(bob).is_empty());
+        }
 
-            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros);
-            sistema.crear_publicacion(0, 1000, 4); //La publicación la crea Charlie.
 
-            //Creo una orden de compra para que exista una orden con id 0.
+
+        //-------------------------------------------------------------------------------------
+        //TESTS REPUTACION
+
+        #[ink::test]
+        //Test que verifica la reputación de un usuario Ambos luego de completar y puntuar órdenes como vendedor y como comprador.
+        fn test_ver_reputacion() {
             let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
-            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Ambos);
+            let mut sistema = Sistema::new();
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Ambos).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 100, 10).unwrap();
 
-            let lista_compra = vec![(0, 1)];
-            assert!(sistema.generar_orden_compra(lista_compra,4000).is_ok());
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Remera".to_string(), "Remera".to_string(), Categoria::Ropa, None).unwrap();
+            sistema.crear_publicacion(1, 100, 10).unwrap();
 
-            //Quiero cancelar la orden.
-            //Primero cancelo desde quien lo compró (alice).
-            assert!(sistema.cancelar_orden(0).is_ok());
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
 
-            //Chequeo  que el estado de la orden no se modificó todavía (porque falta la segunda parte de la cancelación).
-            if let Some(orden) = sistema.ordenes.get(0){
-                assert_eq!(orden.estado, EstadoOrdenCompra::Pendiente);
-            } else {
-                panic!("La orden no fue encontrada después de cancelarla.");
-            }
+            //Sin órdenes ni calificaciones.
+            assert_eq!(sistema.ver_reputacion(alice).unwrap(), Reputacion { prom_vendedor: None, n_ventas: 0, prom_comprador: None, n_compras: 0 });
 
-            //Chequeo que una vez solicitada, no puede solicitar su cancelacion de nuevo
-            if let Err(e) = sistema.cancelar_orden(0) {
-                assert_eq!(e, ErrorSistema::CancelacionYaSolicitada)
-            }
+            //Bob le compra dos veces a Alice (órdenes 0 y 1) y Alice le compra una vez a Charlie (orden 2).
+            sistema.generar_orden_compra(vec![(0, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+            sistema.generar_orden_compra(vec![(0, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.generar_orden_compra(vec![(1, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap();
 
-            //Ahora cancelo desde quien la creó (Charlie).
+            sistema.marcar_orden_como_enviada(0).unwrap();
+            sistema.marcar_orden_como_enviada(1).unwrap();
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            assert!(sistema.cancelar_orden(0).is_ok());
+            sistema.marcar_orden_como_enviada(2).unwrap();
 
-            //Chequeo que el estado de la orden cambió a cancelado.
-            if let Some(orden) = sistema.ordenes.get(0){
-                assert_eq!(orden.estado, EstadoOrdenCompra::Cancelado);
-            } else {
-                panic!("La orden no fue encontrada después de cancelarla.");
-            }
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.marcar_orden_como_recibida(0).unwrap();
+            sistema.marcar_orden_como_recibida(1).unwrap();
+            sistema.puntuar_usuario_por_orden(0, 5).unwrap();
+            sistema.puntuar_usuario_por_orden(1, 4).unwrap();
 
-            //Chequeo que una vez cancelado, no pueda solicitar su cancelacion de nuevo
-            if let Err(e) = sistema.cancelar_orden(0) {
-                assert_eq!(e, ErrorSistema::OrdenCancelada)
-            }
-        }
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.marcar_orden_como_recibida(2).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.puntuar_usuario_por_orden(2, 3).unwrap();
 
-        #[ink::test]
-        // test que verifica que un usuario que no esta involucrado en una orden no pueda cancelarla
-        fn test_tercero_no_puede_cancelar_orden(){
-            let mut sistema = Sistema::new();
-            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
-            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            //Una orden archivada también cuenta.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.puntuar_usuario_por_orden(0, 4).unwrap();
+            sistema.archivar_orden(0).unwrap();
 
-            sistema.ordenes.push(
-                OrdenCompra {
-                    lista_productos: vec!((1,1)),
-                    id_orden_compra: 0,
-                    estado: EstadoOrdenCompra::Pendiente,
-                    id_comprador:charlie,
-                    id_vendedor:alice,
-                    solicitud_cancelacion: None,
-                    monto:23,
-                    puntuado_por_comprador:false,
-                    puntuado_por_vendedor:false,
-                }
-            );
-            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            //(5 + 4) / 2 = 4,5 -> 5
+            assert_eq!(sistema.ver_reputacion(alice).unwrap(), Reputacion { prom_vendedor: Some(5), n_ventas: 2, prom_comprador: Some(3), n_compras: 1 });
+            assert_eq!(sistema.ver_reputacion(bob).unwrap(), Reputacion { prom_vendedor: None, n_ventas: 0, prom_comprador: Some(4), n_compras: 2 });
 
-            if let Err(e) = sistema.cancelar_orden(0){
-                assert_eq!(e, ErrorSistema::UsuarioNoAutorizado);
-            }
+            let django = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().django;
+            assert_eq!(sistema.ver_reputacion(django).unwrap_err(), ErrorSistema::UsuarioNoExiste);
         }
 
+
+
         //-------------------------------------------------------------------------------------
-        //TESTS PUNTUACIÓN A USUARIOS
+        //TESTS CANCELACION UNILATERAL DEL COMPRADOR
 
         #[ink::test]
-        //Test puntuar funciona correctamente.
-        fn test_puntuar_ok(){
-            let mut sistema = Sistema::new();
-            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+        //Test que verifica que el comprador cancele por su cuenta una orden pendiente, recuperando el stock y liberando el escrow.
+        fn test_cancelar_como_comprador_pendiente() {
             let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
 
-            sistema.productos.insert(1, &Producto{
-                nombre: "test".to_string(),
-                descripcion: "otro test".to_string(),
-                categoria:Categoria::Calzado,
-                puntuaciones: Vec::<u8>::new(),
-                total_ventas:0,
-            });
-
-            sistema.ordenes.push(
-                OrdenCompra {
-                    lista_productos: vec!((1,1)),
-                    id_orden_compra: 0,
-                    estado: EstadoOrdenCompra::Recibido,
-                    id_comprador:charlie,
-                    id_vendedor:alice,
-                    solicitud_cancelacion: None,
-                    monto:23,
-                    puntuado_por_comprador:false,
-                    puntuado_por_vendedor:false,
-                }
-            );
-            sistema._registrar_usuario("charlie".to_string(), "zz".to_string(), "char.zz@gmail.com".to_string(), Rol::Ambos, charlie);
-            sistema._registrar_usuario("alice".to_string(), "jhg".to_string(), "alialice@gmail.com".to_string(), Rol::Ambos, alice);
-
-            assert_eq!(sistema.usuarios.get(alice).unwrap().calificaciones_vendedor.len(),0);
-            assert_eq!(sistema.usuarios.get(charlie).unwrap().calificaciones_comprador.len(),0);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            let mut sistema = Sistema::new();
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 100, 10).unwrap();
 
-            assert!(sistema._puntuar_usuario_por_orden(0, 3, charlie).is_ok());
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            sistema.generar_orden_compra(vec![(0, 4)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+            assert_eq!(sistema.publicaciones[0].stock, 6);
+            assert_eq!(sistema.escrow_actual(), 400);
 
+            //El vendedor no puede usar este camino.
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
-            assert!(sistema.puntuar_usuario_por_orden(0, 3).is_ok());
+            assert_eq!(sistema.cancelar_como_comprador(0).unwrap_err(), ErrorSistema::UsuarioNoAutorizado);
 
-            assert_eq!(sistema.usuarios.get(alice).unwrap().calificaciones_vendedor.len(),1);
-            assert_eq!(sistema.usuarios.get(charlie).unwrap().calificaciones_comprador.len(),1);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.cancelar_como_comprador(0).unwrap();
+            assert_eq!(sistema.ordenes.get(0).unwrap().estado, EstadoOrdenCompra::Cancelado);
+            assert_eq!(sistema.publicaciones[0].stock, 10);
+            assert_eq!(sistema.escrow_actual(), 0);
 
-           
+            assert_eq!(sistema.cancelar_como_comprador(0).unwrap_err(), ErrorSistema::OperacionNoValida);
         }
 
         #[ink::test]
-        //Test para verificar que un usuario no pueda puntuar mas de una vez y comprobaciones previas.
-        fn test_errores_al_puntuar(){
-            let mut sistema = Sistema::new();
-            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+        //Test que verifica que una orden enviada, aunque sea en parte, siga requiriendo la cancelación de ambas partes.
+        fn test_cancelar_como_comprador_enviada() {
             let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
 
-            sistema.ordenes.push(
-                OrdenCompra {
-                    lista_productos: vec!((1,1)),
-                    id_orden_compra: 0,
-                    estado: EstadoOrdenCompra::Recibido,
-                    id_comprador:charlie,
-                    id_vendedor:alice,
-                    solicitud_cancelacion: None,
-                    monto:23,
-                    puntuado_por_comprador:true,
-                    puntuado_por_vendedor:true,
-                }
-            );
-            sistema._registrar_usuario("charlie".to_string(), "zz".to_string(), "char.zz@gmail.com".to_string(), Rol::Ambos, charlie);
-            sistema._registrar_usuario("alice".to_string(), "jhg".to_string(), "alialice@gmail.com".to_string(), Rol::Ambos, alice);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            let mut sistema = Sistema::new();
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.nuevo_producto("Remera".to_string(), "Remera".to_string(), Categoria::Ropa, None).unwrap();
+            sistema.crear_publicacion(0, 100, 10).unwrap();
+            sistema.crear_publicacion(1, 100, 10).unwrap();
 
-            assert_eq!(sistema._puntuar_usuario_por_orden(1, 3, charlie).unwrap_err(), ErrorSistema::IdDeOrdenNoValida);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            sistema.generar_orden_compra(vec![(0, 1), (1, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+            sistema.generar_orden_compra(vec![(0, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap();
 
-            assert_eq!(sistema._puntuar_usuario_por_orden(0, 6, alice).unwrap_err(), ErrorSistema::PuntuacionNoValida);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            //La orden 0 queda con un producto enviado y sigue pendiente; la 1 se envía completa.
+            sistema.marcar_item_enviado(0, 0).unwrap();
+            sistema.marcar_orden_como_enviada(1).unwrap();
 
-            assert_eq!(sistema._puntuar_usuario_por_orden(0, 2, charlie).unwrap_err(), ErrorSistema::OrdenYaPuntuada);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            assert_eq!(sistema.cancelar_como_comprador(0).unwrap_err(), ErrorSistema::OperacionNoValida);
+            assert_eq!(sistema.cancelar_como_comprador(1).unwrap_err(), ErrorSistema::OperacionNoValida);
 
-            assert_eq!(sistema._puntuar_usuario_por_orden(0, 2, alice).unwrap_err(), ErrorSistema::OrdenYaPuntuada);
+            //Para la orden enviada sigue haciendo falta que ambos confirmen.
+            sistema.cancelar_orden(1).unwrap();
+            assert_eq!(sistema.ordenes.get(1).unwrap().estado, EstadoOrdenCompra::Enviado);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.cancelar_orden(1).unwrap();
+            assert_eq!(sistema.ordenes.get(1).unwrap().estado, EstadoOrdenCompra::Cancelado);
+        }
 
 
-           
-        }
 
-         #[ink::test]
-        //Test para verificar que un usuario no pueda puntuar una orden no marcada como recibida ni por terceros.
-        fn test_puntuar_orden_recibida(){
-            let mut sistema = Sistema::new();
-            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+        //-------------------------------------------------------------------------------------
+        //TESTS PAGINACION DE ORDENES
+
+        #[ink::test]
+        //Test que verifica las ventanas de ver_mis_ordenes_paginado, el tope de MAX_TAMANO_PAGINA y los offsets fuera de rango.
+        fn test_ver_mis_ordenes_paginado() {
             let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
 
-            sistema.ordenes.push(
-                OrdenCompra {
-                    lista_productos: vec!((1,1)),
-                    id_orden_compra: 0,
-                    estado: EstadoOrdenCompra::Enviado,
-                    id_comprador:charlie,
-                    id_vendedor:alice,
-                    solicitud_cancelacion: None,
-                    monto:23,
-                    puntuado_por_comprador:true,
-                    puntuado_por_vendedor:true,
-                }
-            );
-            sistema._registrar_usuario("charlie".to_string(), "zz".to_string(), "char.zz@gmail.com".to_string(), Rol::Ambos, charlie);
-            sistema._registrar_usuario("alice".to_string(), "jhg".to_string(), "alialice@gmail.com".to_string(), Rol::Ambos, alice);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            let mut sistema = Sistema::new();
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 1, 1000).unwrap();
 
-            assert_eq!(sistema._puntuar_usuario_por_orden(0, 1, charlie).unwrap_err(), ErrorSistema::NoPuedePuntuarOrdenSinRecibir);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            let total = MAX_TAMANO_PAGINA + 10;
+            for _ in 0..total {
+                sistema.generar_orden_compra(vec![(0, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+            }
 
-            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            let ids = |ordenes: Vec<OrdenCompra>| -> Vec<u128> { ordenes.iter().map(|o| o.id_orden_compra).collect() };
+            assert_eq!(ids(sistema.ver_mis_ordenes_paginado(0, 3)), vec![0, 1, 2]);
+            assert_eq!(ids(sistema.ver_mis_ordenes_paginado(3, 3)), vec![3, 4, 5]);
 
-            sistema.ordenes.push(
-                OrdenCompra {
-                    lista_productos: vec!((1,1)),
-                    id_orden_compra: 1,
-                    estado: EstadoOrdenCompra::Recibido,
-                    id_comprador:charlie,
-                    id_vendedor:alice,
-                    solicitud_cancelacion: None,
-                    monto:23,
-                    puntuado_por_comprador:false,
-                    puntuado_por_vendedor:false,
-                }
-            );
-            assert_eq!(sistema._puntuar_usuario_por_orden(1, 5, bob).unwrap_err(), ErrorSistema::UsuarioNoAutorizado);
+            //El límite se recorta al tamaño máximo de página.
+            assert_eq!(sistema.ver_mis_ordenes_paginado(0, u32::MAX).len(), MAX_TAMANO_PAGINA as usize);
 
-           
+            //La última página puede venir incompleta, y un offset fuera de rango devuelve un vector vacío.
+            assert_eq!(ids(sistema.ver_mis_ordenes_paginado(total - 2, 5)), vec![(total - 2) as u128, (total - 1) as u128]);
+            assert!(sistema.ver_mis_ordenes_paginado(total, 5).is_empty());
+            assert!(sistema.ver_mis_ordenes_paginado(u32::MAX, 5).is_empty());
+            assert!(sistema.ver_mis_ordenes_paginado(0, 0).is_empty());
         }
 
+
         #[ink::test]
-        // tests para obtener los puntajes.
-        fn test_obtener_puntajes(){
-            let mut sistema = Sistema::new();
-            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+        //Test que verifica que se aplique la comisión de la categoría dominante de la orden y la global si no tiene una propia.
+        fn test_comision_por_categoria() {
             let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
 
-            sistema.usuarios.insert(charlie, 
-                &Usuario{
-                    nombre:"carlos".to_string(),
-                    apellido:"cc".to_string(),
-                    email:"carlocc".to_string(),
-                    id:charlie,
-                    rol:Rol::Ambos,
-                    publicaciones:Vec::new(),
-                    ordenes:Vec::new(),
-                    productos:Vec::new(),
-                    calificaciones_comprador:vec!(2,3,4),
-                    calificaciones_vendedor:vec!(5,5,5),
-            });
-            assert_eq!(sistema.obtener_puntuacion_de_comprador(alice).unwrap_err(), ErrorSistema::UsuarioNoExiste);
-            assert_eq!(sistema.obtener_puntuacion_de_vendedor(alice).unwrap_err(), ErrorSistema::UsuarioNoExiste);
-            
-            sistema.usuarios.insert(alice, 
-                &Usuario{
-                    nombre:"carlos".to_string(),
-                    apellido:"cc".to_string(),
-                    email:"carlocc".to_string(),
-                    id:charlie,
-                    rol:Rol::Ambos,
-                    publicaciones:Vec::new(),
-                    ordenes:Vec::new(),
-                    productos:Vec::new(),
-                    calificaciones_comprador:Vec::new(),
-                    calificaciones_vendedor:Vec::new(),
-            });
-            assert_eq!(sistema.obtener_puntuacion_de_comprador(alice), Ok(0));
-            assert_eq!(sistema.obtener_puntuacion_de_vendedor(alice), Ok(0));
-            
-            assert_eq!(sistema.obtener_puntuacion_de_comprador(charlie), Ok(3));
-            assert_eq!(sistema.obtener_puntuacion_de_vendedor(charlie), Ok(5));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            let mut sistema = Sistema::new();
+            //1% global y 5% para Tecnología.
+            sistema.set_comision(100).unwrap();
+            sistema.set_comision_categoria(Categoria::Tecnologia, 500).unwrap();
+            assert_eq!(sistema.set_comision_categoria(Categoria::Ropa, 10001).unwrap_err(), ErrorSistema::ComisionInvalida);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Mouse".to_string(), "Mouse".to_string(), Categoria::Tecnologia, None).unwrap();
+            sistema.nuevo_producto("Remera".to_string(), "Remera".to_string(), Categoria::Ropa, None).unwrap();
+            sistema.crear_publicacion(0, 100, 50).unwrap();
+            sistema.crear_publicacion(1, 100, 50).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            //Orden 0: 3 mouses y 1 remera -> domina Tecnología.
+            sistema.generar_orden_compra(vec![(0, 3), (1, 1)], 10000, Some(String::from("Calle 1 123"))).unwrap();
+            //Orden 1: 1 mouse y 3 remeras -> domina Ropa, que usa la comisión global.
+            sistema.generar_orden_compra(vec![(0, 1), (1, 3)], 10000, Some(String::from("Calle 1 123"))).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.marcar_orden_como_enviada(0).unwrap();
+            sistema.marcar_orden_como_enviada(1).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.marcar_orden_como_recibida(0).unwrap();
+            sistema.marcar_orden_como_recibida(1).unwrap();
+
+            //400 * 5% = 20 ; 400 * 1% = 4
+            assert_eq!(sistema.ordenes.get(0).unwrap().comision, 20);
+            assert_eq!(sistema.ordenes.get(1).unwrap().comision, 4);
+            assert_eq!(sistema.comisiones_acumuladas, 24);
+
+            //Sólo el owner puede configurarla.
+            assert_eq!(sistema.set_comision_categoria(Categoria::Ropa, 100).unwrap_err(), ErrorSistema::AccesoDenegado);
         }
 
 
+
         //-------------------------------------------------------------------------------------
-        //TESTS PUBLICACIONES Y STOCK:
+        //TESTS ESTADO DE ORDEN
 
         #[ink::test]
-        //Test para verificar que se puede crear una publicación correctamente.
-        fn test_crear_publicacion() {
+        //Test que verifica que get_estado_orden devuelva cada uno de los estados posibles.
+        fn test_get_estado_orden() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
             let mut sistema = Sistema::new();
-            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 100, 10).unwrap();
 
-            if let Ok(id) = sistema.nuevo_producto("banana".to_string(), "una banana".to_string(), Categoria::Limpieza){
-                assert_eq!(id, 0);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            for _ in 0..5 {
+                sistema.generar_orden_compra(vec![(0, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap();
             }
+            //La orden 0 queda pendiente y la 3 se cancela.
+            sistema.cancelar_como_comprador(3).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.marcar_orden_como_enviada(1).unwrap();
+            sistema.marcar_orden_como_enviada(2).unwrap();
+            sistema.marcar_orden_como_enviada(4).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.marcar_orden_como_recibida(2).unwrap();
+            sistema.abrir_disputa(4).unwrap();
 
+            assert_eq!(sistema.get_estado_orden(0).unwrap(), EstadoOrdenCompra::Pendiente);
+            assert_eq!(sistema.get_estado_orden(1).unwrap(), EstadoOrdenCompra::Enviado);
+            assert_eq!(sistema.get_estado_orden(2).unwrap(), EstadoOrdenCompra::Recibido);
+            assert_eq!(sistema.get_estado_orden(3).unwrap(), EstadoOrdenCompra::Cancelado);
+            assert_eq!(sistema.get_estado_orden(4).unwrap(), EstadoOrdenCompra::EnDisputa);
 
-            //Chequeo el estado posterior del sistema (que se haya creado la publicación).
-            sistema.crear_publicacion(0, 10, 19);
-            assert_eq!(sistema.get_publicaciones().len(), 1);
+            //El vendedor también puede consultarlo.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            assert_eq!(sistema.get_estado_orden(1).unwrap(), EstadoOrdenCompra::Enviado);
         }
 
         #[ink::test]
-        //Test para verificar que tiene_stock_suficiente funcione correctamente.
-        fn test_publicacion_tiene_stock_suficiente(){
-            let mut sistema = Sistema::new();
+        //Test que verifica que un tercero no pueda consultar el estado de una orden ajena.
+        fn test_get_estado_orden_no_autorizado() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
             let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor);
 
-            if let Ok(id) = sistema.nuevo_producto("banana".to_string(), "una banana".to_string(), Categoria::Limpieza){
-                assert_eq!(id, 0);
-            }
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            let mut sistema = Sistema::new();
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 100, 10).unwrap();
 
-            sistema.crear_publicacion(0, 10, 19);
-            assert_eq!(sistema.get_publicaciones().len(), 1);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            sistema.generar_orden_compra(vec![(0, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap();
 
-            assert_eq!(sistema.publicaciones[0].tiene_stock_suficiente(5), true);
-            assert_eq!(sistema.publicaciones[0].tiene_stock_suficiente(10), true);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            assert_eq!(sistema.get_estado_orden(0).unwrap_err(), ErrorSistema::UsuarioNoAutorizado);
+            assert_eq!(sistema.get_estado_orden(7).unwrap_err(), ErrorSistema::IdDeOrdenNoValida);
         }
 
         #[ink::test]
-        //Test que verifica que tiene_stock_suficiente funcione correctamente cuando el stock es insuficiente.
-        fn test_publicacion_tiene_stock_insuficiente() {
-            let mut sistema = Sistema::new();
+        //Test que verifica la consulta en lote con ids válidos, ajenos e inexistentes, y el tope de ids.
+        fn test_get_estados_ordenes() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
             let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            let mut sistema = Sistema::new();
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 100, 10).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            sistema.generar_orden_compra(vec![(0, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Comprador).unwrap();
+            sistema.generar_orden_compra(vec![(0, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap();
 
-            if let Ok(id) = sistema.nuevo_producto("banana".to_string(), "una banana".to_string(), Categoria::Limpieza){
-                assert_eq!(id, 0);
-            }
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.marcar_orden_como_enviada(0).unwrap();
 
-            sistema.crear_publicacion(0, 10, 19); //Le doy 19 de stock.
-            assert_eq!(sistema.get_publicaciones().len(), 1);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            assert_eq!(sistema.get_estados_ordenes(vec![0, 1, 9]), vec![
+                (0, Ok(EstadoOrdenCompra::Enviado)),
+                (1, Err(ErrorSistema::UsuarioNoAutorizado)),
+                (9, Err(ErrorSistema::IdDeOrdenNoValida)),
+            ]);
 
-            assert_eq!(sistema.publicaciones[0].tiene_stock_suficiente(20), false);
+            //Se resuelven como máximo MAX_IDS_POR_CONSULTA ids.
+            let ids: Vec<u128> = (0..(MAX_IDS_POR_CONSULTA as u128 + 10)).collect();
+            assert_eq!(sistema.get_estados_ordenes(ids).len(), MAX_IDS_POR_CONSULTA);
         }
 
 
         #[ink::test]
-        //Test que verifica que no se puede crear una publicación con stock 0.
-        fn test_crear_publicacion_stock_cero() {
+        //Test que verifica que no se pueda comprar una publicación desactivada aunque tenga stock.
+        fn test_compra_publicacion_inactiva() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
             let mut sistema = Sistema::new();
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 100, 10).unwrap();
+            sistema.publicaciones[0].activa = false;
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            assert_eq!(sistema.generar_orden_compra(vec![(0, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap_err(), ErrorSistema::PublicacionInactiva);
+            assert_eq!(sistema.publicaciones[0].stock, 10);
+            assert_eq!(ErrorSistema::PublicacionInactiva.codigo(), 50);
+        }
+
+
+
+        //-------------------------------------------------------------------------------------
+        //TESTS RESERVAS DE STOCK
+
+        #[ink::test]
+        //Test que verifica que una reserva descuente el stock y que otro comprador no pueda tomar esas unidades.
+        fn test_reservar_stock() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
             let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor);
 
-            if let Ok(id) = sistema.nuevo_producto("banana".to_string(), "una banana".to_string(), Categoria::Limpieza){
-                assert_eq!(id, 0);
-            }
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            let mut sistema = Sistema::new();
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 100, 3).unwrap();
+            assert_eq!(sistema.reservar_stock(0, 1).unwrap_err(), ErrorSistema::UsuarioNoEsComprador);
 
-            //Intento crear una publicación con stock 0.
-            let error_stock_cero = sistema.crear_publicacion(0, 10, 0).unwrap_err(); 
-            assert_eq!(error_stock_cero, ErrorSistema::StockInsuficiente); //No se puede crear una publicación con stock 0.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            assert_eq!(sistema.reservar_stock(0, 0).unwrap_err(), ErrorSistema::NoPuedeComprarCero);
+            assert_eq!(sistema.reservar_stock(5, 1).unwrap_err(), ErrorSistema::PublicacionNoValida);
+            sistema.reservar_stock(0, 2).unwrap();
+            assert_eq!(sistema.publicaciones[0].stock, 1);
+            sistema.reservar_stock(0, 1).unwrap();
+            assert_eq!(sistema.publicaciones[0].stock, 0);
+            assert_eq!(sistema.reservas.get(0).unwrap(), vec![(bob, 3, PLAZO_RESERVA)]);
 
-            //Chequeo el estado posterior del sistema (que no se haya creado la publicación).
-            assert_eq!(sistema.get_publicaciones().len(), 0);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Comprador).unwrap();
+            assert_eq!(sistema.reservar_stock(0, 1).unwrap_err(), ErrorSistema::SinStock);
+            assert_eq!(sistema.generar_orden_compra(vec![(0, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap_err(), ErrorSistema::SinStock);
         }
 
         #[ink::test]
-        //Test para verificar que no se puede comprar de una publicación con dinero insuficiente.
-        fn test_comprar_publicacion_dinero_insuficiente() {
+        //Test que verifica que la compra consuma la reserva del comprador y devuelva al stock lo que no compró.
+        fn test_compra_consume_reserva() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
             let mut sistema = Sistema::new();
-            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 100, 5).unwrap();
 
-            if let Ok(id) = sistema.nuevo_producto("banana".to_string(), "una banana".to_string(), Categoria::Limpieza){
-                assert_eq!(id, 0);
-            }
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            sistema.reservar_stock(0, 5).unwrap();
+            assert_eq!(sistema.publicaciones[0].stock, 0);
 
-            sistema.crear_publicacion(0, 10, 19); //Le doy 19 de stock. Cada banana sale 10 pesos.
-            assert_eq!(sistema.get_publicaciones().len(), 1);
+            //Si la compra falla, la reserva se conserva.
+            assert_eq!(sistema.generar_orden_compra(vec![(0, 5)], 10, Some(String::from("Calle 1 123"))).unwrap_err(), ErrorSistema::FondosInsuficientes { requerido: 500, disponible: 10 });
+            assert_eq!(sistema.publicaciones[0].stock, 0);
+            assert_eq!(sistema.reservas.get(0).unwrap(), vec![(bob, 5, PLAZO_RESERVA)]);
 
-            //Creo una orden de compra para que exista una orden con id 0.
+            //También se conserva si falla después de descontar el stock, al no poder asignarle un id a la orden.
+            sistema.proximo_id_orden = u128::MAX;
+            assert_eq!(sistema.generar_orden_compra(vec![(0, 3)], 1000, Some(String::from("Calle 1 123"))).unwrap_err(), ErrorSistema::OrdenesLleno);
+            assert_eq!(sistema.publicaciones[0].stock, 0);
+            assert_eq!(sistema.reservas.get(0).unwrap(), vec![(bob, 5, PLAZO_RESERVA)]);
+            sistema.proximo_id_orden = 0;
+
+            //Bob compra 3 de las 5 unidades reservadas; las otras 2 vuelven al stock.
+            sistema.generar_orden_compra(vec![(0, 3)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+            assert_eq!(sistema.publicaciones[0].stock, 2);
+            assert!(sistema.reservas.get(0).unwrap().is_empty());
+        }
+
+        #[ink::test]
+        //Test que verifica que una reserva vencida devuelva su stock y otro comprador pueda usarlo.
+        fn test_reserva_vencida_se_libera() {
             let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
-            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Ambos);
+            let mut sistema = Sistema::new();
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 100, 2).unwrap();
 
-            let lista_compra = vec![(0, 1)];
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            sistema.reservar_stock(0, 2).unwrap();
 
-            //Intento comprar una publicación con dinero insuficiente.
-            let error_dinero_insuficiente = sistema.generar_orden_compra(lista_compra, 0).unwrap_err(); //Trato de comprar una banana con 0 dinero.
-            assert_eq!(error_dinero_insuficiente, ErrorSistema::DineroInsuficiente); //No se puede comprar la publicación porque el dinero es insuficiente.
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(PLAZO_RESERVA);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Comprador).unwrap();
+            //Charlie puede reservar porque la reserva de Bob venció.
+            sistema.reservar_stock(0, 1).unwrap();
+            assert_eq!(sistema.publicaciones[0].stock, 1);
+            assert_eq!(sistema.reservas.get(0).unwrap(), vec![(charlie, 1, 2 * PLAZO_RESERVA)]);
 
-            //Chequeo el estado posterior del sistema (que no se haya modificado el stock).
-            assert_eq!(sistema.publicaciones[0].stock, 19);
+            //Bob ya no tiene reserva, pero puede comprar la unidad que quedó libre.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.generar_orden_compra(vec![(0, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+            assert_eq!(sistema.publicaciones[0].stock, 0);
+            assert_eq!(sistema.reservas.get(0).unwrap(), vec![(charlie, 1, 2 * PLAZO_RESERVA)]);
         }
 
         #[ink::test]
-        //Test para verificar que un usuario que no es vendedor o ambos pueda crear una publicación.
-        fn test_crear_publicacion_user_no_vendedor() {
-            let mut sistema = Sistema::new();
+        //Test que verifica que simular_orden cuente las reservas como la compra real.
+        fn test_simular_orden_con_reservas() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
             let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
-            
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Comprador);
 
-            let error_user_no_vendedor = sistema.crear_publicacion(0, 1000, 4).unwrap_err();
-            assert_eq!(error_user_no_vendedor, ErrorSistema::UsuarioNoEsVendedor); //Ok.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            let mut sistema = Sistema::new();
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 100, 3).unwrap();
 
-            //Chequeo el estado posterior del sistema (no debe existir ninguna publicación).
-            assert_eq!(sistema.get_publicaciones().len(), 0);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            sistema.reservar_stock(0, 3).unwrap();
+
+            //Bob reservó todo: la simulación cuenta sus unidades reservadas y no las de otros.
+            assert_eq!(sistema.simular_orden(vec![(0, 3)], 1000).unwrap(), 300);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Comprador).unwrap();
+            assert_eq!(sistema.simular_orden(vec![(0, 1)], 1000).unwrap_err(), ErrorSistema::SinStock);
+
+            //Cuando la reserva de Bob vence, Charlie ya puede simular la compra.
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(PLAZO_RESERVA);
+            assert_eq!(sistema.simular_orden(vec![(0, 4)], 1000).unwrap_err(), ErrorSistema::StockInsuficienteDetalle { id_publicacion: 0, solicitado: 4, disponible: 3 });
+            assert_eq!(sistema.simular_orden(vec![(0, 2)], 1000).unwrap(), 200);
         }
 
         #[ink::test]
-        //Test para verificar que no se puede crear una publicación de un producto inválido.
-        fn test_crear_publicacion_producto_invalido() {
+        //Test que verifica que el vendedor no pueda pisar el stock mientras haya reservas vigentes.
+        fn test_modificar_stock_con_reservas() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
             let mut sistema = Sistema::new();
-            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
-            
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 100, 5).unwrap();
 
-            let error_producto_invalido = sistema.crear_publicacion(0, 1000, 4).unwrap_err();
-            assert_eq!(error_producto_invalido, ErrorSistema::ProductoInvalido); //No existe el producto con id 0.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            sistema.reservar_stock(0, 2).unwrap();
 
-            //Chequeo el estado posterior del sistema (no debe existir ninguna publicación).
-             assert_eq!(sistema.get_publicaciones().len(), 0);
+            //Con la reserva vigente no se puede pisar el stock, pero sí cambiar el precio o reabastecer.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            assert_eq!(sistema.modificar_publicacion(0, None, Some(10), None).unwrap_err(), ErrorSistema::OperacionNoValida);
+            sistema.modificar_publicacion(0, Some(120), None, None).unwrap();
+            sistema.reabastecer(0, 1).unwrap();
+            assert_eq!(sistema.publicaciones[0].stock, 4);
+
+            //Vencida la reserva, el stock nuevo es el total y la reserva no se suma después.
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(PLAZO_RESERVA);
+            sistema.modificar_publicacion(0, None, Some(10), None).unwrap();
+            assert!(sistema.reservas.get(0).is_none());
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.generar_orden_compra(vec![(0, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+            assert_eq!(sistema.publicaciones[0].stock, 9);
         }
 
+
+
+        //-------------------------------------------------------------------------------------
+        //TESTS ARITMETICA SEGURA
+
         #[ink::test]
-        //Test que verifica que get publicaciones_propias funcione (con un caso en el que sí funciona).
-        fn test_get_publicaciones_propias_okay() {
-            let mut sistema = Sistema::new();
-            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor);
+        //Test que verifica el límite de desborde de cada helper aritmético.
+        fn test_helpers_aritmetica_segura() {
+            assert_eq!(suma_segura_u128(u128::MAX - 1, 1), Ok(u128::MAX));
+            assert_eq!(suma_segura_u128(u128::MAX, 1), Err(ErrorSistema::Overflow));
 
-            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros);
-            sistema.crear_publicacion(0, 1000, 4); //La publicación la crea Charlie.
+            assert_eq!(suma_segura_u32(u32::MAX - 1, 1), Ok(u32::MAX));
+            assert_eq!(suma_segura_u32(u32::MAX, 1), Err(ErrorSistema::Overflow));
 
-            //Verifico que el usuario pueda obtener sus publicaciones.
-            assert!(sistema.get_publicaciones_propias().is_ok());
+            assert_eq!(mul_segura_u32(u32::MAX / 2, 2), Ok(u32::MAX - 1));
+            assert_eq!(mul_segura_u32(u32::MAX / 2 + 1, 2), Err(ErrorSistema::Overflow));
+            assert_eq!(mul_segura_u32(u32::MAX, 0), Ok(0));
 
-            //Chequeo el estado posterior del sistema.
-            assert_eq!(sistema.get_publicaciones().len(), 1);
+            assert_eq!(ErrorSistema::Overflow.codigo(), 51);
         }
 
+
+
+        //-------------------------------------------------------------------------------------
+        //TESTS COMPRAS DE PRODUCTO
+
         #[ink::test]
-        //Test que verifica que get publicaciones_propias funcione (con un caso en el que el usuario es existe, es vendedor y no tiene publicaciones).
-        fn test_get_publicaciones_propias_vacio_okay() {
-            let mut sistema = Sistema::new();
+        //Test que verifica que el vendedor vea cuánto compró cada comprador de un producto en órdenes recibidas.
+        fn test_ver_compras_de_producto() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
             let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let django = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().django;
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            let mut sistema = Sistema::new();
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.nuevo_producto("Remera".to_string(), "Remera".to_string(), Categoria::Ropa, None).unwrap();
+            sistema.crear_publicacion(0, 100, 50).unwrap();
+            sistema.crear_publicacion(1, 100, 50).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            sistema.generar_orden_compra(vec![(0, 2)], 10000, Some(String::from("Calle 1 123"))).unwrap();
+            sistema.generar_orden_compra(vec![(0, 3), (1, 1)], 10000, Some(String::from("Calle 1 123"))).unwrap();
+
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Comprador).unwrap();
+            sistema.generar_orden_compra(vec![(0, 4)], 10000, Some(String::from("Calle 1 123"))).unwrap();
+            //Esta orden queda pendiente y no se cuenta.
+            sistema.generar_orden_compra(vec![(0, 7)], 10000, Some(String::from("Calle 1 123"))).unwrap();
 
-            //Verifico que el usuario pueda obtener sus publicaciones (debe devolver un vector vacío).
-            let publicaciones_propias = sistema.get_publicaciones_propias().unwrap();
-            assert!(publicaciones_propias.is_empty());
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            for id in 0..3 {
+                sistema.marcar_orden_como_enviada(id).unwrap();
+            }
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.marcar_orden_como_recibida(0).unwrap();
+            sistema.marcar_orden_como_recibida(1).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.marcar_orden_como_recibida(2).unwrap();
 
-            //Chequeo el estado posterior del sistema.
-            assert_eq!(sistema.get_publicaciones().len(), 0);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            assert_eq!(sistema.ver_compras_de_producto(0).unwrap(), vec![(bob, 5), (charlie, 4)]);
+            assert_eq!(sistema.ver_compras_de_producto(1).unwrap(), vec![(bob, 1)]);
+            assert_eq!(sistema.ver_compras_de_producto(9).unwrap_err(), ErrorSistema::ProductoInvalido);
+
+            //Un usuario que no es dueño del producto no puede consultarlo.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(django);
+            sistema.registrar_usuario(String::from("Django"), String::from("Surname"), String::from("django.email"), Rol::Vendedor).unwrap();
+            assert_eq!(sistema.ver_compras_de_producto(0).unwrap_err(), ErrorSistema::OperacionNoValida);
         }
 
+
+
+        //-------------------------------------------------------------------------------------
+        //TESTS STOREFRONT
+
         #[ink::test]
-        //Test que verifica que get publicaciones_propias 'no funcione' (con un caso en el que el usuario no existe en el sistema).
-        fn test_get_publicaciones_propias_usuario_no_existe() {
+        //Test que verifica el resumen de la tienda de un vendedor con publicaciones y ventas calificadas.
+        fn test_storefront() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            let django = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().django;
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
             let mut sistema = Sistema::new();
-            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            //No registro a charlie en el sistema.
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 100, 2).unwrap();
+            sistema.crear_publicacion(0, 100, 10).unwrap();
+            sistema.crear_publicacion(0, 100, 10).unwrap();
+            assert_eq!(sistema.storefront(alice).unwrap(), Storefront { nombre: String::from("Alice"), prom_calificacion: None, n_publicaciones_activas: 3, n_ventas: 0 });
+
+            //Bob agota la primera publicación y hace otra compra.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            sistema.generar_orden_compra(vec![(0, 2)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+            sistema.generar_orden_compra(vec![(1, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+            //Esta orden queda pendiente y no cuenta como venta.
+            sistema.generar_orden_compra(vec![(2, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap();
 
-            let error_usuario_no_existe = sistema.get_publicaciones_propias().unwrap_err();
-            assert_eq!(error_usuario_no_existe, ErrorSistema::UsuarioNoExiste); //El usuario no existe.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.marcar_orden_como_enviada(0).unwrap();
+            sistema.marcar_orden_como_enviada(1).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.marcar_orden_como_recibida(0).unwrap();
+            sistema.marcar_orden_como_recibida(1).unwrap();
+            sistema.puntuar_usuario_por_orden(0, 4).unwrap();
+            sistema.puntuar_usuario_por_orden(1, 3).unwrap();
+
+            //(4 + 3) / 2 = 3,5 -> 4
+            assert_eq!(sistema.storefront(alice).unwrap(), Storefront { nombre: String::from("Alice"), prom_calificacion: Some(4), n_publicaciones_activas: 2, n_ventas: 2 });
+            assert_eq!(sistema.storefront(bob).unwrap_err(), ErrorSistema::UsuarioNoEsVendedor);
+            assert_eq!(sistema.storefront(django).unwrap_err(), ErrorSistema::UsuarioNoExiste);
         }
 
+
+
+        //-------------------------------------------------------------------------------------
+        //TESTS BLOQUEO DE COMPRADORES
+
         #[ink::test]
-        //Test que verifica que get publicaciones_propias 'no funcione' (con un caso en el que el usuario no es vendedor).
-        fn test_get_publicaciones_propias_usuario_no_vendedor() {
-            let mut sistema = Sistema::new();
+        //Test que verifica que un comprador bloqueado no pueda comprar y que al desbloquearlo vuelva a poder.
+        fn test_bloquear_y_desbloquear_comprador() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
             let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            let mut sistema = Sistema::new();
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 100, 10).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            //Un comprador no puede bloquear.
+            assert_eq!(sistema.bloquear_comprador(alice).unwrap_err(), ErrorSistema::UsuarioNoEsVendedor);
+
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Comprador);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Comprador).unwrap();
 
-            let error_usuario_no_es_vendedor = sistema.get_publicaciones_propias().unwrap_err();
-            assert_eq!(error_usuario_no_es_vendedor, ErrorSistema::UsuarioNoEsVendedor); //El usuario no es vendedor.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            assert_eq!(sistema.bloquear_comprador(alice).unwrap_err(), ErrorSistema::OperacionNoValida);
+            sistema.bloquear_comprador(bob).unwrap();
+            sistema.bloquear_comprador(bob).unwrap();
+            assert_eq!(sistema.bloqueados.get(alice).unwrap(), vec![bob]);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            assert_eq!(sistema.generar_orden_compra(vec![(0, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap_err(), ErrorSistema::CompradorBloqueadoPorVendedor);
+            assert_eq!(sistema.reservar_stock(0, 1).unwrap_err(), ErrorSistema::CompradorBloqueadoPorVendedor);
+            assert_eq!(sistema.publicaciones[0].stock, 10);
+
+            //Charlie no está bloqueado y puede comprar.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.generar_orden_compra(vec![(0, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.desbloquear_comprador(bob).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.generar_orden_compra(vec![(0, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+            assert_eq!(sistema.publicaciones[0].stock, 8);
         }
 
 
+
         //-------------------------------------------------------------------------------------
-        //TESTS PRECIO Y CHECKED SUMS:
+        //TESTS MONTO MINIMO POR VENDEDOR
 
         #[ink::test]
-        fn test_calculo_precio(){
-            let mut sistema = Sistema::new();
-            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor);
-            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza);
-            sistema.nuevo_producto("Remera".to_string(), "Remera".to_string(), Categoria::Ropa);
-            sistema.crear_publicacion(0, 10, 19);
-            sistema.crear_publicacion(1, 20, 5);
+        //Test que verifica que se rechacen las órdenes por debajo del mínimo del vendedor y se acepten las que lo alcanzan.
+        fn test_monto_minimo_vendedor() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
 
-            sistema.nuevo_producto("Precioalto".to_string(), "Precioalto".to_string(), Categoria::Ropa);
-            let precio_alto = u32::MAX;
-            sistema.crear_publicacion(2, precio_alto, 5);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            let mut sistema = Sistema::new();
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 100, 10).unwrap();
+            sistema.set_monto_minimo(Some(300)).unwrap();
 
-            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            assert_eq!(sistema.set_monto_minimo(Some(10)).unwrap_err(), ErrorSistema::UsuarioNoEsVendedor);
+            assert_eq!(sistema.generar_orden_compra(vec![(0, 2)], 1000, Some(String::from("Calle 1 123"))).unwrap_err(), ErrorSistema::MontoMinimoNoAlcanzado);
+            //Justo el mínimo y por encima.
+            sistema.generar_orden_compra(vec![(0, 3)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+            sistema.generar_orden_compra(vec![(0, 4)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+
+            //Sin mínimo se acepta cualquier monto.
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
-            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Ambos);
+            sistema.set_monto_minimo(None).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.generar_orden_compra(vec![(0, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+            assert_eq!(sistema.publicaciones[0].stock, 2);
+        }
 
-            let mut lista_compra = Vec::new();
-            lista_compra.push((0,2));
-            lista_compra.push((1,3));
 
 
-            if let Err(e) = sistema.generar_orden_compra(lista_compra.clone(), 70){
-                assert_eq!(e, ErrorSistema::DineroInsuficiente);
-            }
+        //-------------------------------------------------------------------------------------
+        //TESTS REABASTECER
+
+        #[ink::test]
+        //Test que verifica que reabastecer sume stock, reactive una publicación agotada y controle el desborde.
+        fn test_reabastecer() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            let mut sistema = Sistema::new();
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 100, 2).unwrap();
+            sistema.reabastecer(0, 3).unwrap();
+            assert_eq!(sistema.publicaciones[0].stock, 5);
 
-            if let Ok(ord) = sistema.generar_orden_compra(lista_compra.clone(), 200){
-                assert_eq!(ord.monto, 80);
-            }
+            //Bob agota la publicación, que queda desactivada.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            sistema.generar_orden_compra(vec![(0, 5)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+            assert!(!sistema.publicaciones[0].activa);
+            //Sólo el dueño puede reabastecer.
+            assert_eq!(sistema.reabastecer(0, 1).unwrap_err(), ErrorSistema::UsuarioNoAutorizado);
 
-            if let Err(e) = sistema.generar_orden_compra(vec![(1,1), (2,1)], 200) {
-                assert_eq!(e, ErrorSistema::FueraDeRango);
-            }
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            assert_eq!(sistema.reabastecer(7, 1).unwrap_err(), ErrorSistema::PublicacionNoValida);
+            sistema.reabastecer(0, 4).unwrap();
+            assert_eq!(sistema.publicaciones[0].stock, 4);
+            assert!(sistema.publicaciones[0].activa);
 
-            if let Err(e) = sistema.generar_orden_compra(vec![(2,3)], 200) {
-                assert_eq!(e, ErrorSistema::FueraDeRango);
-            }
+            //Si la suma se pasa de rango no se modifica el stock.
+            assert_eq!(sistema.reabastecer(0, u32::MAX).unwrap_err(), ErrorSistema::StockOverflow);
+            assert_eq!(sistema.publicaciones[0].stock, 4);
+        }
 
-            lista_compra.push((999,1));
 
-            if let Err(e) = sistema.validar_precio(lista_compra.clone(), 200){
-                assert_eq!(e, ErrorSistema::PublicacionNoValida);
-            }
 
-        }
+        //-------------------------------------------------------------------------------------
+        //TESTS RECEPCION PARCIAL
 
         #[ink::test]
-        fn test_checked_sums(){
+        //Test que verifica que la orden sólo pase a Recibido y libere el escrow cuando el comprador confirma todas las líneas.
+        fn test_marcar_item_recibido() {
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
             let mut sistema = Sistema::new();
-            sistema.proximo_id_producto = u128::MAX;
-            let result = sistema.generar_id_producto();
-            assert_eq!(result.unwrap_err(), ErrorSistema::ProductosLleno);
 
-            sistema.proximo_id_publicacion = u128::MAX;
-            if let Err(e) = sistema.generar_id_publicacion() {
-                assert_eq!(e, ErrorSistema::PublicacionesLleno);
-            }
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None).unwrap();
+            sistema.crear_publicacion(0, 10, 5).unwrap();
+            sistema.crear_publicacion(1, 20, 5).unwrap();
 
-            sistema.proximo_id_orden = u128::MAX;
-            if let Err(e) = sistema.generar_id_orden() {
-                assert_eq!(e, ErrorSistema::PublicacionesLleno);
-            }
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            sistema.generar_orden_compra(vec![(0, 1), (1, 2)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+            //No se puede confirmar una orden que todavía no fue enviada.
+            assert_eq!(sistema.marcar_item_recibido(0, 0).unwrap_err(), ErrorSistema::OperacionNoValida);
 
-            let mut sistema = Sistema::new();
-            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
-            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza);
+            sistema.marcar_orden_como_enviada(0).unwrap();
+            //Sólo el comprador puede confirmar.
+            assert_eq!(sistema.marcar_item_recibido(0, 0).unwrap_err(), ErrorSistema::UsuarioNoAutorizado);
 
-            sistema.crear_publicacion(0, 10, 19);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            assert_eq!(sistema.marcar_item_recibido(0, 9).unwrap_err(), ErrorSistema::ProductoInvalido);
 
-            if let Some(p) = sistema.publicaciones.get_mut(0) {
-                assert_eq!(p.actualizar_stock(u32::MAX), Err(ErrorSistema::PublicacionesLleno))
-            }
+            //Recepción parcial: la orden sigue enviada y el monto completo sigue en el escrow.
+            sistema.marcar_item_recibido(0, 1).unwrap();
+            let orden = sistema.ordenes.get(0).unwrap();
+            assert_eq!(orden.recepciones, vec![(0, false), (1, true)]);
+            assert_eq!(orden.estado, EstadoOrdenCompra::Enviado);
+            assert_eq!(sistema.escrow_actual(), 50);
+            assert_eq!(sistema.marcar_item_recibido(0, 1).unwrap_err(), ErrorSistema::OperacionNoValida);
 
+            //Al confirmar la última línea la orden se completa y se libera el escrow.
+            sistema.marcar_item_recibido(0, 0).unwrap();
+            let orden = sistema.ordenes.get(0).unwrap();
+            assert_eq!(orden.recepciones, vec![(0, true), (1, true)]);
+            assert_eq!(orden.estado, EstadoOrdenCompra::Recibido);
+            assert_eq!(sistema.escrow_actual(), 0);
+            assert_eq!(sistema.marcar_item_recibido(0, 0).unwrap_err(), ErrorSistema::OperacionNoValida);
         }
 
+
+
         //-------------------------------------------------------------------------------------
-        //TEST ID USUARIOS
+        //TESTS ELIMINAR PRODUCTO
 
         #[ink::test]
-        //Test que verifica que los IDs de los usuarios se agregan correctamente al vector de IDs.
-        fn test_agregar_id_okay() {
-            let mut sistema = Sistema::new();
+        //Test que verifica que no se pueda eliminar un producto publicado y sí uno sin referencias.
+        fn test_eliminar_producto() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
 
-            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            let mut sistema = Sistema::new();
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.nuevo_producto("Termo".to_string(), "Termo".to_string(), Categoria::Otros, None).unwrap();
+            sistema.crear_publicacion(0, 100, 10).unwrap();
 
-            assert!(sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos).is_ok());
-            assert_eq!(sistema.id_usuarios.len(), 1); //Debe tener un ID.
-            assert_eq!(sistema.id_usuarios[0], charlie); //El ID debe ser el de Charlie.
-        }
+            //Sólo el vendedor que creó el producto puede eliminarlo.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Vendedor).unwrap();
+            assert_eq!(sistema.eliminar_producto(1).unwrap_err(), ErrorSistema::UsuarioNoTieneProducto);
 
-        #[ink::test]
-        //Test que verifica que el vector de ids de usuarios está vecío cuando no hay nada.
-        fn test_ids_vacio_okay() {
-            let sistema = Sistema::new();
-            assert!(sistema.id_usuarios.is_empty()); //El vector de IDs debe estar vacío.
-        }
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            assert_eq!(sistema.eliminar_producto(9).unwrap_err(), ErrorSistema::ProductoInvalido);
+            assert_eq!(sistema.eliminar_producto(0).unwrap_err(), ErrorSistema::ProductoEnUso);
+            assert!(sistema.productos.get(0).is_some());
 
+            //El producto sin publicaciones se puede eliminar.
+            sistema.eliminar_producto(1).unwrap();
+            assert!(sistema.productos.get(1).is_none());
+            assert_eq!(sistema.usuarios.get(alice).unwrap().productos, vec![0]);
 
+            //Al eliminar la publicación el primer producto también queda libre.
+            sistema.eliminar_publicacion(0).unwrap();
+            sistema.eliminar_producto(0).unwrap();
+            assert!(sistema.usuarios.get(alice).unwrap().productos.is_empty());
+        }
 
-        //TESTS PUNTUACIÓN A USUARIOS
         #[ink::test]
-        //Test que verifica que la funcionalidad top_5_vendedores funcione correctamente. (Caso en el que hay más de 5 vendedores).
-        fn test_top_5_vendedores_okay() {
-            // Alice como owner de los contratos
+        //Test que verifica que una orden recibida siga reteniendo el producto hasta que la puntúen ambas partes.
+        fn test_eliminar_producto_con_orden_sin_puntuar() {
             let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
 
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
             let mut sistema = Sistema::new();
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 100, 10).unwrap();
 
-            // Pongo a la misma Alice simulando ser ReportesView para que pueda
-            // enviar los mensajes relativos a este
-            sistema.set_reportes_view(alice);
-
-            //Preparo a los vendedores.
-            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
-            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
-            let django = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().django;
-            let eve = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().eve;
-            let frank = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().frank;
-
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            sistema.generar_orden_compra(vec![(0, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.marcar_orden_como_enviada(0).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.marcar_orden_como_recibida(0).unwrap();
 
+            //La orden ya está recibida pero nadie la puntuó, así que el producto sigue en uso.
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
-            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor);
+            sistema.eliminar_publicacion(0).unwrap();
+            assert_eq!(sistema.eliminar_producto(0).unwrap_err(), ErrorSistema::ProductoEnUso);
 
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
-            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Vendedor);
+            sistema.puntuar_usuario_por_orden(0, 5).unwrap();
+            assert_eq!(sistema.productos.get(0).unwrap().puntuaciones, vec![5]);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            assert_eq!(sistema.eliminar_producto(0).unwrap_err(), ErrorSistema::ProductoEnUso);
 
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(django);
-            sistema.registrar_usuario(String::from("Django"), String::from("Surname"), String::from("django.email"), Rol::Ambos);
+            //Con la orden puntuada por ambas partes el producto queda libre.
+            sistema.puntuar_usuario_por_orden(0, 4).unwrap();
+            sistema.eliminar_producto(0).unwrap();
+            assert!(sistema.productos.get(0).is_none());
+        }
 
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(eve);
-            sistema.registrar_usuario(String::from("Eve"), String::from("Surname"), String::from("eve.email"), Rol::Ambos);
 
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(frank);
-            sistema.registrar_usuario(String::from("Frank"), String::from("Surname"), String::from("frank.email"), Rol::Ambos);
 
-            //Les doy las calificaciones.
+        //-------------------------------------------------------------------------------------
+        //TESTS VENTAS POR RANGO DE FECHAS
 
-            if let Some(mut user) = sistema.usuarios.get(&charlie) {
-                user.calificaciones_vendedor = vec![5, 5, 5];
-                sistema.usuarios.insert(&charlie, &user); 
-            }
+        #[ink::test]
+        //Test que verifica que ventas_entre devuelva sólo las ventas recibidas dentro del rango.
+        fn test_ventas_entre() {
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
 
-            if let Some(mut user) = sistema.usuarios.get(&alice) {
-                user.calificaciones_vendedor = vec![5, 5, 1]; //3.6
-                sistema.usuarios.insert(&alice, &user); 
-            }
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            let mut sistema = Sistema::new();
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 100, 10).unwrap();
 
-            if let Some(mut user) = sistema.usuarios.get(&bob) {
-                user.calificaciones_vendedor = vec![1, 1, 1]; 
-                sistema.usuarios.insert(&bob, &user); 
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            for _ in 0..4 {
+                sistema.generar_orden_compra(vec![(0, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap();
             }
-             
-            if let Some(mut user) = sistema.usuarios.get(&django) {
-                user.calificaciones_vendedor = vec![2, 3, 4]; //3
-                sistema.usuarios.insert(&django, &user); 
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            for id in 0..4 {
+                sistema.marcar_orden_como_enviada(id).unwrap();
             }
 
-            if let Some(mut user) = sistema.usuarios.get(&eve) {
-                user.calificaciones_vendedor = vec![5, 5, 5];
-                sistema.usuarios.insert(&eve, &user); 
+            //Las órdenes 0, 1 y 2 se reciben en 100, 200 y 300; la 3 queda enviada.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            for id in 0..3 {
+                ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(100 * (id as u64 + 1));
+                sistema.marcar_orden_como_recibida(id).unwrap();
             }
+            assert_eq!(sistema.ventas_entre(0, 1000).unwrap_err(), ErrorSistema::UsuarioNoEsVendedor);
+
+            sistema.puntuar_usuario_por_orden(0, 5).unwrap();
 
-            if let Some(mut user) = sistema.usuarios.get(&frank) {
-                user.calificaciones_vendedor = vec![0, 0, 0];
-                sistema.usuarios.insert(&frank, &user); 
-            }
-    
-            //Pido el top 5 de vendedores.
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
-            let top_5 = sistema.consultar_top_5_vendedores().unwrap();
-            assert_eq!(top_5.len(), 5); //Debe devolver 5 vendedores.
-                
+            //La orden 0 archivada sigue contando.
+            sistema.puntuar_usuario_por_orden(0, 5).unwrap();
+            sistema.archivar_orden(0).unwrap();
 
-            //Chequeo que estén ordenados correctamente (de mayor a menor puntuación) y que sean los correctos. 
-            assert_eq!(top_5[0].id, charlie);
-            assert_eq!(top_5[1].id, eve); 
-            assert_eq!(top_5[2].id, alice); 
-            assert_eq!(top_5[3].id, django); 
-            assert_eq!(top_5[4].id, bob);      
+            let ids = |ventas: Vec<OrdenCompra>| ventas.iter().map(|orden| orden.id_orden_compra).collect::<Vec<u128>>();
+            assert_eq!(ids(sistema.ventas_entre(0, 1000).unwrap()), vec![0, 1, 2]);
+            assert_eq!(ids(sistema.ventas_entre(100, 200).unwrap()), vec![0, 1]);
+            assert_eq!(ids(sistema.ventas_entre(201, 299).unwrap()), Vec::<u128>::new());
+            assert_eq!(ids(sistema.ventas_entre(300, 300).unwrap()), vec![2]);
+            assert_eq!(sistema.ventas_entre(300, 100).unwrap_err(), ErrorSistema::RangoInvalido);
         }
 
+
+
+        //-------------------------------------------------------------------------------------
+        //TESTS LIMITE DE PUBLICACIONES
+
         #[ink::test]
-        //Este test chequea que la funcionalidad top_5_vendedores funcione correctamente (Caso en el que no hay vendedores).
-        fn test_top_5_vendedores_nulo() {
-            // Alice como owner de los contratos
+        //Test que verifica que un vendedor pueda llegar al límite de publicaciones activas pero no pasarlo.
+        fn test_limite_de_publicaciones() {
             let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
 
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
             let mut sistema = Sistema::new();
-
-            // Acá voy a usar a Bob como ReportesVew para comprobar que no hace
-            // falta que sea el owner del MarketPlace
-            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
-            sistema.set_reportes_view(bob);
-            
+            assert_eq!(sistema.max_publicaciones_por_vendedor, u32::MAX);
+            sistema.set_max_publicaciones_por_vendedor(2).unwrap();
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 100, 1).unwrap();
+            sistema.crear_publicacion(0, 100, 10).unwrap();
+            assert_eq!(sistema.crear_publicacion(0, 100, 10).unwrap_err(), ErrorSistema::LimiteDePublicacionesAlcanzado);
+            assert_eq!(sistema.publicaciones.len(), 2);
+
+            //Sólo el owner puede cambiar el límite.
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
-            let top_5 = sistema.consultar_top_5_vendedores().unwrap();
-            assert!(top_5.is_empty()); //Debe devolver un vector vacío.
+            assert!(sistema.set_max_publicaciones_por_vendedor(10).is_err());
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            //Bob agota la primera publicación, que deja de contar como activa.
+            sistema.generar_orden_compra(vec![(0, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.crear_publicacion(0, 100, 10).unwrap();
+            assert_eq!(sistema.crear_publicacion(0, 100, 10).unwrap_err(), ErrorSistema::LimiteDePublicacionesAlcanzado);
         }
 
         #[ink::test]
-        //Este test chequea que la funcionalidad top_5_vendedores funcione correctamente (Caso en el que hay menos de 5 vendedores).
-        fn test_top_5_vendedores_menor_a_5() {
-            // Alice como owner de los contratos
+        //Test que verifica que reactivar una publicación agotada también respete el límite de publicaciones activas.
+        fn test_limite_de_publicaciones_al_reactivar() {
             let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
 
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
             let mut sistema = Sistema::new();
+            sistema.set_max_publicaciones_por_vendedor(1).unwrap();
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza, None).unwrap();
+            sistema.crear_publicacion(0, 100, 1).unwrap();
+
+            //Alice desactiva la publicación dejándola sin stock y crea otra.
+            sistema.modificar_publicacion(0, None, Some(0), None).unwrap();
+            sistema.crear_publicacion(0, 100, 10).unwrap();
+
+            //Ni modificar_publicacion ni reabastecer pueden volver a activarla.
+            assert_eq!(sistema.modificar_publicacion(0, None, Some(5), None).unwrap_err(), ErrorSistema::LimiteDePublicacionesAlcanzado);
+            assert_eq!(sistema.reabastecer(0, 5).unwrap_err(), ErrorSistema::LimiteDePublicacionesAlcanzado);
+            assert!(!sistema.publicaciones[0].activa);
+            assert_eq!(sistema.publicaciones[0].stock, 0);
+            //Sobre una publicación activa se puede seguir cambiando el stock.
+            sistema.reabastecer(1, 5).unwrap();
+            sistema.modificar_publicacion(1, None, Some(3), None).unwrap();
+
+            //Si se cancela una compra, el stock vuelve pero la publicación sigue inactiva.
+            sistema.modificar_publicacion(1, None, Some(0), None).unwrap();
+            sistema.reabastecer(0, 1).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            sistema.generar_orden_compra(vec![(0, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.crear_publicacion(0, 100, 10).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.cancelar_como_comprador(0).unwrap();
+            assert_eq!(sistema.publicaciones[0].stock, 1);
+            assert!(!sistema.publicaciones[0].activa);
+            assert_eq!(sistema.contar_publicaciones_activas(alice), 1);
+        }
 
-            // Pongo a la misma Alice simulando ser ReportesView para que pueda
-            // enviar los mensajes relativos a este
-            sistema.set_reportes_view(alice);
 
-            //Preparo a los vendedores.
-            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
-            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+
+        //-------------------------------------------------------------------------------------
+        //TESTS REGISTRO IDEMPOTENTE
+
+        #[ink::test]
+        //Test que verifica que registrar_o_obtener registre la primera vez y después devuelva el perfil existente.
+        fn test_registrar_o_obtener() {
             let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            let mut sistema = Sistema::new();
 
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
-            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Ambos);
+            let usuario = sistema.registrar_o_obtener(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            assert_eq!(usuario.nombre, "Bob");
+            assert_eq!(usuario.rol, Rol::Comprador);
+            assert_eq!(sistema.id_usuarios, vec![bob]);
 
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
-            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Vendedor);
+            //La segunda llamada ignora los datos nuevos y no vuelve a registrar.
+            let existente = sistema.registrar_o_obtener(String::from("Otro"), String::from("Nombre"), String::from("otro.email"), Rol::Vendedor).unwrap();
+            assert_eq!(existente.nombre, "Bob");
+            assert_eq!(existente.email, "bob.email");
+            assert_eq!(existente.rol, Rol::Comprador);
+            assert_eq!(sistema.id_usuarios, vec![bob]);
+        }
 
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
-            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Vendedor);
 
-            //Les doy las calificaciones.
 
-            if let Some(mut user) = sistema.usuarios.get(&charlie) {
-                user.calificaciones_vendedor = vec![5, 5, 5];
-                sistema.usuarios.insert(&charlie, &user); 
-            }
+        //-------------------------------------------------------------------------------------
+        //TESTS RANKING DE VENDEDORES
 
-            if let Some(mut user) = sistema.usuarios.get(&alice) {
-                user.calificaciones_vendedor = vec![5, 5, 1]; //3.6
-                sistema.usuarios.insert(&alice, &user); 
-            }
+        #[ink::test]
+        //Test que verifica el orden del ranking (calificación, ventas y orden de registro en empates) y sus ventanas con más de cinco vendedores.
+        fn test_ranking_vendedores() {
+            let mut sistema = Sistema::new();
+            let comprador = AccountId::from([0x60; 32]);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(comprador);
+            sistema.registrar_usuario(String::from("Comprador"), String::from("Surname"), String::from("comprador.email"), Rol::Comprador).unwrap();
+
+            //(calificación, ventas) de cada vendedor, en orden de registro.
+            let datos: [(u8, u32); 7] = [(3, 0), (5, 1), (5, 2), (0, 0), (4, 0), (5, 1), (4, 0)];
+            let mut vendedores = Vec::new();
+            for (i, (calificacion, ventas)) in datos.iter().enumerate() {
+                let vendedor = AccountId::from([0x61 + i as u8; 32]);
+                vendedores.push(vendedor);
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(vendedor);
+                sistema.registrar_usuario(String::from("Vendedor"), String::from("Surname"), String::from("vendedor.email"), Rol::Vendedor).unwrap();
+                let id_producto = sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None).unwrap();
+                sistema.crear_publicacion(id_producto, 10, 10).unwrap();
+                let id_publicacion = sistema.publicaciones.last().unwrap().id_publicacion;
+
+                for _ in 0..*ventas {
+                    ink::env::test::set_caller::<ink::env::DefaultEnvironment>(comprador);
+                    let orden = sistema.generar_orden_compra(vec![(id_publicacion, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+                    ink::env::test::set_caller::<ink::env::DefaultEnvironment>(vendedor);
+                    sistema.marcar_orden_como_enviada(orden.id_orden_compra).unwrap();
+                    ink::env::test::set_caller::<ink::env::DefaultEnvironment>(comprador);
+                    sistema.marcar_orden_como_recibida(orden.id_orden_compra).unwrap();
+                }
 
-            if let Some(mut user) = sistema.usuarios.get(&bob) {
-                user.calificaciones_vendedor = vec![1, 1, 1]; 
-                sistema.usuarios.insert(&bob, &user); 
+                if *calificacion > 0 {
+                    let mut user = sistema.usuarios.get(vendedor).unwrap();
+                    user.calificaciones_vendedor = vec![*calificacion];
+                    sistema.usuarios.insert(vendedor, &user);
+                }
             }
-    
-            //Pido el top 5 de vendedores.
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
-            let top_5 = sistema.consultar_top_5_vendedores().unwrap();
-            assert_eq!(top_5.len(), 3); //Debe devolver 3 vendedores (los únicos que hay).
-                
 
-            //Chequeo que estén ordenados correctamente (de mayor a menor puntuación) y que sean los correctos. 
-            assert_eq!(top_5[0].id, charlie);
+            let esperado = vec![
+                (vendedores[2], 5, 2),
+                (vendedores[1], 5, 1),
+                (vendedores[5], 5, 1),
+                (vendedores[4], 4, 0),
+                (vendedores[6], 4, 0),
+                (vendedores[0], 3, 0),
+                (vendedores[3], 0, 0),
+            ];
+            //El comprador no aparece y el ranking completo supera el top 5.
+            assert_eq!(sistema.ranking_vendedores(0, 10), esperado);
+            //Las ventanas siguen el mismo orden y el empate se mantiene estable entre consultas.
+            assert_eq!(sistema.ranking_vendedores(0, 3), esperado[0..3].to_vec());
+            assert_eq!(sistema.ranking_vendedores(3, 3), esperado[3..6].to_vec());
+            assert_eq!(sistema.ranking_vendedores(6, 3), esperado[6..].to_vec());
+            assert!(sistema.ranking_vendedores(7, 3).is_empty());
+            //El límite se recorta a MAX_TAMANO_PAGINA.
+            assert_eq!(sistema.ranking_vendedores(0, u32::MAX).len(), 7);
         }
 
-        //-------------------------------------------------------------------------------------
-        //TESTS VER PRODUCTOS MÁS VENDIDOS.
 
-         #[ink::test]
-        fn test_productos_mas_vendidos() {
-            // Alice como owner de los contratos
-            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
 
-            let mut sistema = Sistema::new();
+        //-------------------------------------------------------------------------------------
+        //TESTS ORDEN DE COMPRA FUSIONANDO LINEAS
 
-            // Pongo a la misma Alice simulando ser ReportesView para que pueda
-            // enviar los mensajes relativos a este
-            sistema.set_reportes_view(alice);
+        #[ink::test]
+        //Test que verifica que las líneas repetidas se fusionen en una sola y que la compra estricta las siga rechazando.
+        fn test_generar_orden_compra_fusionando() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
 
-            //Preparo a los vendedores.
-            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
-            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Vendedor);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None).unwrap();
+            sistema.crear_publicacion(0, 100, 4).unwrap();
 
-            //Creo productos y publicaciones.
-            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza); //ID 0
-            sistema.nuevo_producto("Remera".to_string(), "Remera".to_string(), Categoria::Ropa); //ID 1
-            sistema.nuevo_producto("Pantalon".to_string(), "Pantalon".to_string(), Categoria::Ropa); //ID 2
-            sistema.nuevo_producto("Arroz".to_string(), "Arroz".to_string(), Categoria::Otros); //ID 3
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Comprador).unwrap();
+            assert_eq!(sistema.generar_orden_compra(vec![(0, 1), (0, 2)], 1000, Some(String::from("Calle 1 123"))).unwrap_err(), ErrorSistema::PublicacionRepetida);
 
-            sistema.crear_publicacion(0, 100, 10); //Cif
-            sistema.crear_publicacion(1, 500, 20); //Remera
-            sistema.crear_publicacion(2, 200, 25); //Pantalon
-            sistema.crear_publicacion(3, 400, 30); //Arroz
+            let orden = sistema.generar_orden_compra_fusionando(vec![(0, 1), (0, 2)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+            assert_eq!(orden.lista_publicaciones, vec![(0, 3)]);
+            assert_eq!(orden.monto, 300);
+            assert_eq!(sistema.publicaciones[0].stock, 1);
+        }
 
-            //Creo órdenes de compra para que haya ventas.
-            let django = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().django;
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(django);
-            sistema.registrar_usuario(String::from("Django"), String::from("Surname"), String::from("django.email"), Rol::Ambos);
-            //Id publicación, cantidad.
-            sistema.generar_orden_compra(vec![(0, 2), (1, 1)], 10000); //Compra 2 Cif y 1 Remera 
-            sistema.generar_orden_compra(vec![(1, 3), (2, 1)], 10000); //Compra 2 Remera y 1 Pantalon 
-            sistema.generar_orden_compra(vec![(3, 3), (0, 1), (2, 2)], 10000); //Compra 3 Arroz, 1 Cif y 2 Pantalon 
+        #[ink::test]
+        //Test que verifica que la línea fusionada respete el stock y el máximo por línea.
+        fn test_generar_orden_compra_fusionando_limites() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
 
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice); // Alice es ReportesView
-            let productos_mas_vendidos = sistema.ver_productos_mas_vendidos(Categoria::Ropa).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None).unwrap();
+            sistema.crear_publicacion(0, 1, 2000).unwrap();
+            sistema.crear_publicacion(0, 1, 4).unwrap();
 
-            assert_eq!(productos_mas_vendidos.len(), 2); //Debe devolver 2 productos.
-            assert_eq!(productos_mas_vendidos[0].0, 1); //El producto más vendido es la Remera (ID 1).
-            assert_eq!(productos_mas_vendidos[0].1, 4); //Se vendieron 4 Remeras.
-            
-            assert_eq!(productos_mas_vendidos[1].0, 2); //El segundo producto más vendido es el Pantalon (ID 2). 
-            assert_eq!(productos_mas_vendidos[1].1, 3); //Se vendieron 3 Pantalones.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Comprador).unwrap();
+
+            //Cada línea por separado entra en el stock, pero fusionadas no.
+            assert!(matches!(
+                sistema.generar_orden_compra_fusionando(vec![(1, 3), (1, 2)], 10000, Some(String::from("Calle 1 123"))).unwrap_err(),
+                ErrorSistema::StockInsuficienteDetalle { .. }
+            ));
+            //Cada línea está por debajo de MAX_CANTIDAD_POR_LINEA, pero la suma lo supera.
+            assert_eq!(
+                sistema.generar_orden_compra_fusionando(vec![(0, MAX_CANTIDAD_POR_LINEA), (0, 1)], 10000, Some(String::from("Calle 1 123"))).unwrap_err(),
+                ErrorSistema::CantidadExcedeLimite
+            );
+            assert_eq!(sistema.publicaciones[0].stock, 2000);
+            assert_eq!(sistema.publicaciones[1].stock, 4);
         }
 
 
+
+        //-------------------------------------------------------------------------------------
+        //TESTS CONTAR VENTAS POR ESTADO
+
         #[ink::test]
-        fn test_productos_mas_vendidos_vacio() {
-            // Alice como owner de los contratos
+        //Test que verifica el conteo de órdenes del vendedor en cada estado y que otros usuarios no las vean.
+        fn test_contar_ventas_por_estado() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
             let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None).unwrap();
+            sistema.crear_publicacion(0, 100, 10).unwrap();
+            assert_eq!(sistema.contar_ventas_por_estado(), (0, 0, 0, 0));
+
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Comprador).unwrap();
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1000);
+            for _ in 0..5 {
+                sistema.generar_orden_compra(vec![(0, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+            }
 
-            let mut sistema = Sistema::new();
+            //Orden 0 y 4 pendientes, 1 enviada, 2 recibida y 3 cancelada por expiración.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.marcar_orden_como_enviada(1).unwrap();
+            sistema.marcar_orden_como_enviada(2).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.marcar_orden_como_recibida(2).unwrap();
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1001 + PLAZO_EXPIRACION_ORDEN);
+            sistema.expirar_orden(3).unwrap();
 
-            // Pongo a la misma Alice simulando ser ReportesView para que pueda
-            // enviar los mensajes relativos a este
-            sistema.set_reportes_view(alice);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            assert_eq!(sistema.contar_ventas_por_estado(), (2, 1, 1, 1));
 
-            //Preparo a los vendedores.
-            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
-            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Vendedor);
+            //La compradora no vendió nada.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            assert_eq!(sistema.contar_ventas_por_estado(), (0, 0, 0, 0));
+        }
 
-            //Creo productos y publicaciones.
-            sistema.nuevo_producto("Cif".to_string(), "Cif".to_string(), Categoria::Limpieza); //ID 0
-            sistema.nuevo_producto("Remera".to_string(), "Remera".to_string(), Categoria::Ropa); //ID 1
-            sistema.nuevo_producto("Pantalon".to_string(), "Pantalon".to_string(), Categoria::Ropa); //ID 2
-            sistema.nuevo_producto("Arroz".to_string(), "Arroz".to_string(), Categoria::Otros); //ID 3
 
-            sistema.crear_publicacion(0, 100, 10); //Cif
-            sistema.crear_publicacion(1, 500, 20); //Remera
-            sistema.crear_publicacion(2, 200, 25); //Pantalon
-            sistema.crear_publicacion(3, 400, 30); //Arroz
 
-            //Creo órdenes de compra para que haya ventas.
+        //-------------------------------------------------------------------------------------
+        //TESTS CATALOGO
+
+        #[ink::test]
+        //Test que verifica que el catálogo una cada publicación activa con su producto, omita las inactivas o sin producto y se pagine.
+        fn test_get_catalogo() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
             let django = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().django;
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None).unwrap();
+            sistema.nuevo_producto("Remera".to_string(), "Negra".to_string(), Categoria::Ropa, None).unwrap();
+            sistema.crear_publicacion(0, 100, 5).unwrap(); //Publicación 0
+            sistema.crear_publicacion(1, 200, 5).unwrap(); //Publicación 1
+
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(django);
-            sistema.registrar_usuario(String::from("Django"), String::from("Surname"), String::from("django.email"), Rol::Ambos);
-            //Id publicación, cantidad.
-            sistema.generar_orden_compra(vec![(0, 2), (1, 1)], 10000); //Compra 2 Cif y 1 Remera 
-            sistema.generar_orden_compra(vec![(1, 3), (2, 1)], 10000); //Compra 2 Remera y 1 Pantalon 
-            sistema.generar_orden_compra(vec![(3, 3), (0, 1), (2, 2)], 10000); //Compra 3 Arroz, 1 Cif y 2 Pantalon 
+            sistema.registrar_usuario(String::from("Django"), String::from("Surname"), String::from("django.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Mate".to_string(), "De calabaza".to_string(), Categoria::Otros, None).unwrap();
+            sistema.nuevo_producto("Buzo".to_string(), "Gris".to_string(), Categoria::Ropa, None).unwrap();
+            sistema.crear_publicacion(2, 300, 5).unwrap(); //Publicación 2, su producto se borra.
+            sistema.crear_publicacion(3, 400, 5).unwrap(); //Publicación 3
+            sistema.crear_publicacion(3, 500, 5).unwrap(); //Publicación 4, queda inactiva.
+
+            sistema.productos.remove(2);
+            sistema.publicaciones[4].activa = false;
+
+            let ids = |pagina: Vec<(Publicacion, Producto)>| -> Vec<u128> {
+                pagina.iter().map(|(publicacion, _)| publicacion.id_publicacion).collect()
+            };
+            let catalogo = sistema.get_catalogo(0, 10);
+            assert_eq!(catalogo.iter().map(|(publicacion, _)| publicacion.id_publicacion).collect::<Vec<u128>>(), vec![0, 1, 3]);
+            //Cada publicación viene con los datos de su producto.
+            assert_eq!(catalogo[0].0.precio, 100);
+            assert_eq!(catalogo[0].1.nombre, "Termo");
+            assert_eq!(catalogo[2].0.id_publicador, django);
+            assert_eq!(catalogo[2].1.nombre, "Buzo");
+            assert_eq!(catalogo[2].1.categoria, Categoria::Ropa);
 
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice); // Alice es ReportesView
-            let productos_mas_vendidos = sistema.ver_productos_mas_vendidos(Categoria::Musica).unwrap();
-            assert_eq!(productos_mas_vendidos.len(), 0); //Debe devolver 2 productos.
+            //Paginación sobre las publicaciones incluidas.
+            assert_eq!(ids(sistema.get_catalogo(0, 2)), vec![0, 1]);
+            assert_eq!(ids(sistema.get_catalogo(2, 2)), vec![3]);
+            assert!(sistema.get_catalogo(3, 2).is_empty());
+            assert_eq!(sistema.get_catalogo(0, u32::MAX).len(), 3);
         }
 
+
+
         //-------------------------------------------------------------------------------------
-        //TESTS PARA ESTADISTICAS POR CATEGORIA
+        //TESTS HISTORIAL DE PRECIOS
 
         #[ink::test]
-        fn test_estadisticas_por_categorias() {
-            // Alice como owner de los contratos
-            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
-
+        //Test que verifica que cada cambio de precio guarde el precio anterior con su timestamp y que el historial esté acotado.
+        fn test_historial_precios() {
             let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None).unwrap();
+            sistema.crear_publicacion(0, 100, 4).unwrap();
+            assert!(sistema.get_historial_precios(0).unwrap().is_empty());
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1000);
+            sistema.modificar_publicacion(0, Some(150), None, None).unwrap();
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(2000);
+            sistema.modificar_publicacion(0, Some(120), None, None).unwrap();
+            //Modificar sin cambiar el precio no agrega entradas.
+            sistema.modificar_publicacion(0, Some(120), Some(10), None).unwrap();
+            sistema.modificar_publicacion(0, None, Some(5), None).unwrap();
+
+            assert_eq!(sistema.get_historial_precios(0).unwrap(), vec![(100, 1000), (150, 2000)]);
+            assert_eq!(sistema.publicaciones[0].precio, 120);
+            assert_eq!(sistema.get_historial_precios(7).unwrap_err(), ErrorSistema::PublicacionNoValida);
+
+            //Pasado el máximo se descartan los cambios más viejos.
+            for i in 0..MAX_HISTORIAL_PRECIOS as u32 {
+                sistema.modificar_publicacion(0, Some(200 + i), None, None).unwrap();
+            }
+            let historial = sistema.get_historial_precios(0).unwrap();
+            assert_eq!(historial.len(), MAX_HISTORIAL_PRECIOS);
+            assert_eq!(historial[0], (120, 2000));
+            assert_eq!(historial[MAX_HISTORIAL_PRECIOS - 1].0, 200 + MAX_HISTORIAL_PRECIOS as u32 - 2);
+        }
 
-            // Pongo a la misma Alice simulando ser ReportesView para que pueda
-            // enviar los mensajes relativos a este
-            sistema.set_reportes_view(alice);
 
-            sistema.productos.insert(0, &Producto{
-                nombre:"remera".to_string(),
-                descripcion:"negra".to_string(),
-                categoria:Categoria::Ropa,
-                puntuaciones:vec![1,2,3],
-                total_ventas:23,
-            });
 
-            sistema.productos.insert(1, &Producto{
-                nombre:"pantalon".to_string(),
-                descripcion:"azul".to_string(),
-                categoria:Categoria::Ropa,
-                puntuaciones:vec![4,4],
-                total_ventas:12,
-            });
+        //-------------------------------------------------------------------------------------
+        //TESTS ORDENES PENDIENTES DE CALIFICAR
 
-            sistema.productos.insert(2, &Producto{
-                nombre:"Auriculares".to_string(),
-                descripcion:"XYZ".to_string(),
-                categoria:Categoria::Tecnologia,
-                puntuaciones:vec![3,2],
-                total_ventas:100,
-            });
+        #[ink::test]
+        //Test que verifica que sólo se devuelvan las órdenes recibidas que el usuario todavía no calificó desde su lado.
+        fn test_ordenes_pendientes_de_calificar() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
 
-            sistema.proximo_id_producto = 3;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None).unwrap();
+            sistema.crear_publicacion(0, 100, 10).unwrap();
 
-            assert_eq!(sistema.estadisticas_por_categoria().unwrap(), vec![(Categoria::Tecnologia, 100, 2), (Categoria::Ropa, 35, 2)]);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Comprador).unwrap();
+            for _ in 0..4 {
+                sistema.generar_orden_compra(vec![(0, 1)], 1000, Some(String::from("Calle 1 123"))).unwrap();
+            }
+            //Las órdenes 0, 1 y 2 se reciben; la 3 queda pendiente.
+            for id in 0..3 {
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+                sistema.marcar_orden_como_enviada(id).unwrap();
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+                sistema.marcar_orden_como_recibida(id).unwrap();
+            }
+            assert_eq!(sistema.ordenes_pendientes_de_calificar(), vec![0, 1, 2]);
+
+            //La compradora califica la 0 y el vendedor la 1.
+            sistema.puntuar_usuario_por_orden(0, 5).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.puntuar_usuario_por_orden(1, 4).unwrap();
+
+            assert_eq!(sistema.ordenes_pendientes_de_calificar(), vec![0, 2]);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            assert_eq!(sistema.ordenes_pendientes_de_calificar(), vec![1, 2]);
+
+            //Un usuario no registrado no tiene órdenes.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(AccountId::from([0x70; 32]));
+            assert!(sistema.ordenes_pendientes_de_calificar().is_empty());
         }
 
+
+
         //-------------------------------------------------------------------------------------
-        //TESTS PARA TOP 5 COMPRADORES
+        //TESTS ORDENES PROPUESTAS POR EL VENDEDOR
 
         #[ink::test]
-        fn test_top_5_compradores() {
+        //Test que verifica que la propuesta no descuente stock y que al aceptarla la orden pase a Pendiente y descuente el stock.
+        fn test_orden_propuesta_aceptada() {
+            let mut sistema = Sistema::new();
             let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
             let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
-            let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;            
 
-            // Alice como owner de los contratos
-            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Comprador).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None).unwrap();
+            sistema.crear_publicacion(0, 100, 10).unwrap();
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1000);
+            let id_orden = sistema.crear_orden_para_comprador(alice, vec![(0, 3)]).unwrap();
+            let propuesta = sistema.ordenes.get(id_orden).unwrap();
+            assert_eq!(propuesta.estado, EstadoOrdenCompra::PropuestaPendiente);
+            assert_eq!(propuesta.id_comprador, alice);
+            assert_eq!(propuesta.id_vendedor, charlie);
+            assert_eq!(propuesta.monto, 300);
+            assert_eq!(sistema.publicaciones[0].stock, 10);
+            assert_eq!(sistema.escrow_actual(), 0);
+            assert_eq!(sistema.usuarios.get(alice).unwrap().ordenes, vec![id_orden]);
+            assert_eq!(sistema.usuarios.get(charlie).unwrap().ordenes, vec![id_orden]);
 
-            let mut sistema = Sistema::new();
-
-            // Pongo a la misma Alice simulando ser ReportesView para que pueda
-            // enviar los mensajes relativos a este
-            sistema.set_reportes_view(alice);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(2000);
+            //La categoría exige dirección, así que el comprador la carga antes de aceptar.
+            assert_eq!(sistema.aceptar_orden(id_orden).unwrap_err(), ErrorSistema::DireccionRequerida);
+            sistema.actualizar_direccion(id_orden, String::from("Calle 1 123")).unwrap();
+            sistema.aceptar_orden(id_orden).unwrap();
+            let orden = sistema.ordenes.get(id_orden).unwrap();
+            assert_eq!(orden.estado, EstadoOrdenCompra::Pendiente);
+            assert_eq!(orden.expira_en, 2000 + PLAZO_EXPIRACION_ORDEN);
+            assert_eq!(sistema.publicaciones[0].stock, 7);
+            assert_eq!(sistema.escrow_actual(), 300);
+
+            //Ya aceptada, no se puede aceptar ni rechazar de nuevo.
+            assert_eq!(sistema.aceptar_orden(id_orden).unwrap_err(), ErrorSistema::OperacionNoValida);
+            assert_eq!(sistema.rechazar_orden(id_orden).unwrap_err(), ErrorSistema::OperacionNoValida);
+        }
 
-            sistema.usuarios.insert(charlie, &Usuario{
-                nombre:"charlie".to_string(),
-                apellido:"char".to_string(),
-                email:"ch@".to_string(),
-                id:charlie,
-                rol:Rol::Comprador,
-                publicaciones: Vec::new(),
-                ordenes: Vec::new(),
-                productos: Vec::new(),
-                calificaciones_comprador: vec![1,2,3],
-                calificaciones_vendedor: Vec::new(),
-            });
+        #[ink::test]
+        //Test que verifica que el comprador pueda rechazar una propuesta sin que cambie el stock.
+        fn test_orden_propuesta_rechazada() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
 
-            sistema.usuarios.insert(alice, &Usuario{
-                nombre:"alice".to_string(),
-                apellido:"alice".to_string(),
-                email:"alice@".to_string(),
-                id:alice,
-                rol:Rol::Ambos,
-                publicaciones: Vec::new(),
-                ordenes: Vec::new(),
-                productos: Vec::new(),
-                calificaciones_comprador: vec![5,4,3],
-                calificaciones_vendedor: Vec::new(),
-            });
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Comprador).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None).unwrap();
+            sistema.crear_publicacion(0, 100, 10).unwrap();
+            let id_orden = sistema.crear_orden_para_comprador(alice, vec![(0, 3)]).unwrap();
 
-            sistema.usuarios.insert(bob, &Usuario{
-                nombre:"bob".to_string(),
-                apellido:"bob".to_string(),
-                email:"bob@".to_string(),
-                id:bob,
-                rol:Rol::Ambos,
-                publicaciones: Vec::new(),
-                ordenes: Vec::new(),
-                productos: Vec::new(),
-                calificaciones_comprador: vec![5,5],
-                calificaciones_vendedor: Vec::new(),
-            });
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.rechazar_orden(id_orden).unwrap();
+            assert_eq!(sistema.ordenes.get(id_orden).unwrap().estado, EstadoOrdenCompra::Cancelado);
+            assert_eq!(sistema.publicaciones[0].stock, 10);
+            assert_eq!(sistema.aceptar_orden(id_orden).unwrap_err(), ErrorSistema::OperacionNoValida);
+        }
 
-            sistema.id_usuarios.push(charlie);
-            sistema.id_usuarios.push(alice);
-            sistema.id_usuarios.push(bob);
+        #[ink::test]
+        //Test que verifica que el vendedor no pueda cambiar el precio ni ceder la publicación de una propuesta abierta.
+        fn test_orden_propuesta_no_se_modifica() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let django = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().django;
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
 
-            let top_5 = sistema.consultar_top_5_compradores().unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Comprador).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(django);
+            sistema.registrar_usuario(String::from("Django"), String::from("Surname"), String::from("django.email"), Rol::Vendedor).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None).unwrap();
+            sistema.crear_publicacion(0, 100, 10).unwrap();
+            let id_orden = sistema.crear_orden_para_comprador(alice, vec![(0, 3)]).unwrap();
 
-            assert_eq!(top_5[0].id, bob);
+            //Mientras la propuesta está abierta la publicación no se puede ceder ni eliminar.
+            assert_eq!(sistema.transferir_publicacion(0, django).unwrap_err(), ErrorSistema::OperacionNoValida);
+            assert_eq!(sistema.eliminar_publicacion(0).unwrap_err(), ErrorSistema::OperacionNoValida);
 
-            assert_eq!(top_5[1].id, alice);
+            //Si el vendedor sube el precio, el comprador no termina pagando el monto nuevo.
+            sistema.modificar_publicacion(0, Some(150), None, None).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.actualizar_direccion(id_orden, String::from("Calle 1 123")).unwrap();
+            assert_eq!(sistema.aceptar_orden(id_orden).unwrap_err(), ErrorSistema::PropuestaDesactualizada);
+            assert_eq!(sistema.publicaciones[0].stock, 10);
 
-            assert_eq!(top_5[2].id, charlie);
-            
+            //Con el precio original la propuesta se acepta por el monto acordado.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.modificar_publicacion(0, Some(100), None, None).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.aceptar_orden(id_orden).unwrap();
+            assert_eq!(sistema.ordenes.get(id_orden).unwrap().monto, 300);
         }
 
-        //-------------------------------------------------------------------------------------
-        //TESTS PARA CANTIDAD DE ORDENES DE COMPRA
-
         #[ink::test]
-        fn test_cantidad_ordenes_cero() {
+        //Test que verifica que un vendedor sólo pueda proponer órdenes con sus propias publicaciones.
+        fn test_orden_propuesta_publicacion_ajena() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let django = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().django;
             let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Comprador).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(django);
+            sistema.registrar_usuario(String::from("Django"), String::from("Surname"), String::from("django.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Mate".to_string(), "De calabaza".to_string(), Categoria::Otros, None).unwrap();
+            sistema.crear_publicacion(0, 50, 10).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None).unwrap();
+            sistema.crear_publicacion(1, 100, 10).unwrap();
+
+            assert_eq!(sistema.crear_orden_para_comprador(alice, vec![(0, 1)]).unwrap_err(), ErrorSistema::UsuarioNoAutorizado);
+            assert_eq!(sistema.crear_orden_para_comprador(alice, vec![(1, 1), (0, 1)]).unwrap_err(), ErrorSistema::VendedorDistinto);
+            //Un comprador no puede proponer órdenes.
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            assert_eq!(sistema.crear_orden_para_comprador(alice, vec![(1, 1)]).unwrap_err(), ErrorSistema::UsuarioNoEsVendedor);
+            assert!(sistema.id_ordenes.is_empty());
+        }
 
+
+        #[ink::test]
+        //Test que verifica que una propuesta sin aceptar no se pueda enviar, recibir ni cancelar, y que no reserve stock.
+        fn test_orden_propuesta_sin_aceptar() {
             let mut sistema = Sistema::new();
-            sistema.set_reportes_view(alice);
-            
-            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Ambos);
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
 
-            let cantidades_ordenes = sistema.cantidad_ordenes_por_usuario().unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Comprador).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None).unwrap();
+            sistema.crear_publicacion(0, 100, 10).unwrap();
+            let id_orden = sistema.crear_orden_para_comprador(alice, vec![(0, 3)]).unwrap();
 
-            let ordenes_alice = cantidades_ordenes.clone()
-            .into_iter()
-            .find(|(id, _)| *id == alice)
-            .map(|(_, ordenes)| ordenes)
-            .unwrap();
+            //El vendedor no puede enviarla ni cancelarla antes de que el comprador la acepte.
+            assert_eq!(sistema.marcar_orden_como_enviada(id_orden).unwrap_err(), ErrorSistema::CompradorNoAceptoOrden);
+            assert_eq!(sistema.marcar_item_enviado(id_orden, 0).unwrap_err(), ErrorSistema::CompradorNoAceptoOrden);
+            assert_eq!(sistema.cancelar_orden(id_orden).unwrap_err(), ErrorSistema::CompradorNoAceptoOrden);
 
-            assert_eq!(cantidades_ordenes.len(), 1); // Hay solo un usuario registrado, habrá una única tupla en el vector
-            assert_eq!(ordenes_alice, 0); // Alice tiene 0 ordenes
+            //El comprador tampoco puede recibirla ni cancelarla: tiene que aceptarla o rechazarla.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            assert_eq!(sistema.marcar_orden_como_recibida(id_orden).unwrap_err(), ErrorSistema::CompradorNoAceptoOrden);
+            assert_eq!(sistema.cancelar_orden(id_orden).unwrap_err(), ErrorSistema::CompradorNoAceptoOrden);
+            assert_eq!(sistema.cancelar_como_comprador(id_orden).unwrap_err(), ErrorSistema::CompradorNoAceptoOrden);
+
+            let orden = sistema.ordenes.get(id_orden).unwrap();
+            assert_eq!(orden.estado, EstadoOrdenCompra::PropuestaPendiente);
+            assert!(orden.solicitud_cancelacion.is_none());
+            assert_eq!(sistema.publicaciones[0].stock, 10);
+
+            //Una vez aceptada, el envío funciona.
+            sistema.actualizar_direccion(id_orden, String::from("Calle 1 123")).unwrap();
+            sistema.aceptar_orden(id_orden).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            assert!(sistema.marcar_orden_como_enviada(id_orden).is_ok());
         }
 
         #[ink::test]
-        fn test_cantidad_ordenes_una() {
+        //Test que verifica que sólo el comprador al que va dirigida la propuesta pueda aceptarla o rechazarla.
+        fn test_orden_propuesta_otro_comprador() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
             let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
             let bob = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob;
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
 
-            let mut sistema = Sistema::new();
-            sistema.set_reportes_view(alice);
-            
-            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Ambos);
-            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros);
-            sistema.crear_publicacion(0, 1000, 4);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Comprador).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
+            sistema.registrar_usuario(String::from("Bob"), String::from("Surname"), String::from("bob.email"), Rol::Comprador).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None).unwrap();
+            sistema.crear_publicacion(0, 100, 10).unwrap();
+            let id_orden = sistema.crear_orden_para_comprador(alice, vec![(0, 3)]).unwrap();
 
+            //Ni otro comprador ni el propio vendedor pueden aceptarla.
+            assert_eq!(sistema.aceptar_orden(id_orden).unwrap_err(), ErrorSistema::UsuarioNoAutorizado);
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(bob);
-            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Comprador);
-            sistema.generar_orden_compra(vec![(0, 1)],4000);
+            assert_eq!(sistema.aceptar_orden(id_orden).unwrap_err(), ErrorSistema::UsuarioNoAutorizado);
+            assert_eq!(sistema.rechazar_orden(id_orden).unwrap_err(), ErrorSistema::UsuarioNoAutorizado);
+            assert_eq!(sistema.aceptar_orden(99).unwrap_err(), ErrorSistema::IdDeOrdenNoValida);
 
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
-            let cantidades_ordenes = sistema.cantidad_ordenes_por_usuario().unwrap();
+            assert_eq!(sistema.ordenes.get(id_orden).unwrap().estado, EstadoOrdenCompra::PropuestaPendiente);
+            assert_eq!(sistema.publicaciones[0].stock, 10);
+        }
 
-            let ordenes_alice = cantidades_ordenes.clone()
-            .into_iter()
-            .find(|(id, _)| *id == alice)
-            .map(|(_, ordenes)| ordenes)
-            .unwrap();
 
-            let ordenes_bob = cantidades_ordenes.clone()
-            .into_iter()
-            .find(|(id, _)| *id == bob)
-            .map(|(_, ordenes)| ordenes)
-            .unwrap();
 
-            assert_eq!(cantidades_ordenes.len(), 2); // Hay dos usuarios registrados, habrá dos tuplas en el vector
-            assert_eq!(ordenes_alice, 1); // Alice tiene 1 ordene, como vendedor
-            assert_eq!(ordenes_bob, 1); // Bob tiene 1 orden, como comprador
+        //-------------------------------------------------------------------------------------
+        //TESTS PUBLICACIONES CON STOCK
+
+        #[ink::test]
+        //Test que verifica que una publicación activa sin stock no aparezca y que vuelva a aparecer al reponerla.
+        fn test_get_publicaciones_con_stock() {
+            let mut sistema = Sistema::new();
+            let charlie = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().charlie;
+            let alice = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice;
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.registrar_usuario(String::from("Charlie"), String::from("Surname"), String::from("charlie.email"), Rol::Vendedor).unwrap();
+            sistema.nuevo_producto("Termo".to_string(), "Termo de metal".to_string(), Categoria::Otros, None).unwrap();
+            sistema.crear_publicacion(0, 100, 2).unwrap();
+            sistema.crear_publicacion(0, 100, 5).unwrap();
+            sistema.crear_publicacion(0, 100, 5).unwrap();
+            sistema.publicaciones[2].activa = false;
+
+            //La compradora reserva todo el stock de la publicación 0, que queda activa con stock 0.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
+            sistema.registrar_usuario(String::from("Alice"), String::from("Surname"), String::from("alice.email"), Rol::Comprador).unwrap();
+            sistema.reservar_stock(0, 2).unwrap();
+            assert!(sistema.publicaciones[0].activa);
+            assert_eq!(sistema.publicaciones[0].stock, 0);
+
+            let ids: Vec<u128> = sistema.get_publicaciones_con_stock().iter().map(|p| p.id_publicacion).collect();
+            assert_eq!(ids, vec![1]);
+
+            //Al reponer stock vuelve a aparecer.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(charlie);
+            sistema.reabastecer(0, 3).unwrap();
+            let ids: Vec<u128> = sistema.get_publicaciones_con_stock().iter().map(|p| p.id_publicacion).collect();
+            assert_eq!(ids, vec![0, 1]);
         }
 
     }